@@ -0,0 +1,18 @@
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+
+    // Builds from a release tarball have no git checkout; the version
+    // output then just leaves the commit out.
+    let hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok());
+    if let Some(hash) = hash {
+        println!("cargo:rustc-env=XWLM_GIT_HASH={}", hash.trim());
+    }
+}
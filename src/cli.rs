@@ -0,0 +1,298 @@
+use std::{
+    collections::HashMap,
+    fs,
+    sync::mpsc::{Receiver, SyncSender},
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+use wlx_monitors::{WlMonitor, WlMonitorAction, WlMonitorEvent};
+
+use crate::{
+    compositor::{
+        self, Compositor,
+        format::{SaveContext, monitor_config_content},
+        workspace_config::parse_workspace_config,
+    },
+    state::App,
+    utils::{self, WriteAccessError},
+    xwlm_config::{self, ConfigError},
+};
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("unknown argument: {0}\n{USAGE}")]
+    Usage(String),
+
+    #[error("toggle needs a monitor name\n{USAGE}")]
+    MissingMonitor,
+
+    #[error("no xwlm config found, run xwlm once to set it up: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("no supported compositor detected")]
+    UnknownCompositor,
+
+    #[error("the compositor reported no monitors")]
+    NoMonitors,
+
+    #[error(transparent)]
+    Write(#[from] WriteAccessError),
+
+    #[error("{0}")]
+    Toggle(String),
+
+    #[error("lost the connection to the compositor")]
+    Disconnected,
+
+    #[error("the compositor did not confirm the change to {0}")]
+    NoConfirmation(String),
+}
+
+const USAGE: &str =
+    "usage: xwlm [--version | save [--dry-run] | toggle <name> [--dry-run]]";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Tui,
+    Version,
+    /// Write the live monitor state to the configured monitor config.
+    Save {
+        dry_run: bool,
+    },
+    /// Turn a monitor on or off, then save and reload.
+    Toggle {
+        name: String,
+        dry_run: bool,
+    },
+}
+
+impl Command {
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<Self, CliError> {
+        let mut args = args.into_iter();
+        let Some(command) = args.next() else {
+            return Ok(Command::Tui);
+        };
+        match command.as_str() {
+            "--version" | "-V" => return Ok(Command::Version),
+            "save" | "toggle" => {}
+            _ => return Err(CliError::Usage(command)),
+        }
+
+        let mut dry_run = false;
+        let mut name = None;
+        for arg in args {
+            match arg.as_str() {
+                "--dry-run" | "-n" => dry_run = true,
+                _ if command == "toggle" && name.is_none() => name = Some(arg),
+                _ => return Err(CliError::Usage(arg)),
+            }
+        }
+        if command == "save" {
+            return Ok(Command::Save { dry_run });
+        }
+        let name = name.ok_or(CliError::MissingMonitor)?;
+        Ok(Command::Toggle { name, dry_run })
+    }
+}
+
+/// Version, commit and compositor, for `--version` and the version modal.
+pub fn version_lines(comp: Compositor) -> Vec<String> {
+    let mut lines = vec![format!("xwlm {}", env!("CARGO_PKG_VERSION"))];
+    if let Some(hash) = option_env!("XWLM_GIT_HASH") {
+        lines.push(format!("commit {}", hash));
+    }
+    lines.push(match compositor::version(comp) {
+        Some(version) => {
+            format!("compositor {} {}", comp.label(), version)
+        }
+        None => format!("compositor {}", comp.label()),
+    });
+    lines
+}
+
+/// Waits briefly for the compositor's first monitor report.
+pub fn initial_monitors(
+    wlx_events: &Receiver<WlMonitorEvent>,
+) -> Vec<WlMonitor> {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        match wlx_events.recv_timeout(left) {
+            Ok(WlMonitorEvent::InitialState(monitors)) => return monitors,
+            Ok(_) => continue,
+            Err(_) => break,
+        }
+    }
+    Vec::new()
+}
+
+/// Snapshots the live monitors into the configured monitor config, keeping
+/// its workspace rules. With `dry_run` the result is printed instead.
+pub fn save(
+    wlx_events: &Receiver<WlMonitorEvent>,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let config = xwlm_config::load_config()?;
+    let comp = compositor::detect();
+    if comp == Compositor::Unknown {
+        return Err(CliError::UnknownCompositor);
+    }
+    let monitors = initial_monitors(wlx_events);
+    if monitors.is_empty() {
+        return Err(CliError::NoMonitors);
+    }
+
+    let path = &config.monitor_config_path;
+    let workspaces = parse_workspace_config(comp, path);
+    let descriptions = config
+        .match_by_description
+        .then(|| compositor::monitor_descriptions(comp));
+    // The live layout is what's being captured, so `auto` placements are
+    // replaced with the positions they ended up at
+    let startup_positions = HashMap::new();
+    let context = SaveContext {
+        descriptions: descriptions.as_ref(),
+        sway_tuning: &compositor::sway_tuning(comp, path),
+        header: config.header(),
+        startup_positions: &startup_positions,
+    };
+    let Some(content) =
+        monitor_config_content(comp, path, &monitors, &workspaces, &context)
+    else {
+        return Err(CliError::UnknownCompositor);
+    };
+
+    if dry_run {
+        print!("{}", content);
+        return Ok(());
+    }
+    fs::write(path, content).map_err(|e| WriteAccessError::new(path, e))?;
+    println!("Saved {} monitor(s) to {}", monitors.len(), path.display());
+    Ok(())
+}
+
+/// Turns the monitor called `name` on or off the way `t` does in the TUI,
+/// placing an enabled monitor where the TUI would, then writes the monitor
+/// config and reloads. With `dry_run` it only says what it would do.
+pub fn toggle(
+    wlx_events: &Receiver<WlMonitorEvent>,
+    wlx_action_handler: SyncSender<WlMonitorAction>,
+    name: &str,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let config = xwlm_config::load_config()?;
+    if compositor::detect() == Compositor::Unknown {
+        return Err(CliError::UnknownCompositor);
+    }
+    let monitors = initial_monitors(wlx_events);
+    if monitors.is_empty() {
+        return Err(CliError::NoMonitors);
+    }
+
+    let mut app = App::new(wlx_action_handler, &config);
+    app.set_monitors(monitors);
+    let idx = app.monitor_named(name).map_err(CliError::Toggle)?;
+    let position = app.plan_toggle(idx).map_err(CliError::Toggle)?;
+    let name = app.monitors[idx].name.clone();
+    let summary = match position {
+        Some((x, y)) => format!("enable {} at {},{}", name, x, y),
+        None => format!("disable {}", name),
+    };
+    if dry_run {
+        println!("Would {}", summary);
+        return Ok(());
+    }
+
+    app.perform_toggle(&name, position.is_none())
+        .map_err(|_| CliError::Disconnected)?;
+    await_toggle(wlx_events, &mut app, &name, position.is_some())?;
+    app.save_now();
+    if let Some(e) = app.error_message.take() {
+        return Err(CliError::Toggle(e));
+    }
+    println!("Did {}", summary);
+    Ok(())
+}
+
+/// Feeds monitor updates to `app` until `name` reports being `enabled`.
+fn await_toggle(
+    wlx_events: &Receiver<WlMonitorEvent>,
+    app: &mut App,
+    name: &str,
+    enabled: bool,
+) -> Result<(), CliError> {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        match wlx_events.recv_timeout(left) {
+            Ok(WlMonitorEvent::Changed(monitor)) => {
+                let done = monitor.name == name && monitor.enabled == enabled;
+                app.update_monitor(*monitor);
+                if done {
+                    return Ok(());
+                }
+            }
+            Ok(WlMonitorEvent::ActionFailed { action, reason }) => {
+                return Err(CliError::Toggle(format!(
+                    "Failed to {} {}: {}",
+                    utils::action_label(&action),
+                    name,
+                    reason
+                )));
+            }
+            Ok(_) => continue,
+            Err(_) => break,
+        }
+    }
+    Err(CliError::NoConfirmation(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(Command::parse(args(&[])).unwrap(), Command::Tui);
+        assert_eq!(
+            Command::parse(args(&["--version"])).unwrap(),
+            Command::Version
+        );
+        assert_eq!(
+            Command::parse(args(&["save"])).unwrap(),
+            Command::Save { dry_run: false }
+        );
+        assert_eq!(
+            Command::parse(args(&["save", "--dry-run"])).unwrap(),
+            Command::Save { dry_run: true }
+        );
+        assert!(matches!(
+            Command::parse(args(&["apply"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            Command::parse(args(&["save", "--force"])),
+            Err(CliError::Usage(_))
+        ));
+        assert_eq!(
+            Command::parse(args(&["toggle", "HDMI-A-1", "--dry-run"])).unwrap(),
+            Command::Toggle {
+                name: "HDMI-A-1".to_string(),
+                dry_run: true
+            }
+        );
+        assert!(matches!(
+            Command::parse(args(&["toggle"])),
+            Err(CliError::MissingMonitor)
+        ));
+        assert!(matches!(
+            Command::parse(args(&["toggle", "DP-1", "DP-2"])),
+            Err(CliError::Usage(_))
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    io,
+    process::{Command, Stdio},
+};
+
+use crate::{
+    compositor::{Compositor, ipc_command, run_checked},
+    constants::NEUTRAL_COLOR_TEMP,
+};
+
+/// Whether a temperature set here tints every output rather than only the
+/// one it was set for.
+pub fn is_global(compositor: Compositor) -> bool {
+    compositor == Compositor::Hyprland
+}
+
+/// Tints `monitor` to `kelvin`. Hyprland goes through hyprsunset, which
+/// tints every output; elsewhere a `wlsunset` instance per output holds the
+/// gamma, since it resets as soon as the client exits.
+pub fn apply(
+    compositor: Compositor,
+    monitor: &str,
+    kelvin: u32,
+) -> io::Result<()> {
+    match compositor {
+        Compositor::Hyprland => {
+            let mut command = ipc_command("hyprctl");
+            command.arg("hyprsunset");
+            if kelvin >= NEUTRAL_COLOR_TEMP {
+                command.arg("identity");
+            } else {
+                command.args(["temperature", &kelvin.to_string()]);
+            }
+            run_checked(&mut command).map_err(|e| {
+                io::Error::other(format!("{} (is hyprsunset running?)", e))
+            })
+        }
+        _ => {
+            // Replace the instance started for this output earlier
+            let _ = Command::new("pkill")
+                .args(["-f", &wlsunset_pattern(monitor)])
+                .status();
+            if kelvin >= NEUTRAL_COLOR_TEMP {
+                return Ok(());
+            }
+            Command::new("wlsunset")
+                .args(wlsunset_args(monitor, kelvin))
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map(drop)
+                .map_err(|e| io::Error::other(format!("wlsunset: {}", e)))
+        }
+    }
+}
+
+/// wlsunset has no fixed-temperature mode, so day and night are pinned one
+/// degree apart.
+fn wlsunset_args(monitor: &str, kelvin: u32) -> Vec<String> {
+    vec![
+        "-o".to_string(),
+        monitor.to_string(),
+        "-t".to_string(),
+        kelvin.to_string(),
+        "-T".to_string(),
+        (kelvin + 1).to_string(),
+    ]
+}
+
+/// A `pkill -f` regex for the wlsunset started for `monitor`, with the
+/// name escaped so it only ever matches that output.
+fn wlsunset_pattern(monitor: &str) -> String {
+    let mut escaped = String::new();
+    for c in monitor.chars() {
+        if "\\.^$|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    format!("^wlsunset -o {} -t ", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wlsunset_command_matches_kill_pattern() {
+        let args = wlsunset_args("DP-1", 4500);
+        assert_eq!(args, ["-o", "DP-1", "-t", "4500", "-T", "4501"]);
+
+        let command_line = format!("wlsunset {}", args.join(" "));
+        let prefix = wlsunset_pattern("DP-1");
+        assert!(command_line.starts_with(prefix.trim_start_matches('^')));
+    }
+
+    #[test]
+    fn test_kill_pattern_escapes_the_monitor_name() {
+        assert_eq!(
+            wlsunset_pattern("HDMI.A+1"),
+            "^wlsunset -o HDMI\\.A\\+1 -t "
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::{fmt, io, process::Command};
+
+use wlx_monitors::WlMonitor;
+
+use crate::{
+    compositor::{Compositor, ipc_command, run_checked},
+    constants::{MAX_CUSTOM_DIMENSION, MAX_CUSTOM_REFRESH},
+};
+
+/// A mode typed by the user, which the monitor may not advertise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomMode {
+    pub width: i32,
+    pub height: i32,
+    pub refresh_rate: i32,
+}
+
+impl fmt::Display for CustomMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}@{}", self.width, self.height, self.refresh_rate)
+    }
+}
+
+impl CustomMode {
+    /// Parses `WIDTHxHEIGHT@REFRESH`, e.g. `2560x1440@100`. A trailing `Hz`
+    /// is accepted.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        let usage =
+            || format!("'{input}' isn't a mode, expected WIDTHxHEIGHT@REFRESH");
+        let (size, refresh) = input.split_once('@').ok_or_else(usage)?;
+        let (width, height) = size.split_once(['x', 'X']).ok_or_else(usage)?;
+        let refresh = refresh.trim_end_matches("Hz").trim_end_matches("hz");
+
+        let number = |s: &str| s.trim().parse::<i32>().map_err(|_| usage());
+        let mode = CustomMode {
+            width: number(width)?,
+            height: number(height)?,
+            refresh_rate: number(refresh)?,
+        };
+
+        if !(1..=MAX_CUSTOM_DIMENSION).contains(&mode.width)
+            || !(1..=MAX_CUSTOM_DIMENSION).contains(&mode.height)
+        {
+            return Err(format!(
+                "Width and height must be between 1 and {MAX_CUSTOM_DIMENSION}"
+            ));
+        }
+        if !(1..=MAX_CUSTOM_REFRESH).contains(&mode.refresh_rate) {
+            return Err(format!(
+                "Refresh rate must be between 1 and {MAX_CUSTOM_REFRESH} Hz"
+            ));
+        }
+        Ok(mode)
+    }
+}
+
+/// Switches `monitor` to `mode` through the compositor's own tool, since
+/// the output management protocol only picks from advertised modes.
+pub fn apply(
+    compositor: Compositor,
+    monitor: &WlMonitor,
+    mode: CustomMode,
+) -> io::Result<()> {
+    let mut command = match compositor {
+        Compositor::Hyprland => {
+            let mut c = ipc_command("hyprctl");
+            c.args([
+                "keyword",
+                "monitor",
+                &format!(
+                    "{},{},{}x{},{}",
+                    monitor.name,
+                    mode,
+                    monitor.position.x,
+                    monitor.position.y,
+                    monitor.scale
+                ),
+            ]);
+            c
+        }
+        Compositor::Sway => {
+            let mut c = ipc_command("swaymsg");
+            c.args([
+                "output",
+                &monitor.name,
+                "mode",
+                "--custom",
+                &format!("{}Hz", mode),
+            ]);
+            c
+        }
+        Compositor::River => {
+            let mut c = Command::new("wlr-randr");
+            c.args([
+                "--output",
+                &monitor.name,
+                "--custom-mode",
+                &format!("{}Hz", mode),
+            ]);
+            c
+        }
+        Compositor::Unknown => {
+            return Err(io::Error::other(
+                "custom modes need a known compositor",
+            ));
+        }
+    };
+
+    run_checked(&mut command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_custom_mode() {
+        let mode = CustomMode::parse(" 2560x1440@100 ").unwrap();
+        assert_eq!(
+            mode,
+            CustomMode {
+                width: 2560,
+                height: 1440,
+                refresh_rate: 100
+            }
+        );
+        assert_eq!(
+            CustomMode::parse("1920X1080@60Hz").unwrap().refresh_rate,
+            60
+        );
+        assert_eq!(mode.to_string(), "2560x1440@100");
+    }
+
+    #[test]
+    fn test_parse_custom_mode_rejects_bad_input() {
+        assert!(CustomMode::parse("2560x1440").is_err());
+        assert!(CustomMode::parse("wide x tall@60").is_err());
+        assert!(CustomMode::parse("0x1440@60").is_err());
+        assert!(CustomMode::parse("2560x1440@-5").is_err());
+        assert!(CustomMode::parse("99999x1440@60").is_err());
+        assert!(CustomMode::parse("2560x1440@5000").is_err());
+    }
+}
@@ -1,13 +1,24 @@
 use std::{env, path::PathBuf};
 
-use crate::compositor::{Compositor, hyprland, sway};
+use crate::{
+    compositor::{Compositor, hyprland, sway},
+    xwlm_config::with_header,
+};
+
+/// How many levels of `source`/`include` are followed before extraction
+/// gives up, so a pathological config can't recurse forever.
+pub const MAX_INCLUDE_DEPTH: usize = 16;
 
 #[derive(Debug)]
 pub struct ExtractionPlan {
     pub output_content: String,
-    pub modified_files: Vec<(PathBuf, String)>,
+    /// Each file that lost lines, its new content, and how many
+    /// `monitor =`/`output` entries were taken from it.
+    pub modified_files: Vec<(PathBuf, String, usize)>,
     pub source_line: Option<String>,
     pub main_config: PathBuf,
+    /// The file the monitor entries are consolidated into.
+    pub output_path: PathBuf,
     pub source_exists: bool,
 }
 
@@ -16,78 +27,74 @@ impl ExtractionPlan {
         !self.output_content.is_empty()
     }
 
-    pub fn apply(&self) -> Result<(), String> {
+    pub fn apply(&self, header: &str) -> Result<(), String> {
+        for (path, content) in self.planned_writes(header)? {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    format!("Failed to create {}: {e}", parent.display())
+                })?;
+            }
+            std::fs::write(&path, content).map_err(|e| {
+                format!("Failed to write {}: {e}", path.display())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Every file `apply` writes, with its full new content, in the order
+    /// they are written: the monitors file first, then the files that lose
+    /// lines, then the main config if it only gains the source line. The
+    /// monitors file starts with `header`.
+    pub fn planned_writes(
+        &self,
+        header: &str,
+    ) -> Result<Vec<(PathBuf, String)>, String> {
         if self.output_content.is_empty() {
             return Err("No monitor configuration found to extract".into());
         }
 
-        let output_dir = self
-            .main_config
-            .parent()
-            .ok_or("Cannot determine config directory")?;
-
-        let output_filename = self.extract_output_filename();
-        let output_path = output_dir.join(output_filename);
-
-        // Step 1: Write the monitors.conf file first
-        let comment = "# This file is managed by xwlm. Do not edit manually.\n\n";
-        let final_content = format!("{}{}", comment, self.output_content);
-        std::fs::write(&output_path, final_content)
-            .map_err(|e| format!("Failed to write {}: {e}", output_path.display()))?;
-
-        // Step 2: Write modified files, adding source line to main_config if needed
-        for (path, content) in &self.modified_files {
-            if path == &self.main_config {
-                // For main config, add source line to the modified content
-                let mut final_content = content.clone();
-                if !final_content.ends_with('\n') {
-                    final_content.push('\n');
-                }
-                if let Some(ref line) = self.source_line {
-                    final_content.push('\n');
-                    final_content.push_str(line);
-                    final_content.push('\n');
-                }
-                std::fs::write(path, final_content)
-                    .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
-            } else {
-                std::fs::write(path, content)
-                    .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        let output_path = self.output_path.clone();
+        let mut writes = vec![(
+            output_path.clone(),
+            with_header(header, &self.output_content),
+        )];
+
+        let output_canonical = output_path.canonicalize().ok();
+        for (path, content, _) in &self.modified_files {
+            // A config that already sources the output file had its lines
+            // extracted from it too; the output file replaces it
+            if output_canonical.is_some()
+                && path.canonicalize().ok() == output_canonical
+            {
+                continue;
             }
+            let mut final_content = content.clone();
+            if path == &self.main_config
+                && let Some(ref line) = self.source_line
+            {
+                append_directive(&mut final_content, line);
+            }
+            writes.push((path.clone(), final_content));
         }
 
-        // Step 3: If main_config wasn't in modified_files but we need to add source
         if !self
             .modified_files
             .iter()
-            .any(|(p, _)| p == &self.main_config)
+            .any(|(p, ..)| p == &self.main_config)
             && let Some(ref line) = self.source_line
         {
             let mut content = std::fs::read_to_string(&self.main_config)
-                .map_err(|e| format!("Failed to read {}: {e}", self.main_config.display()))?;
-            if !content.ends_with('\n') {
-                content.push('\n');
-            }
-            content.push('\n');
-            content.push_str(line);
-            content.push('\n');
-            std::fs::write(&self.main_config, content)
-                .map_err(|e| format!("Failed to write {}: {e}", self.main_config.display()))?;
+                .map_err(|e| {
+                    format!(
+                        "Failed to read {}: {e}",
+                        self.main_config.display()
+                    )
+                })?;
+            append_directive(&mut content, line);
+            writes.push((self.main_config.clone(), content));
         }
 
-        Ok(())
-    }
-
-    fn extract_output_filename(&self) -> &str {
-        if let Some(ref line) = self.source_line {
-            if let Some(path) = line.strip_prefix("source = ") {
-                return extract_filename(path);
-            }
-            if let Some(path) = line.strip_prefix("include ") {
-                return extract_filename(path);
-            }
-        }
-        "monitors.conf"
+        Ok(writes)
     }
 }
 
@@ -102,11 +109,55 @@ pub fn main_config_path(compositor: Compositor) -> Option<PathBuf> {
     if p.exists() { Some(p) } else { None }
 }
 
-fn extract_filename(path: &str) -> &str {
-    let path = path.trim();
-    path.rsplit('/').next().unwrap_or(path)
+/// The argument of a `source = ...` or `include ...` directive with any
+/// trailing comment and surrounding quotes removed.
+pub fn directive_argument(rest: &str) -> Option<String> {
+    let rest = match rest.find(" #").or_else(|| rest.find("\t#")) {
+        Some(i) => &rest[..i],
+        None => rest,
+    };
+    let arg = rest.trim();
+    let arg = arg
+        .strip_prefix('"')
+        .and_then(|a| a.strip_suffix('"'))
+        .or_else(|| arg.strip_prefix('\'').and_then(|a| a.strip_suffix('\'')))
+        .unwrap_or(arg)
+        .trim();
+    (!arg.is_empty()).then(|| arg.to_string())
 }
 
+/// Collapses a directive line to a comparable form: comment dropped, quotes
+/// removed and whitespace (including around `=`) normalized.
+fn normalize_directive(line: &str) -> Option<String> {
+    let line = line.trim();
+    let (keyword, rest) =
+        line.split_once(|c: char| c.is_whitespace() || c == '=')?;
+    let rest = rest.trim_start().strip_prefix('=').unwrap_or(rest);
+    Some(format!(
+        "{} {}",
+        keyword.to_ascii_lowercase(),
+        directive_argument(rest)?
+    ))
+}
+
+/// Appends `line` to a config unless an equivalent directive is already
+/// there, so running extraction twice never sources the file twice.
+fn append_directive(content: &mut String, line: &str) {
+    let wanted = normalize_directive(line);
+    if content.lines().any(|l| normalize_directive(l) == wanted) {
+        return;
+    }
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push('\n');
+    content.push_str(line);
+    content.push('\n');
+}
+
+/// Plans moving the monitor entries out of `config_path` into
+/// `output_filename`, which is relative to the config's directory unless it
+/// is absolute or starts with `~/`.
 pub fn extract_monitors(
     config_path: &std::path::Path,
     compositor: Compositor,
@@ -138,39 +189,44 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_extract_filename_with_tilde() {
-        assert_eq!(
-            extract_filename("~/.config/hypr/monitors.conf"),
-            "monitors.conf"
-        );
-    }
-
-    #[test]
-    fn test_extract_filename_with_absolute() {
-        assert_eq!(
-            extract_filename("/home/user/.config/hypr/monitors.conf"),
-            "monitors.conf"
-        );
+    fn test_directive_argument_formats() {
+        for rest in [
+            " ~/.config/hypr/monitors.conf",
+            "\t~/.config/hypr/monitors.conf  # xwlm",
+            " \"~/.config/hypr/monitors.conf\"",
+            " '~/.config/hypr/monitors.conf'\t# managed",
+        ] {
+            assert_eq!(
+                directive_argument(rest).as_deref(),
+                Some("~/.config/hypr/monitors.conf"),
+                "{rest:?}"
+            );
+        }
+        assert_eq!(directive_argument("  # nothing"), None);
     }
 
     #[test]
-    fn test_extract_filename_relative() {
-        assert_eq!(extract_filename("monitors.conf"), "monitors.conf");
-    }
+    fn test_append_directive_is_idempotent() {
+        let line = "source = ~/.config/hypr/monitors.conf";
+        for existing in [
+            "source = ~/.config/hypr/monitors.conf\n",
+            "source=~/.config/hypr/monitors.conf\n",
+            "\tsource\t=\t\"~/.config/hypr/monitors.conf\" # displays\n",
+            "SOURCE = ~/.config/hypr/monitors.conf\n",
+        ] {
+            let mut content = format!("general {{\n}}\n{existing}");
+            let before = content.clone();
+            append_directive(&mut content, line);
+            assert_eq!(content, before, "{existing:?}");
+        }
 
-    #[test]
-    fn test_extract_filename_with_spaces() {
-        assert_eq!(
-            extract_filename("  ~/.config/hypr/monitors.conf  "),
-            "monitors.conf"
-        );
-    }
+        let mut content = "general {\n}".to_string();
+        append_directive(&mut content, line);
+        append_directive(&mut content, line);
+        assert_eq!(content.matches("monitors.conf").count(), 1);
 
-    #[test]
-    fn test_extract_filename_nested() {
-        assert_eq!(
-            extract_filename("~/.config/hypr/subdir/monitors.conf"),
-            "monitors.conf"
-        );
+        let mut content = "include \"monitors\"\n".to_string();
+        append_directive(&mut content, "include monitors");
+        assert_eq!(content, "include \"monitors\"\n");
     }
 }
@@ -1,19 +1,34 @@
-use std::process::Command;
-use std::{io, path::PathBuf};
+use std::{collections::HashMap, io, path::PathBuf};
 
 use wlx_monitors::{WlMonitor, WlTransform};
 
-use crate::compositor::{workspace_config::WorkspaceRule, Compositor};
+use crate::{
+    compositor::{
+        Compositor, hyprland, ipc_command, run_checked,
+        sway::{SwayOutputBlock, SwayTuning},
+        workspace_config::WorkspaceRule,
+    },
+    xwlm_config::with_header,
+};
 
-pub fn reload(compositor: Compositor) {
-    let result = match compositor {
-        Compositor::Hyprland => Command::new("hyprctl").arg("reload").output(),
-        Compositor::Sway => Command::new("swaymsg").arg("reload").output(),
-        _ => return,
+pub fn reload(compositor: Compositor) -> io::Result<()> {
+    let program = match compositor {
+        Compositor::Hyprland => "hyprctl",
+        Compositor::Sway => "swaymsg",
+        _ => return Ok(()),
     };
-    if let Err(e) = result {
-        eprintln!("Failed to reload compositor: {e}");
-    }
+    run_checked(ipc_command(program).arg("reload"))
+}
+
+/// What the config writers need besides the monitors and workspace rules.
+pub struct SaveContext<'a> {
+    /// Connector → description, to write Hyprland monitors as `desc:...`.
+    pub descriptions: Option<&'a HashMap<String, String>>,
+    pub sway_tuning: &'a HashMap<String, SwayTuning>,
+    pub header: &'a str,
+    /// Where each monitor was when xwlm started. A Hyprland `auto` position
+    /// is kept while the monitor is still there.
+    pub startup_positions: &'a HashMap<String, (i32, i32)>,
 }
 
 pub fn save_monitor_config(
@@ -21,25 +36,72 @@ pub fn save_monitor_config(
     path: &PathBuf,
     monitors: &[WlMonitor],
     workspaces: &[WorkspaceRule],
+    context: &SaveContext,
 ) -> io::Result<()> {
+    let Some(content) =
+        monitor_config_content(compositor, path, monitors, workspaces, context)
+    else {
+        return Ok(());
+    };
+    std::fs::write(path, content)
+}
+
+/// What `save_monitor_config` would write, or `None` for an unknown
+/// compositor. Existing Sway output blocks and Hyprland keywords are read
+/// from `path`.
+pub fn monitor_config_content(
+    compositor: Compositor,
+    path: &PathBuf,
+    monitors: &[WlMonitor],
+    workspaces: &[WorkspaceRule],
+    context: &SaveContext,
+) -> Option<String> {
     let content = match compositor {
-        Compositor::Hyprland => format_hyprland(monitors, workspaces),
-        Compositor::Sway => format_sway(monitors, workspaces),
+        Compositor::Hyprland => {
+            let existing = std::fs::read_to_string(path).unwrap_or_default();
+            format_hyprland(
+                monitors,
+                workspaces,
+                context.descriptions,
+                &existing,
+                context.startup_positions,
+            )
+        }
+        Compositor::Sway => {
+            let existing = std::fs::read_to_string(path).unwrap_or_default();
+            format_sway(monitors, workspaces, &existing, context.sway_tuning)
+        }
         Compositor::River => format_river(monitors),
-        Compositor::Unknown => return Ok(()),
+        Compositor::Unknown => return None,
     };
-    let comment = "# This file is managed by xwlm. Do not edit manually.\n\n";
-    let final_content = format!("{}{}", comment, content);
-    std::fs::write(path, final_content)
+    Some(with_header(context.header, &content))
 }
 
-fn current_mode(monitor: &WlMonitor) -> (i32, i32, i32) {
+/// The monitors' live state in the compositor's config format, without
+/// workspace rules. Used to seed a new config on first run.
+pub fn starter_config(
+    compositor: Compositor,
+    monitors: &[WlMonitor],
+) -> String {
+    match compositor {
+        Compositor::Hyprland => {
+            format_hyprland(monitors, &[], None, "", &HashMap::new())
+        }
+        Compositor::Sway => format_sway(monitors, &[], "", &HashMap::new()),
+        Compositor::River => format_river(monitors),
+        Compositor::Unknown => String::new(),
+    }
+}
+
+/// The mode the monitor runs, if it reports one. Some virtual and headless
+/// outputs list no modes at all; the writers then leave the mode to the
+/// compositor rather than writing `0x0`.
+fn current_mode(monitor: &WlMonitor) -> Option<(i32, i32, i32)> {
     monitor
         .modes
         .iter()
         .find(|m| m.is_current)
         .map(|m| (m.resolution.width, m.resolution.height, m.refresh_rate))
-        .unwrap_or((0, 0, 60))
 }
 
 fn format_scale(scale: f64) -> String {
@@ -50,7 +112,7 @@ fn format_scale(scale: f64) -> String {
     }
 }
 
-fn transform_to_hyprland(t: WlTransform) -> u8 {
+pub fn transform_to_hyprland(t: WlTransform) -> u8 {
     match t {
         WlTransform::Normal => 0,
         WlTransform::Rotate90 => 1,
@@ -63,7 +125,7 @@ fn transform_to_hyprland(t: WlTransform) -> u8 {
     }
 }
 
-fn transform_to_sway(t: WlTransform) -> &'static str {
+pub fn transform_to_sway(t: WlTransform) -> &'static str {
     match t {
         WlTransform::Normal => "normal",
         WlTransform::Rotate90 => "90",
@@ -76,17 +138,48 @@ fn transform_to_sway(t: WlTransform) -> &'static str {
     }
 }
 
+/// With `descriptions`, monitors are written as `desc:<description>` so the
+/// config follows the physical monitor across connector changes. Workspace
+/// rules keep using connector names.
+///
+/// Mode keywords and `auto` from the `existing` entries are kept while they
+/// still describe the monitor, so rewriting doesn't pin the current values.
 fn format_hyprland(
     monitors: &[WlMonitor],
     workspaces: &[WorkspaceRule],
+    descriptions: Option<&HashMap<String, String>>,
+    existing: &str,
+    startup_positions: &HashMap<String, (i32, i32)>,
 ) -> String {
     let mut lines = Vec::new();
     for m in monitors {
-        let (w, h, refresh) = current_mode(m);
-        let scale = format_scale(m.scale);
+        let description = descriptions.and_then(|d| d.get(&m.name));
+        let id = description
+            .map(|desc| format!("desc:{}", desc))
+            .unwrap_or_else(|| m.name.clone());
+        let keywords = hyprland::entry_keywords(
+            existing,
+            &m.name,
+            description.map(String::as_str),
+        );
+        let modes: Vec<(i32, i32, i32, bool)> = m
+            .modes
+            .iter()
+            .map(|mode| {
+                let res = &mode.resolution;
+                (res.width, res.height, mode.refresh_rate, mode.preferred)
+            })
+            .collect();
         let base = format!(
-            "monitor = {}, {}x{}@{}, {}x{}, {}",
-            m.name, w, h, refresh, m.position.x, m.position.y, scale,
+            "monitor = {}, {}, {}, {}",
+            id,
+            hyprland_mode(current_mode(m), &modes, keywords.mode.as_deref()),
+            hyprland_position(
+                (m.position.x, m.position.y),
+                startup_positions.get(&m.name).copied(),
+                keywords.position.as_deref(),
+            ),
+            format_scale(m.scale),
         );
         if m.transform != WlTransform::Normal {
             lines.push(format!(
@@ -98,7 +191,7 @@ fn format_hyprland(
             lines.push(base);
         }
         if !m.enabled {
-            lines.push(format!("monitor = {}, disable", m.name));
+            lines.push(format!("monitor = {}, disable", id));
         }
     }
 
@@ -124,20 +217,79 @@ fn format_hyprland(
     lines.join("\n")
 }
 
-fn format_sway(monitors: &[WlMonitor], workspaces: &[WorkspaceRule]) -> String {
+/// The mode field: a mode keyword while it still picks the current mode,
+/// else the mode itself. `modes` are `(width, height, refresh, preferred)`.
+/// Without a current mode the keyword stays, or `preferred` is written.
+fn hyprland_mode(
+    current: Option<(i32, i32, i32)>,
+    modes: &[(i32, i32, i32, bool)],
+    keyword: Option<&str>,
+) -> String {
+    let Some(current) = current else {
+        return keyword.unwrap_or("preferred").to_string();
+    };
+    match keyword {
+        Some(keyword)
+            if hyprland::resolve_mode_keyword(keyword, modes)
+                == Some(current) =>
+        {
+            keyword.to_string()
+        }
+        _ => format!("{}x{}@{}", current.0, current.1, current.2),
+    }
+}
+
+/// The position field: an `auto` placement while the monitor is still where
+/// it was at startup, else the position itself.
+fn hyprland_position(
+    position: (i32, i32),
+    startup: Option<(i32, i32)>,
+    keyword: Option<&str>,
+) -> String {
+    match keyword {
+        Some(keyword) if startup == Some(position) => keyword.to_string(),
+        _ => format!("{}x{}", position.0, position.1),
+    }
+}
+
+fn format_sway(
+    monitors: &[WlMonitor],
+    workspaces: &[WorkspaceRule],
+    existing: &str,
+    tuning: &HashMap<String, SwayTuning>,
+) -> String {
+    let existing_blocks = SwayOutputBlock::parse_all(existing);
     let mut blocks = Vec::new();
     for m in monitors {
+        // Edit the block already on disk so its comments and extra keys
+        // survive; only mode/pos/scale/transform are rewritten.
+        let existing_block = existing_blocks.iter().find(|b| b.name == m.name);
         if !m.enabled {
-            blocks.push(format!("output {} disable", m.name));
+            match existing_block {
+                Some(block) => {
+                    let mut block = block.clone();
+                    block.set_enabled(false);
+                    blocks.push(block.to_string());
+                }
+                None => blocks.push(format!("output {} disable", m.name)),
+            }
             continue;
         }
-        let (w, h, refresh) = current_mode(m);
-        let scale = format_scale(m.scale);
-        let transform = transform_to_sway(m.transform);
-        blocks.push(format!(
-            "output {} {{\n    mode {}x{}@{}Hz\n    pos {} {}\n    scale {}\n    transform {}\n}}",
-            m.name, w, h, refresh, m.position.x, m.position.y, scale, transform,
-        ));
+        let mut block = existing_block
+            .cloned()
+            .unwrap_or_else(|| SwayOutputBlock::new(&m.name));
+        block.set_enabled(true);
+        match current_mode(m) {
+            Some((w, h, refresh)) => block.set_mode(w, h, refresh),
+            None => block.clear_mode(),
+        }
+        block.set_position(m.position.x, m.position.y);
+        block.set_scale(&format_scale(m.scale));
+        block.set_transform(transform_to_sway(m.transform));
+        if let Some(tuning) = tuning.get(&m.name) {
+            block.set_tuning(tuning);
+        }
+        blocks.push(block.to_string());
     }
 
     let ws_lines: Vec<String> = workspaces
@@ -159,14 +311,78 @@ fn format_river(monitors: &[WlMonitor]) -> String {
             lines.push(format!("wlr-randr --output {} --off", m.name));
             continue;
         }
-        let (w, h, refresh) = current_mode(m);
+        let mode = match current_mode(m) {
+            Some((w, h, refresh)) => {
+                format!("--mode {}x{}@{}Hz", w, h, refresh)
+            }
+            None => "--preferred".to_string(),
+        };
         let scale = format_scale(m.scale);
         let transform = transform_to_sway(m.transform);
         lines.push(format!(
-            "wlr-randr --output {} --mode {}x{}@{}Hz --pos {},{} --scale {} --transform {}",
-            m.name, w, h, refresh, m.position.x, m.position.y, scale, transform,
+            "wlr-randr --output {} {} --pos {},{} --scale {} --transform {}",
+            m.name, mode, m.position.x, m.position.y, scale, transform,
         ));
     }
     lines.push(String::new());
     lines.join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferred_auto_entry_round_trips_until_changed() {
+        let existing = "monitor = DP-1, preferred, auto, 1\n";
+        let keywords = hyprland::entry_keywords(existing, "DP-1", None);
+        let modes = [(3840, 2160, 60, true), (1920, 1080, 60, false)];
+
+        let line = format!(
+            "monitor = DP-1, {}, {}, 1\n",
+            hyprland_mode(
+                Some((3840, 2160, 60)),
+                &modes,
+                keywords.mode.as_deref()
+            ),
+            hyprland_position(
+                (0, 0),
+                Some((0, 0)),
+                keywords.position.as_deref()
+            ),
+        );
+        assert_eq!(line, existing);
+
+        assert_eq!(
+            hyprland_mode(
+                Some((1920, 1080, 60)),
+                &modes,
+                keywords.mode.as_deref()
+            ),
+            "1920x1080@60"
+        );
+        assert_eq!(
+            hyprland_position(
+                (1920, 0),
+                Some((0, 0)),
+                keywords.position.as_deref()
+            ),
+            "1920x0"
+        );
+    }
+
+    #[test]
+    fn test_modeless_monitor_leaves_mode_to_compositor() {
+        assert_eq!(hyprland_mode(None, &[], None), "preferred");
+        assert_eq!(hyprland_mode(None, &[], Some("highres")), "highres");
+
+        let mut block = SwayOutputBlock::parse_all(
+            "output HEADLESS-1 {\n    mode 0x0@60Hz\n    pos 0 0\n}\n",
+        )
+        .remove(0);
+        block.clear_mode();
+        let written = block.to_string();
+        assert!(!written.contains("mode"), "{written}");
+        assert!(written.contains("pos 0 0"), "{written}");
+    }
+}
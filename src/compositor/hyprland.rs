@@ -1,18 +1,26 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
-use crate::compositor::extraction::{ExtractionPlan, resolve_path};
-use crate::compositor::position::ConfigPosition;
+use crate::compositor::extraction::{
+    ExtractionPlan, MAX_INCLUDE_DEPTH, directive_argument, resolve_path,
+};
+use crate::compositor::ipc_command;
+use crate::compositor::position::{ConfigPlacement, ConfigPosition};
 
-pub fn extract(config_path: &Path, output_filename: &str) -> Result<ExtractionPlan, String> {
+pub fn extract(
+    config_path: &Path,
+    output_filename: &str,
+) -> Result<ExtractionPlan, String> {
     let config_path = config_path
         .canonicalize()
         .map_err(|e| format!("Cannot resolve config path: {e}"))?;
 
-    let output_path = config_path
-        .parent()
-        .ok_or("Cannot determine config directory")?
-        .join(output_filename);
+    let output_path = resolve_path(
+        config_path
+            .parent()
+            .ok_or("Cannot determine config directory")?,
+        output_filename,
+    );
 
     let mut extracted = Vec::new();
     let mut modified_files = Vec::new();
@@ -28,18 +36,14 @@ pub fn extract(config_path: &Path, output_filename: &str) -> Result<ExtractionPl
         &mut modified_files,
         &mut visited,
         &mut source_exists,
+        0,
     )?;
 
-    let output_content = if extracted.is_empty() {
-        String::new()
-    } else {
-        let mut content = String::from("# Generated by xwlm. Do not edit manually.\n\n");
-        for line in &extracted {
-            content.push_str(line);
-            content.push('\n');
-        }
-        content
-    };
+    let mut output_content = String::new();
+    for line in &extracted {
+        output_content.push_str(line);
+        output_content.push('\n');
+    }
 
     let source_line = if !source_exists && !extracted.is_empty() {
         let source_path = get_source_path(&output_path);
@@ -53,6 +57,7 @@ pub fn extract(config_path: &Path, output_filename: &str) -> Result<ExtractionPl
         modified_files,
         source_line,
         main_config: config_path,
+        output_path,
         source_exists,
     })
 }
@@ -76,15 +81,25 @@ fn parse_file(
     path: &Path,
     output_path: &Path,
     extracted: &mut Vec<String>,
-    modified_files: &mut Vec<(PathBuf, String)>,
+    modified_files: &mut Vec<(PathBuf, String, usize)>,
     visited: &mut HashSet<PathBuf>,
     source_exists: &mut bool,
+    depth: usize,
 ) -> Result<(), String> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(format!(
+            "Includes nested deeper than {} levels at {}",
+            MAX_INCLUDE_DEPTH,
+            path.display()
+        ));
+    }
+
     let content = std::fs::read_to_string(path)
         .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
 
     let mut kept_lines = Vec::new();
     let mut file_modified = false;
+    let mut monitor_count = 0;
     let Some(base_dir) = path.parent() else {
         return Err(format!(
             "can't find the parent directory {}",
@@ -101,6 +116,9 @@ fn parse_file(
         }
 
         if is_monitor_line(trimmed) || is_workspace_line(trimmed) {
+            if is_monitor_line(trimmed) {
+                monitor_count += 1;
+            }
             extracted.push(trimmed.to_string());
             file_modified = true;
             continue;
@@ -123,6 +141,7 @@ fn parse_file(
                         modified_files,
                         visited,
                         source_exists,
+                        depth + 1,
                     )?;
                 }
             } else if resolved
@@ -139,7 +158,7 @@ fn parse_file(
 
     if file_modified {
         let new_content = kept_lines.join("\n");
-        modified_files.push((path.to_path_buf(), new_content));
+        modified_files.push((path.to_path_buf(), new_content, monitor_count));
     }
 
     Ok(())
@@ -169,22 +188,68 @@ fn parse_source_line(line: &str) -> Option<String> {
         return None;
     }
     let rest = line["source".len()..].trim_start();
-    if !rest.starts_with('=') {
-        return None;
-    }
-    let path = rest[1..].trim();
-    if path.is_empty() {
-        return None;
+    directive_argument(rest.strip_prefix('=')?)
+}
+
+pub fn config_position(
+    content: &str,
+    monitor_name: &str,
+    description: Option<&str>,
+) -> Option<ConfigPosition> {
+    match config_placement(content, monitor_name, description)? {
+        ConfigPlacement::Position(position) => Some(position),
+        ConfigPlacement::Mirror(_) => None,
     }
-    Some(path.to_string())
 }
 
-pub fn config_position(content: &str, monitor_name: &str) -> Option<ConfigPosition> {
-    let mut found_position: Option<ConfigPosition> = None;
+/// Finds how the config places a monitor. Entries may name it by connector
+/// or, when its `description` is known, by `desc:<description>`.
+pub fn config_placement(
+    content: &str,
+    monitor_name: &str,
+    description: Option<&str>,
+) -> Option<ConfigPlacement> {
+    let mut found: Option<ConfigPlacement> = None;
+    let mut v2_block: Option<MonitorV2Block> = None;
 
     for line in content.lines() {
         let line = line.trim();
 
+        // Inside a `monitorv2 { ... }` block, collect key = value pairs
+        // until the closing brace
+        if let Some(block) = v2_block.as_mut() {
+            block.depth += line.matches('{').count() as i32;
+            block.depth -= line.matches('}').count() as i32;
+            if let Some((key, value)) = line.split_once('=') {
+                block.set(key.trim(), value.trim());
+            }
+            if block.depth <= 0
+                && let Some(block) = v2_block.take()
+                && block.output.as_deref().is_some_and(|o| {
+                    matches_monitor(o, monitor_name, description)
+                })
+                && !block.disabled
+            {
+                if let Some(source) = block.mirror {
+                    found = Some(ConfigPlacement::Mirror(source));
+                } else if let Some(position) = block.position {
+                    found = Some(ConfigPlacement::Position(position));
+                }
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("monitorv2")
+            && rest.trim_start().starts_with('{')
+        {
+            v2_block = Some(MonitorV2Block {
+                depth: rest.matches('{').count() as i32
+                    - rest.matches('}').count() as i32,
+                ..Default::default()
+            });
+            continue;
+        }
+
         // Skip comments and non-monitor lines
         if line.starts_with('#') || !line.starts_with("monitor") {
             continue;
@@ -195,11 +260,13 @@ pub fn config_position(content: &str, monitor_name: &str) -> Option<ConfigPositi
             .trim_start_matches([' ', '='])
             .trim();
 
-        // Split into comma-separated parts
-        let parts: Vec<&str> = line.split(',').map(|p| p.trim()).collect();
+        let parts = monitor_fields(line);
 
-        // Must start with the target monitor name
-        if parts.first().copied() != Some(monitor_name) {
+        // Must start with the target monitor name (or its description)
+        if !parts
+            .first()
+            .is_some_and(|p| matches_monitor(p, monitor_name, description))
+        {
             continue;
         }
 
@@ -208,15 +275,310 @@ pub fn config_position(content: &str, monitor_name: &str) -> Option<ConfigPositi
             continue;
         }
 
+        // A mirrored monitor follows its source and has no position of
+        // its own: `monitor = DP-2, 1920x1080, 0x0, 1, mirror, DP-1`
+        if let Some(i) = parts.iter().position(|p| *p == "mirror")
+            && let Some(source) = parts.get(i + 1)
+        {
+            found = Some(ConfigPlacement::Mirror(source.to_string()));
+            continue;
+        }
+
         // Position is the third field (index 2), formatted as "XxY"
         if let Some(pos_str) = parts.get(2)
             && let Some((x, y)) = parse_xy_position(pos_str)
         {
-            found_position = Some(ConfigPosition { x, y });
+            found = Some(ConfigPlacement::Position(ConfigPosition { x, y }));
+        }
+    }
+
+    found
+}
+
+fn matches_monitor(field: &str, name: &str, description: Option<&str>) -> bool {
+    match field.strip_prefix("desc:") {
+        Some(desc) => description.is_some_and(|d| d == desc.trim()),
+        None => field == name,
+    }
+}
+
+/// Monitor descriptions ("Make Model Serial") by connector name, as reported
+/// by `hyprctl monitors all`.
+pub fn monitor_descriptions() -> HashMap<String, String> {
+    ipc_command("hyprctl")
+        .args(["monitors", "all"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| {
+            parse_monitor_descriptions(&String::from_utf8_lossy(&out.stdout))
+        })
+        .unwrap_or_default()
+}
+
+fn parse_monitor_descriptions(output: &str) -> HashMap<String, String> {
+    let mut descriptions = HashMap::new();
+    let mut current: Option<&str> = None;
+
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("Monitor ") {
+            current = rest.split_whitespace().next();
+            continue;
+        }
+        if let Some(name) = current
+            && let Some(desc) = line.trim().strip_prefix("description:")
+        {
+            // Older Hyprland versions append the connector: "... (DP-1)"
+            let desc = desc.trim();
+            let desc = desc.strip_suffix(&format!(" ({name})")).unwrap_or(desc);
+            if !desc.is_empty() {
+                descriptions.insert(name.to_string(), desc.to_string());
+            }
+        }
+    }
+
+    descriptions
+}
+
+/// The output each existing workspace is on, as reported by
+/// `hyprctl workspaces`.
+pub fn live_workspaces() -> HashMap<usize, String> {
+    ipc_command("hyprctl")
+        .arg("workspaces")
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| parse_live_workspaces(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+/// Parses headers like `workspace ID 2 (2) on monitor DP-1:`. Special and
+/// named workspaces have no numeric id and are skipped.
+fn parse_live_workspaces(output: &str) -> HashMap<usize, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("workspace ID ")?;
+            let (id, rest) = rest.split_once(' ')?;
+            let (_, monitor) = rest.split_once(" on monitor ")?;
+            Some((id.parse().ok()?, monitor.trim_end_matches(':').to_string()))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct MonitorV2Block {
+    depth: i32,
+    output: Option<String>,
+    position: Option<ConfigPosition>,
+    mirror: Option<String>,
+    disabled: bool,
+}
+
+impl MonitorV2Block {
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "output" => self.output = Some(value.to_string()),
+            "position" => {
+                // `position = 0x0`, also tolerating `position = 0, 0`
+                let xy = parse_xy_position(value).or_else(|| {
+                    let (x, y) = value.split_once(',')?;
+                    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+                });
+                self.position = xy.map(|(x, y)| ConfigPosition { x, y });
+            }
+            "mirror" => self.mirror = Some(value.to_string()),
+            "disabled" => self.disabled = matches!(value, "true" | "1" | "yes"),
+            _ => {}
+        }
+    }
+}
+
+/// Splits the value of a `monitor = ...` line into its comma-separated
+/// fields. A `desc:` identifier may itself contain commas, so it extends up
+/// to the first field that looks like a mode.
+pub fn monitor_fields(value: &str) -> Vec<&str> {
+    let parts: Vec<&str> = value.split(',').map(str::trim).collect();
+    if !parts.first().is_some_and(|p| p.starts_with("desc:")) {
+        return parts;
+    }
+
+    // The identifier covers every field before the mode
+    let Some(id_count) = parts
+        .iter()
+        .skip(1)
+        .position(|p| is_mode_field(p))
+        .map(|i| i + 1)
+    else {
+        return vec![value.trim()];
+    };
+    let id_len = value
+        .split(',')
+        .take(id_count)
+        .map(|raw| raw.len() + 1)
+        .sum::<usize>()
+        - 1;
+    let mut fields = vec![value[..id_len].trim()];
+    fields.extend_from_slice(&parts[id_count..]);
+    fields
+}
+
+fn is_mode_field(field: &str) -> bool {
+    matches!(
+        field,
+        "preferred"
+            | "highres"
+            | "highrr"
+            | "maxwidth"
+            | "disable"
+            | "disabled"
+    ) || field
+        .split('@')
+        .next()
+        .and_then(|res| res.split_once('x'))
+        .is_some_and(|(w, h)| {
+            w.parse::<u32>().is_ok() && h.parse::<u32>().is_ok()
+        })
+}
+
+/// Placeholders a monitor's entry uses instead of concrete values, such as
+/// `monitor = DP-1, preferred, auto, 1`.
+#[derive(Debug, Default, PartialEq)]
+pub struct EntryKeywords {
+    pub mode: Option<String>,
+    pub position: Option<String>,
+}
+
+/// The keywords in the last enabled `monitor =` entry for the monitor.
+pub fn entry_keywords(
+    content: &str,
+    monitor_name: &str,
+    description: Option<&str>,
+) -> EntryKeywords {
+    let mut found = EntryKeywords::default();
+    for line in content.lines() {
+        let line = line.trim();
+        let Some(rest) = line.strip_prefix("monitor") else {
+            continue;
+        };
+        let Some(rest) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let parts = monitor_fields(rest);
+        if !parts
+            .first()
+            .is_some_and(|p| matches_monitor(p, monitor_name, description))
+            || parts.contains(&"disable")
+        {
+            continue;
         }
+        found = EntryKeywords {
+            mode: parts
+                .get(1)
+                .filter(|m| is_mode_keyword(m))
+                .map(|m| m.to_string()),
+            position: parts
+                .get(2)
+                .filter(|p| p.starts_with("auto"))
+                .map(|p| p.to_string()),
+        };
     }
+    found
+}
+
+pub fn is_mode_keyword(field: &str) -> bool {
+    matches!(field, "preferred" | "highres" | "highrr" | "maxwidth")
+}
+
+/// The mode Hyprland picks for a mode keyword, from the monitor's modes as
+/// `(width, height, refresh, preferred)`. `highres` favours resolution over
+/// refresh rate, `highrr` the other way round, and `maxwidth` takes the
+/// widest mode.
+pub fn resolve_mode_keyword(
+    keyword: &str,
+    modes: &[(i32, i32, i32, bool)],
+) -> Option<(i32, i32, i32)> {
+    let area = |m: &&(i32, i32, i32, bool)| m.0 as i64 * m.1 as i64;
+    let mode = match keyword {
+        "preferred" => modes.iter().find(|m| m.3)?,
+        "highres" => modes.iter().max_by_key(|m| (area(m), m.2))?,
+        "highrr" => modes.iter().max_by_key(|m| (m.2, area(m)))?,
+        "maxwidth" => modes.iter().max_by_key(|m| (m.0, m.2))?,
+        _ => return None,
+    };
+    Some((mode.0, mode.1, mode.2))
+}
 
-    found_position
+/// `monitor =` entries as `(line index, monitor id, is a disable line)`.
+fn monitor_entries(content: &str) -> Vec<(usize, String, bool)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let rest = line.trim().strip_prefix("monitor")?;
+            let rest = rest.trim_start().strip_prefix('=')?;
+            let parts = monitor_fields(rest);
+            let id = parts.first()?;
+            Some((i, id.to_string(), parts.get(1) == Some(&"disable")))
+        })
+        .collect()
+}
+
+/// Lines of entries that a later entry for the same monitor overrides. Only
+/// the last settings line is kept, plus a `disable` line if one follows it.
+fn overridden_entries(entries: &[(usize, String, bool)]) -> HashSet<usize> {
+    let last = |id: &str, disable: bool| {
+        entries
+            .iter()
+            .rev()
+            .find(|(_, other, d)| other == id && *d == disable)
+            .map(|(i, ..)| *i)
+    };
+    entries
+        .iter()
+        .filter(|(i, id, disable)| {
+            let settings = last(id, false);
+            let kept = if *disable {
+                last(id, true) == Some(*i) && settings.is_none_or(|s| s < *i)
+            } else {
+                settings == Some(*i)
+            };
+            !kept
+        })
+        .map(|(i, ..)| *i)
+        .collect()
+}
+
+/// Monitors with more `monitor =` entries than their effective config
+/// needs, with how many entries each has.
+pub fn duplicate_monitors(content: &str) -> Vec<(String, usize)> {
+    let entries = monitor_entries(content);
+    let overridden = overridden_entries(&entries);
+    let mut duplicates: Vec<(String, usize)> = Vec::new();
+    for (i, id, _) in &entries {
+        if !overridden.contains(i) || duplicates.iter().any(|(d, _)| d == id) {
+            continue;
+        }
+        let count = entries.iter().filter(|(_, other, _)| other == id).count();
+        duplicates.push((id.clone(), count));
+    }
+    duplicates
+}
+
+/// `content` without the entries a later entry overrides, so each monitor
+/// keeps the one line Hyprland ends up using.
+pub fn collapse_duplicates(content: &str) -> String {
+    let overridden = overridden_entries(&monitor_entries(content));
+    let mut collapsed: String = content
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| !overridden.contains(i))
+        .map(|(_, line)| format!("{}\n", line))
+        .collect();
+    if !content.ends_with('\n') {
+        collapsed.pop();
+    }
+    collapsed
 }
 
 fn parse_xy_position(s: &str) -> Option<(i32, i32)> {
@@ -229,11 +591,311 @@ fn parse_xy_position(s: &str) -> Option<(i32, i32)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compositor::temp_config_dir;
+    use crate::xwlm_config::DEFAULT_HEADER;
+    use std::fs;
+
+    #[test]
+    fn test_extract_follows_nested_sources() {
+        let dir = temp_config_dir("hypr-nested");
+        fs::write(
+            dir.join("hyprland.conf"),
+            "source = child.conf\ndecoration {\n}\n",
+        )
+        .unwrap();
+        fs::write(dir.join("child.conf"), "source = grandchild.conf\n")
+            .unwrap();
+        fs::write(
+            dir.join("grandchild.conf"),
+            "# displays\nmonitor = DP-1, 2560x1440@144, 0x0, 1\nworkspace = 1, monitor:DP-1\n",
+        )
+        .unwrap();
+
+        let plan =
+            extract(&dir.join("hyprland.conf"), "monitors.conf").unwrap();
+
+        assert!(
+            plan.output_content
+                .contains("monitor = DP-1, 2560x1440@144, 0x0, 1")
+        );
+        assert!(plan.output_content.contains("workspace = 1, monitor:DP-1"));
+        let grandchild = dir.join("grandchild.conf").canonicalize().unwrap();
+        assert_eq!(plan.modified_files.len(), 1);
+        assert_eq!(plan.modified_files[0].0, grandchild);
+        assert_eq!(plan.modified_files[0].1, "# displays");
+        assert_eq!(plan.modified_files[0].2, 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_into_chosen_subdirectory() {
+        let dir = temp_config_dir("hypr-chosen");
+        fs::write(
+            dir.join("hyprland.conf"),
+            "monitor = DP-1, 2560x1440@144, 0x0, 1\ninput {\n}\n",
+        )
+        .unwrap();
+
+        let plan =
+            extract(&dir.join("hyprland.conf"), "config.d/displays.conf")
+                .unwrap();
+        let output = dir.canonicalize().unwrap().join("config.d/displays.conf");
+        assert_eq!(plan.output_path, output);
+        assert!(
+            plan.source_line
+                .as_deref()
+                .is_some_and(|line| line.ends_with("config.d/displays.conf"))
+        );
+        plan.apply("Displays, kept by xwlm").unwrap();
+
+        let written = fs::read_to_string(&output).unwrap();
+        assert!(written.starts_with(
+            "# Displays, kept by xwlm\n\nmonitor = DP-1, 2560x1440@144, 0x0, 1\n"
+        ));
+        let main = fs::read_to_string(dir.join("hyprland.conf")).unwrap();
+        assert!(!main.contains("monitor = DP-1"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_keeps_existing_source_line() {
+        let dir = temp_config_dir("hypr-resource");
+        fs::write(
+            dir.join("hyprland.conf"),
+            "\tsource\t= \"monitors.conf\"  # displays\nmonitor = HDMI-A-1, preferred, auto, 1\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("monitors.conf"),
+            "monitor = DP-1, 2560x1440@144, 0x0, 1\n",
+        )
+        .unwrap();
+
+        let plan =
+            extract(&dir.join("hyprland.conf"), "monitors.conf").unwrap();
+        assert!(plan.source_exists);
+        assert_eq!(plan.source_line, None);
+        plan.apply(DEFAULT_HEADER).unwrap();
+
+        let monitors = fs::read_to_string(dir.join("monitors.conf")).unwrap();
+        assert!(monitors.contains("monitor = DP-1"));
+        assert!(monitors.contains("monitor = HDMI-A-1"));
+        let main = fs::read_to_string(dir.join("hyprland.conf")).unwrap();
+        assert_eq!(main.matches("source").count(), 1);
+
+        // A second run finds nothing new to move and adds no source line
+        let plan =
+            extract(&dir.join("hyprland.conf"), "monitors.conf").unwrap();
+        assert_eq!(plan.source_line, None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_survives_source_cycle() {
+        let dir = temp_config_dir("hypr-cycle");
+        fs::write(dir.join("hyprland.conf"), "source = a.conf\n").unwrap();
+        fs::write(
+            dir.join("a.conf"),
+            "source = hyprland.conf\nmonitor = HDMI-A-1, preferred, auto, 1\n",
+        )
+        .unwrap();
+
+        let plan =
+            extract(&dir.join("hyprland.conf"), "monitors.conf").unwrap();
+
+        assert!(plan.output_content.contains("monitor = HDMI-A-1"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_rejects_excessive_nesting() {
+        let dir = temp_config_dir("hypr-deep");
+        fs::write(dir.join("hyprland.conf"), "source = 0.conf\n").unwrap();
+        for i in 0..=MAX_INCLUDE_DEPTH {
+            fs::write(
+                dir.join(format!("{i}.conf")),
+                format!("source = {}.conf\n", i + 1),
+            )
+            .unwrap();
+        }
+        fs::write(
+            dir.join(format!("{}.conf", MAX_INCLUDE_DEPTH + 1)),
+            "monitor = DP-1, preferred, auto, 1\n",
+        )
+        .unwrap();
+
+        let result = extract(&dir.join("hyprland.conf"), "monitors.conf");
+
+        assert!(result.is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_position_monitorv2_block() {
+        let content = "monitorv2 {
+    output = DP-1
+    mode = 2560x1440@144
+    position = 1920x0
+    scale = 1
+}";
+        assert_eq!(
+            config_position(content, "DP-1", None),
+            Some(ConfigPosition { x: 1920, y: 0 })
+        );
+        assert_eq!(config_position(content, "DP-2", None), None);
+    }
+
+    #[test]
+    fn test_config_position_legacy_line() {
+        let content = "monitor = eDP-1, 1920x1080@60, 0x0, 1";
+        assert_eq!(
+            config_position(content, "eDP-1", None),
+            Some(ConfigPosition { x: 0, y: 0 })
+        );
+    }
+
+    #[test]
+    fn test_config_position_mixed_styles() {
+        let content = "monitor = eDP-1, 1920x1080@60, 0x0, 1
+# external
+monitorv2 {
+    output = DP-1
+    position = 1920, 0
+}
+monitorv2 {
+    output = HDMI-A-1
+    position = 4480x0
+    disabled = true
+}
+monitor = HDMI-A-1, 1920x1080@60, 4480x0, 1";
+        assert_eq!(
+            config_position(content, "eDP-1", None),
+            Some(ConfigPosition { x: 0, y: 0 })
+        );
+        assert_eq!(
+            config_position(content, "DP-1", None),
+            Some(ConfigPosition { x: 1920, y: 0 })
+        );
+        assert_eq!(
+            config_position(content, "HDMI-A-1", None),
+            Some(ConfigPosition { x: 4480, y: 0 })
+        );
+    }
+
+    #[test]
+    fn test_config_placement_mirror() {
+        let content = "monitor = DP-1, 1920x1080, 0x0, 1
+monitor = DP-2, 1920x1080, 0x0, 1, mirror, DP-1";
+        assert_eq!(
+            config_placement(content, "DP-2", None),
+            Some(ConfigPlacement::Mirror("DP-1".to_string()))
+        );
+        assert_eq!(config_position(content, "DP-2", None), None);
+        assert_eq!(
+            config_placement(content, "DP-1", None),
+            Some(ConfigPlacement::Position(ConfigPosition { x: 0, y: 0 }))
+        );
+    }
+
+    #[test]
+    fn test_config_placement_mirror_monitorv2() {
+        let content = "monitorv2 {
+    output = HDMI-A-1
+    mirror = eDP-1
+}";
+        assert_eq!(
+            config_placement(content, "HDMI-A-1", None),
+            Some(ConfigPlacement::Mirror("eDP-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_config_placement_by_description() {
+        let content = "monitor = desc:Dell Inc. DELL U2720Q 5KC0123, 3840x2160@60, 1920x0, 1.5
+monitor = DP-2, 1920x1080@60, 0x0, 1";
+        let desc = Some("Dell Inc. DELL U2720Q 5KC0123");
+        assert_eq!(
+            config_position(content, "DP-3", desc),
+            Some(ConfigPosition { x: 1920, y: 0 })
+        );
+        assert_eq!(config_position(content, "DP-3", None), None);
+        assert_eq!(
+            config_position(content, "DP-2", Some("Other Monitor")),
+            Some(ConfigPosition { x: 0, y: 0 })
+        );
+    }
+
+    #[test]
+    fn test_monitor_fields_with_commas_in_description() {
+        assert_eq!(
+            monitor_fields(
+                "desc:Acme, Inc. X27, 1x2 Edition, 2560x1440@144, 0x0, 1"
+            ),
+            vec![
+                "desc:Acme, Inc. X27, 1x2 Edition",
+                "2560x1440@144",
+                "0x0",
+                "1"
+            ]
+        );
+        assert_eq!(
+            monitor_fields("desc:Acme, Inc. X27, disable"),
+            vec!["desc:Acme, Inc. X27", "disable"]
+        );
+        assert_eq!(
+            monitor_fields("DP-1, preferred, auto, 1"),
+            vec!["DP-1", "preferred", "auto", "1"]
+        );
+
+        let content = "monitor = desc:Acme, Inc. X27, preferred, 1920x0, 1";
+        assert_eq!(
+            config_position(content, "DP-3", Some("Acme, Inc. X27")),
+            Some(ConfigPosition { x: 1920, y: 0 })
+        );
+    }
+
+    #[test]
+    fn test_parse_monitor_descriptions() {
+        let output = "Monitor eDP-1 (ID 0):
+\t1920x1080@60.00800 at 0x0
+\tdescription: Chimei Innolux Corporation 0x1406
+\tmake: Chimei Innolux Corporation
+
+Monitor DP-1 (ID 1):
+\tdescription: Dell Inc. DELL U2720Q 5KC0123 (DP-1)
+";
+        let descriptions = parse_monitor_descriptions(output);
+        assert_eq!(
+            descriptions.get("eDP-1").map(String::as_str),
+            Some("Chimei Innolux Corporation 0x1406")
+        );
+        assert_eq!(
+            descriptions.get("DP-1").map(String::as_str),
+            Some("Dell Inc. DELL U2720Q 5KC0123")
+        );
+    }
+
+    #[test]
+    fn test_parse_live_workspaces() {
+        let output = "workspace ID 1 (1) on monitor DP-1:\n\tmonitorID: 0\n\twindows: 2\n\n\
+                      workspace ID 4 (4) on monitor HDMI-A-1:\n\tmonitorID: 1\n\n\
+                      workspace ID -98 (special:scratch) on monitor DP-1:\n";
+        let live = parse_live_workspaces(output);
+        assert_eq!(live.len(), 2);
+        assert_eq!(live[&1], "DP-1");
+        assert_eq!(live[&4], "HDMI-A-1");
+    }
 
     #[test]
     fn test_get_source_path_with_home() {
         let home = std::env::var("HOME").unwrap_or_default();
-        let path = PathBuf::from(format!("{}/.config/hypr/monitors.conf", home));
+        let path =
+            PathBuf::from(format!("{}/.config/hypr/monitors.conf", home));
         let result = get_source_path(&path);
         assert_eq!(result, "~/.config/hypr/monitors.conf");
     }
@@ -275,4 +937,95 @@ mod tests {
         assert_eq!(parse_source_line("source="), None);
         assert_eq!(parse_source_line("sourcemonitors.conf"), None);
     }
+
+    #[test]
+    fn test_entry_keywords_for_preferred_auto() {
+        let content = "monitor = DP-1, preferred, auto, 1\n\
+                       monitor = HDMI-A-1, 1920x1080@60, 0x0, 1\n";
+        assert_eq!(
+            entry_keywords(content, "DP-1", None),
+            EntryKeywords {
+                mode: Some("preferred".to_string()),
+                position: Some("auto".to_string()),
+            }
+        );
+        assert_eq!(
+            entry_keywords(content, "HDMI-A-1", None),
+            EntryKeywords::default()
+        );
+    }
+
+    #[test]
+    fn test_resolve_preferred_mode_keyword() {
+        let modes = [(2560, 1440, 144, false), (2560, 1440, 60, true)];
+        assert_eq!(
+            resolve_mode_keyword("preferred", &modes),
+            Some((2560, 1440, 60))
+        );
+        assert_eq!(resolve_mode_keyword("preferred", &[]), None);
+    }
+
+    #[test]
+    fn test_resolve_highres_and_highrr() {
+        let modes = [
+            (3840, 2160, 60, true),
+            (2560, 1440, 144, false),
+            (2560, 1440, 165, false),
+            (3840, 2160, 30, false),
+            (1920, 1080, 240, false),
+        ];
+        assert_eq!(
+            resolve_mode_keyword("highres", &modes),
+            Some((3840, 2160, 60))
+        );
+        assert_eq!(
+            resolve_mode_keyword("highrr", &modes),
+            Some((1920, 1080, 240))
+        );
+        assert_eq!(
+            resolve_mode_keyword("maxwidth", &modes),
+            Some((3840, 2160, 60))
+        );
+        assert_eq!(resolve_mode_keyword("1920x1080", &modes), None);
+    }
+
+    #[test]
+    fn test_entry_keywords_for_highrr() {
+        let content = "monitor = DP-1, highrr, auto-right, 1\n";
+        assert_eq!(
+            entry_keywords(content, "DP-1", None),
+            EntryKeywords {
+                mode: Some("highrr".to_string()),
+                position: Some("auto-right".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_collapse_duplicates_keeps_last_entry() {
+        let content = "\
+monitor = DP-1, 1920x1080@60, 0x0, 1
+# laptop
+monitor = eDP-1, preferred, auto, 1
+monitor = DP-1, 2560x1440@144, 0x0, 1
+monitor = eDP-1, disable
+monitor = HDMI-A-1, preferred, auto, 1
+monitor = HDMI-A-1, disable
+monitor = HDMI-A-1, 1920x1080@60, 2560x0, 1
+";
+        assert_eq!(
+            duplicate_monitors(content),
+            [("DP-1".to_string(), 2), ("HDMI-A-1".to_string(), 3)]
+        );
+        assert_eq!(
+            collapse_duplicates(content),
+            "\
+# laptop
+monitor = eDP-1, preferred, auto, 1
+monitor = DP-1, 2560x1440@144, 0x0, 1
+monitor = eDP-1, disable
+monitor = HDMI-A-1, 1920x1080@60, 2560x0, 1
+"
+        );
+    }
 }
@@ -0,0 +1,50 @@
+use std::process::{Child, Command, Stdio};
+
+/// Puts a bar naming each output on the output itself. swaynag draws through
+/// layer-shell, so it works on Hyprland and River as well as Sway. Outputs
+/// it couldn't be started for are left out; the caller kills the bars.
+pub fn show(outputs: &[String]) -> Vec<Child> {
+    outputs
+        .iter()
+        .filter_map(|name| {
+            Command::new("swaynag")
+                .args(swaynag_args(name))
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()
+        })
+        .collect()
+}
+
+pub fn hide(bars: &mut Vec<Child>) {
+    for mut bar in bars.drain(..) {
+        let _ = bar.kill();
+        let _ = bar.wait();
+    }
+}
+
+fn swaynag_args(output: &str) -> Vec<String> {
+    vec![
+        "-o".to_string(),
+        output.to_string(),
+        "-t".to_string(),
+        "warning".to_string(),
+        "-m".to_string(),
+        format!("This is {}", output),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swaynag_targets_the_output() {
+        assert_eq!(
+            swaynag_args("DP-3"),
+            ["-o", "DP-3", "-t", "warning", "-m", "This is DP-3"]
+        );
+    }
+}
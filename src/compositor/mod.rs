@@ -1,13 +1,32 @@
+pub mod color_temp;
+pub mod custom_mode;
 pub mod extraction;
 pub mod format;
 mod hyprland;
+pub mod identify;
+pub mod monitor_config;
 pub mod position;
 mod sway;
+pub mod wallpaper;
 pub mod workspace_config;
 
-use std::env;
+use std::{
+    collections::HashMap,
+    env,
+    ffi::OsString,
+    fs, io,
+    os::unix::{fs::MetadataExt, net::UnixStream},
+    path::{Path, PathBuf},
+    process::{self, Stdio},
+    sync::OnceLock,
+};
 
-#[derive(Debug, Clone, Copy)]
+pub use sway::{
+    SwayTuning, apply_workspaces as apply_sway_workspaces,
+    workspace_commands as sway_workspace_commands,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Compositor {
     Hyprland,
     Sway,
@@ -25,31 +44,703 @@ impl Compositor {
         }
     }
 
-    pub fn supports_workspace_defaults(self) -> bool {
-        matches!(self, Compositor::Hyprland)
+    pub fn capabilities(self) -> Capabilities {
+        let base = Capabilities {
+            workspace_defaults: false,
+            persistent_workspaces: false,
+            saves_config: true,
+            bitdepth: false,
+            max_render_time: false,
+            transform_note: None,
+            scale_denominator: None,
+        };
+        match self {
+            Compositor::Hyprland => Capabilities {
+                workspace_defaults: true,
+                persistent_workspaces: true,
+                ..base
+            },
+            Compositor::Sway => Capabilities {
+                bitdepth: true,
+                max_render_time: true,
+                scale_denominator: Some(120),
+                ..base
+            },
+            Compositor::River => Capabilities {
+                transform_note: Some(
+                    "Saved as wlr-randr commands; River may behave differently",
+                ),
+                ..base
+            },
+            Compositor::Unknown => Capabilities {
+                saves_config: false,
+                transform_note: Some(
+                    "Applied live only; not saved to a config",
+                ),
+                ..base
+            },
+        }
+    }
+
+    /// Workspaces listed when the config doesn't set `workspace_count`.
+    /// River uses tags and xwlm writes no workspace rules for it, so the
+    /// Workspace panel is left empty there.
+    pub fn default_workspace_count(self) -> usize {
+        match self {
+            Compositor::Hyprland | Compositor::Sway => 10,
+            Compositor::River | Compositor::Unknown => 0,
+        }
+    }
+
+    /// Practical scale range for the compositor. Hyprland rejects very large
+    /// scales outright, while Sway and River accept any positive fraction.
+    pub fn scale_limits(self) -> ScaleLimits {
+        match self {
+            Compositor::Hyprland => ScaleLimits {
+                min: 0.25,
+                max: 5.0,
+            },
+            Compositor::Sway | Compositor::River => ScaleLimits {
+                min: 0.1,
+                max: 10.0,
+            },
+            Compositor::Unknown => ScaleLimits {
+                min: 0.5,
+                max: 10.0,
+            },
+        }
+    }
+}
+
+/// What xwlm can set and write to the config on a compositor. Panels use
+/// this to hide or grey out controls that would silently do nothing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capabilities {
+    workspace_defaults: bool,
+    persistent_workspaces: bool,
+    saves_config: bool,
+    bitdepth: bool,
+    max_render_time: bool,
+    transform_note: Option<&'static str>,
+    scale_denominator: Option<u32>,
+}
+
+impl Capabilities {
+    pub fn workspace_defaults(self) -> bool {
+        self.workspace_defaults
+    }
+
+    pub fn persistent_workspaces(self) -> bool {
+        self.persistent_workspaces
+    }
+
+    /// Whether changes are written to a config file, not just applied live.
+    pub fn saves_config(self) -> bool {
+        self.saves_config
+    }
+
+    /// Output bit depth isn't exposed by the wlr output protocol, so it
+    /// can only be written to the config, which Sway supports as
+    /// `render_bit_depth`.
+    pub fn bitdepth(self) -> bool {
+        self.bitdepth
+    }
+
+    /// Sway's per-output `max_render_time`.
+    pub fn max_render_time(self) -> bool {
+        self.max_render_time
+    }
+
+    /// Rejects scales the compositor would refuse or silently change:
+    /// anything not positive, outside `limits`, or, on Sway, off the 1/120
+    /// steps of fractional scaling that Sway rounds every scale to.
+    pub fn check_scale(
+        self,
+        scale: f64,
+        limits: ScaleLimits,
+    ) -> Result<(), String> {
+        if scale.is_nan() || scale <= 0.0 {
+            return Err(format!("Scale must be positive, got {:.2}", scale));
+        }
+        limits.check(scale)?;
+        if let Some(denominator) = self.scale_denominator {
+            let steps = scale * denominator as f64;
+            if (steps - steps.round()).abs() > 0.01 {
+                return Err(format!(
+                    "Scale {:.2} is not a multiple of 1/{}; the nearest is {:.4}",
+                    scale,
+                    denominator,
+                    steps.round() / denominator as f64
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// `scale` within `limits` and, on Sway, rounded to the nearest 1/120
+    /// so Sway keeps it as is. A change from `from` always moves at least
+    /// one step, so a scale step smaller than that doesn't get stuck.
+    pub fn snap_scale(self, from: f64, scale: f64, limits: ScaleLimits) -> f64 {
+        let scale = limits.clamp(scale);
+        let Some(denominator) = self.scale_denominator else {
+            return scale;
+        };
+        let d = denominator as f64;
+        let mut steps = (scale * d).round();
+        if steps == (from * d).round() && scale != from {
+            steps += (scale - from).signum();
+        }
+        // Limits that aren't on a step themselves are kept inside of
+        let lowest = (limits.min * d - 1e-6).ceil();
+        let highest = (limits.max * d + 1e-6).floor();
+        steps.max(lowest).min(highest) / d
+    }
+
+    /// Caveat shown in the Transform panel for compositors where transforms
+    /// don't go through the compositor's own config.
+    pub fn transform_note(self) -> Option<&'static str> {
+        self.transform_note
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleLimits {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl ScaleLimits {
+    pub fn with_overrides(self, min: Option<f64>, max: Option<f64>) -> Self {
+        Self {
+            min: min.unwrap_or(self.min),
+            max: max.unwrap_or(self.max),
+        }
+    }
+
+    /// Unlike `f64::clamp`, doesn't panic when a one-sided override puts
+    /// `min` above the compositor's `max`; `max` wins.
+    pub fn clamp(self, scale: f64) -> f64 {
+        scale.max(self.min).min(self.max)
+    }
+
+    pub fn check(self, scale: f64) -> Result<(), String> {
+        const EPSILON: f64 = 1e-9;
+        if scale < self.min - EPSILON || scale > self.max + EPSILON {
+            return Err(format!(
+                "Scale {:.2} is outside the supported range {:.2}–{:.2}",
+                scale, self.min, self.max
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Hyprland needs the logical size (resolution / scale) to be a whole number
+/// of pixels, otherwise it falls back to the nearest scale that is.
+pub fn hyprland_scale_is_clean(width: i32, height: i32, scale: f64) -> bool {
+    let is_whole = |v: f64| (v - v.round()).abs() < 0.01;
+    is_whole(width as f64 / scale) && is_whole(height as f64 / scale)
+}
+
+/// Monitor descriptions ("Make Model Serial") by connector name, for
+/// compositors that can match monitors by description. Empty otherwise.
+pub fn monitor_descriptions(compositor: Compositor) -> HashMap<String, String> {
+    match compositor {
+        Compositor::Hyprland => hyprland::monitor_descriptions(),
+        _ => HashMap::new(),
+    }
+}
+
+/// Monitors listed more than once in the monitor config at `path`, with
+/// how many entries each has. Only Hyprland configs can repeat a monitor.
+pub fn duplicate_monitors(
+    compositor: Compositor,
+    path: &Path,
+) -> Vec<(String, usize)> {
+    match compositor {
+        Compositor::Hyprland => hyprland::duplicate_monitors(
+            &fs::read_to_string(path).unwrap_or_default(),
+        ),
+        _ => Vec::new(),
+    }
+}
+
+/// Removes the entries a later entry for the same monitor overrides from the
+/// monitor config at `path`. Returns how many lines were removed.
+pub fn collapse_duplicates(
+    compositor: Compositor,
+    path: &Path,
+) -> io::Result<usize> {
+    if compositor != Compositor::Hyprland {
+        return Ok(0);
+    }
+    let content = fs::read_to_string(path)?;
+    let collapsed = hyprland::collapse_duplicates(&content);
+    fs::write(path, &collapsed)?;
+    Ok(content.lines().count() - collapsed.lines().count())
+}
+
+/// Workspace id → output it currently lives on, from the running compositor.
+pub fn live_workspaces(compositor: Compositor) -> HashMap<usize, String> {
+    match compositor {
+        Compositor::Hyprland => hyprland::live_workspaces(),
+        Compositor::Sway => sway::live_workspaces(),
+        _ => HashMap::new(),
     }
 }
 
+/// The compositor a monitor config looks like it was written for, judged by
+/// its directory (`~/.config/hypr`, `~/.config/sway`, ...) and then by its
+/// content. `None` when neither gives it away, e.g. an empty file elsewhere.
+pub fn config_compositor(path: &Path) -> Option<Compositor> {
+    for component in path.components().rev().skip(1) {
+        match component.as_os_str().to_str() {
+            Some("hypr") => return Some(Compositor::Hyprland),
+            Some("sway") => return Some(Compositor::Sway),
+            Some("river") => return Some(Compositor::River),
+            _ => {}
+        }
+    }
+    content_compositor(&fs::read_to_string(path).ok()?)
+}
+
+fn content_compositor(content: &str) -> Option<Compositor> {
+    content.lines().map(str::trim).find_map(|line| {
+        if (line.starts_with("monitor") && line.contains('='))
+            || line.starts_with("monitorv2")
+        {
+            Some(Compositor::Hyprland)
+        } else if line.starts_with("output ") {
+            Some(Compositor::Sway)
+        } else if line.starts_with("wlr-randr") {
+            Some(Compositor::River)
+        } else {
+            None
+        }
+    })
+}
+
+/// Per-output tuning kept in the monitor config, for compositors that
+/// have any. Empty otherwise.
+pub fn sway_tuning(
+    compositor: Compositor,
+    path: &Path,
+) -> HashMap<String, SwayTuning> {
+    match compositor {
+        Compositor::Sway => {
+            sway::parse_tuning(&fs::read_to_string(path).unwrap_or_default())
+        }
+        _ => HashMap::new(),
+    }
+}
+
+static DETECTED: OnceLock<Compositor> = OnceLock::new();
+
+/// The IPC socket `detect` found on disk, as the variable `hyprctl` or
+/// `swaymsg` reads it from.
+static PROBED_SOCKET: OnceLock<(&'static str, OsString)> = OnceLock::new();
+
+/// Which compositor is running. The session environment is checked first;
+/// shells that never got it (SSH, `su`, some launchers) fall back to a live
+/// IPC socket and then to the compositors' own CLIs. The result is cached,
+/// and a socket found on disk is handed to the commands `ipc_command` makes.
 pub fn detect() -> Compositor {
+    *DETECTED.get_or_init(|| {
+        detect_from_env()
+            .or_else(probe_sockets)
+            .or_else(probe_commands)
+            .unwrap_or(Compositor::Unknown)
+    })
+}
+
+fn detect_from_env() -> Option<Compositor> {
     if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
-        return Compositor::Hyprland;
+        return Some(Compositor::Hyprland);
     }
 
     if env::var_os("SWAYSOCK").is_some() {
-        return Compositor::Sway;
+        return Some(Compositor::Sway);
     }
 
-    if let Ok(desktop) = env::var("XDG_CURRENT_DESKTOP") {
-        let lower = desktop.to_ascii_lowercase();
-        for entry in lower.split(':') {
-            match entry.trim() {
-                "hyprland" => return Compositor::Hyprland,
-                "sway" => return Compositor::Sway,
-                "river" => return Compositor::River,
-                _ => {}
-            }
+    let desktop = env::var("XDG_CURRENT_DESKTOP").ok()?;
+    desktop.to_ascii_lowercase().split(':').find_map(|entry| {
+        match entry.trim() {
+            "hyprland" => Some(Compositor::Hyprland),
+            "sway" => Some(Compositor::Sway),
+            "river" => Some(Compositor::River),
+            _ => None,
         }
+    })
+}
+
+fn runtime_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_RUNTIME_DIR") {
+        return Some(PathBuf::from(dir));
     }
+    let uid = fs::metadata("/proc/self").ok()?.uid();
+    Some(PathBuf::from(format!("/run/user/{}", uid)))
+}
 
-    Compositor::Unknown
+/// The newest socket matching `pattern` that accepts a connection; sockets
+/// left behind by a crashed compositor are skipped.
+fn live_socket(pattern: &str) -> Option<PathBuf> {
+    let mut sockets: Vec<PathBuf> = glob::glob(pattern)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|path| UnixStream::connect(path).is_ok())
+        .collect();
+    sockets
+        .sort_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok());
+    sockets.pop()
+}
+
+fn probe_sockets() -> Option<Compositor> {
+    let runtime = runtime_dir()?;
+    let hyprland = [
+        format!("{}/hypr/*/.socket.sock", runtime.display()),
+        "/tmp/hypr/*/.socket.sock".to_string(),
+    ];
+    if let Some(socket) = hyprland.iter().find_map(|p| live_socket(p))
+        && let Some(signature) = socket.parent().and_then(Path::file_name)
+    {
+        let _ = PROBED_SOCKET
+            .set(("HYPRLAND_INSTANCE_SIGNATURE", signature.to_os_string()));
+        return Some(Compositor::Hyprland);
+    }
+
+    if let Some(socket) =
+        live_socket(&format!("{}/sway-ipc.*.sock", runtime.display()))
+    {
+        let _ = PROBED_SOCKET.set(("SWAYSOCK", socket.into_os_string()));
+        return Some(Compositor::Sway);
+    }
+
+    None
+}
+
+/// `program` as a command that reaches the compositor's IPC even when the
+/// socket was only found on disk by `detect`.
+pub fn ipc_command(program: &str) -> process::Command {
+    let mut command = process::Command::new(program);
+    if let Some((key, value)) = PROBED_SOCKET.get() {
+        command.env(key, value);
+    }
+    command
+}
+
+/// Runs `command`, failing with what it printed (stderr, or stdout when
+/// stderr is empty) if it exits non-zero.
+pub fn run_checked(command: &mut process::Command) -> io::Result<()> {
+    check_status(&command.output()?)
+}
+
+fn check_status(output: &process::Output) -> io::Result<()> {
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = if stderr.trim().is_empty() {
+        String::from_utf8_lossy(&output.stdout)
+    } else {
+        stderr
+    };
+    let reason = reason.trim();
+    Err(io::Error::other(if reason.is_empty() {
+        output.status.to_string()
+    } else {
+        reason.to_string()
+    }))
+}
+
+fn probe_commands() -> Option<Compositor> {
+    let succeeds = |program: &str, args: &[&str]| {
+        process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .is_ok_and(|output| output.status.success())
+    };
+    if succeeds("hyprctl", &["version"]) {
+        return Some(Compositor::Hyprland);
+    }
+    if succeeds("swaymsg", &["-t", "get_version"]) {
+        return Some(Compositor::Sway);
+    }
+    None
+}
+
+static VERSION: OnceLock<Option<String>> = OnceLock::new();
+
+/// The running compositor's version as it reports it, queried once.
+pub fn version(compositor: Compositor) -> Option<&'static str> {
+    VERSION.get_or_init(|| query_version(compositor)).as_deref()
+}
+
+fn query_version(compositor: Compositor) -> Option<String> {
+    let (program, args): (&str, &[&str]) = match compositor {
+        Compositor::Hyprland => ("hyprctl", &["version"]),
+        Compositor::Sway => ("swaymsg", &["-t", "get_version"]),
+        Compositor::River => ("river", &["-version"]),
+        Compositor::Unknown => return None,
+    };
+    let output = ipc_command(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Picks the version out of `hyprctl version`, `swaymsg -t get_version` or
+/// `river -version` output. Hyprland's `Tag:` line wins over its first line,
+/// which older releases fill with the commit message.
+fn parse_version(output: &str) -> Option<String> {
+    if let Some(tag) = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Tag:"))
+    {
+        let tag = tag.split(',').next().unwrap_or_default().trim();
+        let tag = tag.trim_start_matches('v');
+        let release = tag.split('-').next().unwrap_or_default();
+        if !release.is_empty() {
+            return Some(release.to_string());
+        }
+    }
+    output
+        .lines()
+        .next()?
+        .split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| matches!(c, ',' | '(' | ')'))
+                .trim_start_matches('v')
+        })
+        .find(|word| {
+            word.starts_with(|c: char| c.is_ascii_digit()) && word.contains('.')
+        })
+        .map(str::to_string)
+}
+
+/// A fresh, empty directory under the system temp dir for a test's
+/// config files.
+#[cfg(test)]
+fn temp_config_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!(
+        "xwlm-test-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    fn output(code: i32, stdout: &str, stderr: &str) -> process::Output {
+        process::Output {
+            status: process::ExitStatus::from_raw(code << 8),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_check_status() {
+        assert!(check_status(&output(0, "ok", "")).is_ok());
+
+        let err = check_status(&output(1, "", "error: no such monitor\n"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "error: no such monitor");
+
+        let err =
+            check_status(&output(1, "Error: bad config\n", "")).unwrap_err();
+        assert_eq!(err.to_string(), "Error: bad config");
+
+        let err = check_status(&output(3, "", "")).unwrap_err();
+        assert_eq!(err.to_string(), "exit status: 3");
+    }
+
+    #[test]
+    fn test_scale_limits_differ_per_compositor() {
+        let hyprland = Compositor::Hyprland.scale_limits();
+        let sway = Compositor::Sway.scale_limits();
+        assert_ne!(hyprland, sway);
+        assert_eq!(hyprland.clamp(8.0), 5.0);
+        assert_eq!(sway.clamp(8.0), 8.0);
+        assert_eq!(sway.clamp(0.05), 0.1);
+    }
+
+    #[test]
+    fn test_scale_outside_range_is_rejected() {
+        let limits = Compositor::Hyprland.scale_limits();
+        assert!(limits.check(1.5).is_ok());
+        assert!(limits.check(5.0).is_ok());
+
+        let err = limits.check(5.01).unwrap_err();
+        assert!(err.contains("outside the supported range"));
+        assert!(limits.check(0.2).is_err());
+    }
+
+    #[test]
+    fn test_scale_limit_overrides() {
+        let limits = Compositor::Sway
+            .scale_limits()
+            .with_overrides(Some(1.0), None);
+        assert_eq!(limits.min, 1.0);
+        assert_eq!(limits.max, 10.0);
+        assert!(limits.check(0.5).is_err());
+    }
+
+    #[test]
+    fn test_capabilities_per_compositor() {
+        let hyprland = Compositor::Hyprland.capabilities();
+        assert!(hyprland.workspace_defaults());
+        assert!(hyprland.persistent_workspaces());
+        assert!(hyprland.saves_config());
+
+        let sway = Compositor::Sway.capabilities();
+        assert!(!sway.workspace_defaults());
+        assert!(!sway.persistent_workspaces());
+        assert!(sway.transform_note().is_none());
+        assert!(sway.bitdepth() && sway.max_render_time());
+
+        assert!(Compositor::River.capabilities().transform_note().is_some());
+        assert!(!Compositor::Unknown.capabilities().saves_config());
+        assert!(!hyprland.bitdepth());
+    }
+
+    #[test]
+    fn test_config_compositor_from_directory() {
+        assert_eq!(
+            config_compositor(Path::new("/home/u/.config/hypr/monitors.conf")),
+            Some(Compositor::Hyprland)
+        );
+        assert_eq!(
+            config_compositor(Path::new("/home/u/.config/sway/output.conf")),
+            Some(Compositor::Sway)
+        );
+        assert_eq!(
+            config_compositor(Path::new("/nonexistent/monitors.conf")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_content_compositor() {
+        assert_eq!(
+            content_compositor("# xwlm\nmonitor = DP-1, preferred, auto, 1\n"),
+            Some(Compositor::Hyprland)
+        );
+        assert_eq!(
+            content_compositor("output DP-1 {\n    mode 1920x1080\n}\n"),
+            Some(Compositor::Sway)
+        );
+        assert_eq!(
+            content_compositor("wlr-randr --output DP-1 --off\n"),
+            Some(Compositor::River)
+        );
+        assert_eq!(content_compositor("# nothing yet\n"), None);
+    }
+
+    #[test]
+    fn test_default_workspace_count_per_compositor() {
+        assert_eq!(Compositor::Hyprland.default_workspace_count(), 10);
+        assert_eq!(Compositor::Sway.default_workspace_count(), 10);
+        assert_eq!(Compositor::River.default_workspace_count(), 0);
+    }
+
+    #[test]
+    fn test_sway_scale_check() {
+        let sway = Compositor::Sway.capabilities();
+        let limits = Compositor::Sway.scale_limits();
+        for scale in [1.0, 1.25, 1.5, 1.75, 2.0, 4.0 / 3.0] {
+            assert!(sway.check_scale(scale, limits).is_ok(), "{scale}");
+        }
+        // Reached by key presses, so off by float error
+        let stepped = (0..50).fold(1.0, |s, _| s + 0.01);
+        assert!(sway.check_scale(stepped, limits).is_ok());
+
+        for scale in [1.01, 1.33, 0.0, -1.0, 11.0] {
+            assert!(sway.check_scale(scale, limits).is_err(), "{scale}");
+        }
+
+        let hyprland = Compositor::Hyprland.capabilities();
+        let limits = Compositor::Hyprland.scale_limits();
+        assert!(hyprland.check_scale(1.01, limits).is_ok());
+    }
+
+    #[test]
+    fn test_sway_scale_snaps_to_steps() {
+        let sway = Compositor::Sway.capabilities();
+        let limits = Compositor::Sway.scale_limits();
+
+        let mut scale = 1.0;
+        while scale < 4.0 / 3.0 - 1e-9 {
+            scale = sway.snap_scale(scale, scale + 0.01, limits);
+            assert!(sway.check_scale(scale, limits).is_ok(), "{scale}");
+        }
+        assert!((scale - 4.0 / 3.0).abs() < 1e-9, "{scale}");
+
+        // A step below 1/120 still moves
+        assert_eq!(sway.snap_scale(1.0, 1.001, limits), 121.0 / 120.0);
+        assert_eq!(sway.snap_scale(1.0, 0.999, limits), 119.0 / 120.0);
+
+        let hyprland = Compositor::Hyprland.capabilities();
+        let limits = Compositor::Hyprland.scale_limits();
+        assert_eq!(hyprland.snap_scale(1.0, 1.01, limits), 1.01);
+    }
+
+    #[test]
+    fn test_hyprland_scale_is_clean() {
+        assert!(hyprland_scale_is_clean(1920, 1080, 1.0));
+        assert!(hyprland_scale_is_clean(2560, 1440, 1.25));
+        assert!(hyprland_scale_is_clean(3840, 2160, 1.5));
+        assert!(!hyprland_scale_is_clean(1920, 1080, 1.3));
+    }
+
+    #[test]
+    fn test_live_socket_skips_stale_sockets() {
+        let dir = temp_config_dir("detect");
+        let pattern = format!("{}/sway-ipc.*.sock", dir.display());
+
+        let stale = dir.join("sway-ipc.1000.1.sock");
+        drop(std::os::unix::net::UnixListener::bind(&stale).unwrap());
+        assert_eq!(live_socket(&pattern), None);
+
+        let live = dir.join("sway-ipc.1000.2.sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&live).unwrap();
+        assert_eq!(live_socket(&pattern), Some(live));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_version_from_each_compositor() {
+        assert_eq!(
+            parse_version(
+                "Hyprland 0.45.2 built from branch  at commit 12f9 ().\n\
+                 Date: Sat Nov 9 2024\n\
+                 Tag: v0.45.2, commits: 5465\n"
+            ),
+            Some("0.45.2".to_string())
+        );
+        assert_eq!(
+            parse_version(
+                "Hyprland, built from branch main at commit 2a1c dirty \
+                 (props: bump version to v0.40.0).\n\
+                 Tag: v0.40.0-12-g2a1c, commits: 4620\n"
+            ),
+            Some("0.40.0".to_string())
+        );
+        assert_eq!(
+            parse_version("sway version 1.9\n"),
+            Some("1.9".to_string())
+        );
+        assert_eq!(parse_version("0.3.5\n"), Some("0.3.5".to_string()));
+        assert_eq!(parse_version("unknown command\n"), None);
+    }
 }
@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use wlx_monitors::{WlMonitor, WlTransform};
+
+use crate::{
+    compositor::{
+        Compositor,
+        format::{transform_to_hyprland, transform_to_sway},
+        hyprland,
+        sway::SwayOutputBlock,
+    },
+    constants::TRANSFORMS,
+    utils::transform_label,
+};
+
+/// A monitor entry as written in the compositor's monitor config. Fields
+/// the config leaves to the compositor (`preferred`, `auto`) are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedMonitor {
+    pub name: String,
+    pub enabled: bool,
+    pub mode: Option<(i32, i32, Option<f64>)>,
+    /// Hyprland mode keyword such as `preferred` or `highrr`, in place of
+    /// `mode`.
+    pub mode_keyword: Option<String>,
+    pub position: Option<(i32, i32)>,
+    pub scale: Option<f64>,
+    pub transform: Option<WlTransform>,
+}
+
+impl SavedMonitor {
+    /// The configured mode, with a mode keyword resolved against the modes
+    /// `monitor` offers.
+    pub fn mode_for(
+        &self,
+        monitor: &WlMonitor,
+    ) -> Option<(i32, i32, Option<f64>)> {
+        if self.mode.is_some() {
+            return self.mode;
+        }
+        let modes: Vec<(i32, i32, i32, bool)> = monitor
+            .modes
+            .iter()
+            .map(|m| {
+                (
+                    m.resolution.width,
+                    m.resolution.height,
+                    m.refresh_rate,
+                    m.preferred,
+                )
+            })
+            .collect();
+        let (w, h, refresh) = hyprland::resolve_mode_keyword(
+            self.mode_keyword.as_deref()?,
+            &modes,
+        )?;
+        Some((w, h, Some(refresh as f64)))
+    }
+}
+
+/// One attribute where the live compositor state differs from the config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorDrift {
+    pub monitor: String,
+    pub field: &'static str,
+    pub saved: String,
+    pub live: String,
+}
+
+pub fn parse_monitor_config(
+    compositor: Compositor,
+    path: &PathBuf,
+) -> Vec<SavedMonitor> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+    match compositor {
+        Compositor::Hyprland => parse_hyprland_monitors(&content),
+        Compositor::Sway => parse_sway_monitors(&content),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_hyprland_monitors(content: &str) -> Vec<SavedMonitor> {
+    let mut saved: Vec<SavedMonitor> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let Some(rest) = trimmed.strip_prefix("monitor") else {
+            continue;
+        };
+        let Some(rest) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let parts = hyprland::monitor_fields(rest);
+        let Some(&name) = parts.first() else {
+            continue;
+        };
+
+        let entry = if parts.get(1) == Some(&"disable") {
+            SavedMonitor {
+                name: name.to_string(),
+                enabled: false,
+                mode: None,
+                mode_keyword: None,
+                position: None,
+                scale: None,
+                transform: None,
+            }
+        } else {
+            SavedMonitor {
+                name: name.to_string(),
+                enabled: true,
+                mode: parts.get(1).and_then(|m| parse_mode(m)),
+                mode_keyword: parts
+                    .get(1)
+                    .filter(|m| hyprland::is_mode_keyword(m))
+                    .map(|m| m.to_string()),
+                position: parts.get(2).and_then(|p| {
+                    let (x, y) = p.split_once('x')?;
+                    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+                }),
+                scale: parts.get(3).and_then(|s| s.parse().ok()),
+                // Hyprland leaves a monitor without the option unrotated
+                transform: Some(
+                    parts
+                        .iter()
+                        .skip(4)
+                        .skip_while(|&&p| p != "transform")
+                        .nth(1)
+                        .and_then(|t| t.parse().ok())
+                        .and_then(|t| {
+                            TRANSFORMS
+                                .into_iter()
+                                .find(|&w| transform_to_hyprland(w) == t)
+                        })
+                        .unwrap_or(WlTransform::Normal),
+                ),
+            }
+        };
+
+        // A later `disable` line overrides the monitor's earlier entry
+        match saved.iter_mut().find(|m| m.name == entry.name) {
+            Some(existing) if !entry.enabled => existing.enabled = false,
+            Some(existing) => *existing = entry,
+            None => saved.push(entry),
+        }
+    }
+
+    saved
+}
+
+fn parse_sway_monitors(content: &str) -> Vec<SavedMonitor> {
+    let mut saved: Vec<SavedMonitor> = SwayOutputBlock::parse_all(content)
+        .iter()
+        .map(|block| SavedMonitor {
+            name: block.name.clone(),
+            enabled: true,
+            mode: block
+                .get("mode")
+                .or_else(|| block.get("resolution"))
+                .or_else(|| block.get("res"))
+                .and_then(parse_mode),
+            mode_keyword: None,
+            position: block
+                .get("pos")
+                .or_else(|| block.get("position"))
+                .and_then(|p| {
+                    let mut it = p.split_whitespace();
+                    Some((it.next()?.parse().ok()?, it.next()?.parse().ok()?))
+                }),
+            scale: block.get("scale").and_then(|s| s.parse().ok()),
+            transform: block.get("transform").and_then(|t| {
+                TRANSFORMS.into_iter().find(|&w| transform_to_sway(w) == t)
+            }),
+        })
+        .collect();
+
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if let ["output", name, "disable"] = parts.as_slice() {
+            match saved.iter_mut().find(|m| m.name == *name) {
+                Some(existing) => existing.enabled = false,
+                None => saved.push(SavedMonitor {
+                    name: name.to_string(),
+                    enabled: false,
+                    mode: None,
+                    mode_keyword: None,
+                    position: None,
+                    scale: None,
+                    transform: None,
+                }),
+            }
+        }
+    }
+
+    saved
+}
+
+/// Parses `WxH`, `WxH@R` or `WxH@RHz`. Returns `None` for keywords such as
+/// `preferred` or `highres`.
+fn parse_mode(mode: &str) -> Option<(i32, i32, Option<f64>)> {
+    let (res, refresh) = match mode.split_once('@') {
+        Some((res, refresh)) => (res, Some(refresh.trim_end_matches("Hz"))),
+        None => (mode, None),
+    };
+    let (w, h) = res.split_once('x')?;
+    let refresh = match refresh {
+        Some(r) => Some(r.parse().ok()?),
+        None => None,
+    };
+    Some((w.trim().parse().ok()?, h.trim().parse().ok()?, refresh))
+}
+
+/// The config entry for a live monitor. Entries written as `desc:...` are
+/// matched through `descriptions` (connector → description).
+pub fn saved_entry<'a>(
+    saved: &'a [SavedMonitor],
+    monitor: &WlMonitor,
+    descriptions: &HashMap<String, String>,
+) -> Option<&'a SavedMonitor> {
+    saved.iter().find(|s| match s.name.strip_prefix("desc:") {
+        Some(desc) => descriptions
+            .get(&monitor.name)
+            .is_some_and(|d| d == desc.trim()),
+        None => s.name == monitor.name,
+    })
+}
+
+/// Compares the config against the live monitors. Config entries written as
+/// `desc:...` are matched through `descriptions` (connector → description).
+pub fn find_drift(
+    saved: &[SavedMonitor],
+    live: &[WlMonitor],
+    descriptions: &HashMap<String, String>,
+) -> Vec<MonitorDrift> {
+    let mut drift = Vec::new();
+
+    for monitor in live {
+        let Some(entry) = saved_entry(saved, monitor, descriptions) else {
+            continue;
+        };
+
+        let mut push = |field, saved: String, live: String| {
+            drift.push(MonitorDrift {
+                monitor: monitor.name.clone(),
+                field,
+                saved,
+                live,
+            });
+        };
+
+        if entry.enabled != monitor.enabled {
+            let state = |on: bool| if on { "on" } else { "off" }.to_string();
+            push("enabled", state(entry.enabled), state(monitor.enabled));
+            continue;
+        }
+        if !monitor.enabled {
+            continue;
+        }
+
+        if let Some((w, h, refresh)) = entry.mode_for(monitor)
+            && let Some(current) = monitor.modes.iter().find(|m| m.is_current)
+        {
+            let (lw, lh) =
+                (current.resolution.width, current.resolution.height);
+            let refresh_differs = refresh
+                .is_some_and(|r| (r - current.refresh_rate as f64).abs() > 1.0);
+            if (w, h) != (lw, lh) || refresh_differs {
+                push(
+                    "mode",
+                    format!("{}x{}", w, h)
+                        + &refresh
+                            .map(|r| format!("@{}", r))
+                            .unwrap_or_default(),
+                    format!("{}x{}@{}", lw, lh, current.refresh_rate),
+                );
+            }
+        }
+
+        if let Some((x, y)) = entry.position
+            && (x, y) != (monitor.position.x, monitor.position.y)
+        {
+            push(
+                "position",
+                format!("{},{}", x, y),
+                format!("{},{}", monitor.position.x, monitor.position.y),
+            );
+        }
+
+        if let Some(scale) = entry.scale
+            && (scale - monitor.scale).abs() > 0.01
+        {
+            push("scale", format!("{}", scale), format!("{}", monitor.scale));
+        }
+
+        if let Some(transform) = entry.transform
+            && transform != monitor.transform
+        {
+            push(
+                "transform",
+                transform_label(transform).to_string(),
+                transform_label(monitor.transform).to_string(),
+            );
+        }
+    }
+
+    drift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hyprland_monitors() {
+        let content = "# managed
+monitor = DP-1, 2560x1440@143.97, 1920x0, 1.25
+monitor = eDP-1, preferred, auto, 1
+monitor = HDMI-A-1, 1920x1080@60, 0x0, 1
+monitor = HDMI-A-1, disable
+";
+        let saved = parse_hyprland_monitors(content);
+        assert_eq!(saved.len(), 3);
+        assert_eq!(saved[0].mode, Some((2560, 1440, Some(143.97))));
+        assert_eq!(saved[0].position, Some((1920, 0)));
+        assert_eq!(saved[0].scale, Some(1.25));
+        assert_eq!(saved[1].mode, None);
+        assert_eq!(saved[1].position, None);
+        assert!(!saved[2].enabled);
+    }
+
+    #[test]
+    fn test_parse_hyprland_monitors_desc_with_commas() {
+        let content = "monitor = desc:Acme, Inc. X27, 2560x1440@60, 1920x0, 1.5
+monitor = desc:Foo, Bar=Baz, disable
+";
+        let saved = parse_hyprland_monitors(content);
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[0].name, "desc:Acme, Inc. X27");
+        assert_eq!(saved[0].mode, Some((2560, 1440, Some(60.0))));
+        assert_eq!(saved[0].position, Some((1920, 0)));
+        assert_eq!(saved[0].scale, Some(1.5));
+        assert_eq!(saved[1].name, "desc:Foo, Bar=Baz");
+        assert!(!saved[1].enabled);
+    }
+
+    #[test]
+    fn test_parse_saved_transforms() {
+        let hyprland = parse_hyprland_monitors(
+            "monitor = DP-1, 1920x1080@60, 0x0, 1, transform, 3
+monitor = DP-2, 1920x1080@60, 1080x0, 1
+",
+        );
+        assert_eq!(hyprland[0].transform, Some(WlTransform::Rotate270));
+        assert_eq!(hyprland[1].transform, Some(WlTransform::Normal));
+
+        let sway = parse_sway_monitors(
+            "output DP-1 {
+    transform flipped-90
+}
+output DP-2 {
+    scale 1
+}
+",
+        );
+        assert_eq!(sway[0].transform, Some(WlTransform::Flipped90));
+        assert_eq!(sway[1].transform, None);
+    }
+
+    #[test]
+    fn test_parse_sway_monitors() {
+        let content = "output DP-1 {
+    mode 2560x1440@144Hz
+    pos 1920 0
+    scale 1.5
+}
+
+output eDP-1 disable
+";
+        let saved = parse_sway_monitors(content);
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[0].mode, Some((2560, 1440, Some(144.0))));
+        assert_eq!(saved[0].position, Some((1920, 0)));
+        assert_eq!(saved[0].scale, Some(1.5));
+        assert_eq!(saved[1].name, "eDP-1");
+        assert!(!saved[1].enabled);
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(parse_mode("1920x1080"), Some((1920, 1080, None)));
+        assert_eq!(
+            parse_mode("1920x1080@60Hz"),
+            Some((1920, 1080, Some(60.0)))
+        );
+        assert_eq!(parse_mode("preferred"), None);
+    }
+
+    #[test]
+    fn test_parse_hyprland_mode_keywords() {
+        let content = "monitor = DP-1, highres, auto, 1\n\
+                       monitor = DP-2, highrr, 0x0, 1\n";
+        let saved = parse_hyprland_monitors(content);
+        assert_eq!(saved[0].mode, None);
+        assert_eq!(saved[0].mode_keyword.as_deref(), Some("highres"));
+        assert_eq!(saved[1].mode_keyword.as_deref(), Some("highrr"));
+    }
+}
@@ -1,6 +1,6 @@
 use std::{fs, path::PathBuf};
 
-use crate::compositor::{hyprland, sway, Compositor};
+use crate::compositor::{Compositor, hyprland, sway};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ConfigPosition {
@@ -8,10 +8,19 @@ pub struct ConfigPosition {
     pub y: i32,
 }
 
+/// How the config places a monitor: at a position, or mirroring another
+/// output, in which case it has no position of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigPlacement {
+    Position(ConfigPosition),
+    Mirror(String),
+}
+
 pub fn get_position(
     compositor: Compositor,
     config_path: &PathBuf,
     monitor_name: &str,
+    description: Option<&str>,
 ) -> Option<ConfigPosition> {
     if !config_path.exists() {
         return None;
@@ -20,8 +29,32 @@ pub fn get_position(
     let content = fs::read_to_string(config_path).ok()?;
 
     match compositor {
-        Compositor::Hyprland => hyprland::config_position(&content, monitor_name),
+        Compositor::Hyprland => {
+            hyprland::config_position(&content, monitor_name, description)
+        }
         Compositor::Sway => sway::config_position(&content, monitor_name),
         _ => None,
     }
 }
+
+/// The output `monitor_name` mirrors according to the saved config, if any.
+pub fn get_mirror_source(
+    compositor: Compositor,
+    config_path: &PathBuf,
+    monitor_name: &str,
+    description: Option<&str>,
+) -> Option<String> {
+    let content = fs::read_to_string(config_path).ok()?;
+
+    let placement = match compositor {
+        Compositor::Hyprland => {
+            hyprland::config_placement(&content, monitor_name, description)
+        }
+        _ => None,
+    };
+
+    match placement? {
+        ConfigPlacement::Mirror(source) => Some(source),
+        ConfigPlacement::Position(_) => None,
+    }
+}
@@ -1,18 +1,28 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::io;
 use std::path::{Path, PathBuf};
 
-use crate::compositor::extraction::{ExtractionPlan, resolve_path};
+use crate::compositor::extraction::{
+    ExtractionPlan, MAX_INCLUDE_DEPTH, directive_argument, resolve_path,
+};
 use crate::compositor::position::ConfigPosition;
+use crate::compositor::workspace_config::WorkspaceRule;
+use crate::compositor::{ipc_command, run_checked};
 
-pub fn extract(config_path: &Path, output_filename: &str) -> Result<ExtractionPlan, String> {
+pub fn extract(
+    config_path: &Path,
+    output_filename: &str,
+) -> Result<ExtractionPlan, String> {
     let config_path = config_path
         .canonicalize()
         .map_err(|e| format!("Cannot resolve config path: {e}"))?;
 
-    let output_path = config_path
-        .parent()
-        .ok_or("Cannot determine config directory")?
-        .join(output_filename);
+    let output_path = resolve_path(
+        config_path
+            .parent()
+            .ok_or("Cannot determine config directory")?,
+        output_filename,
+    );
 
     let mut extracted = Vec::new();
     let mut modified_files = Vec::new();
@@ -28,18 +38,14 @@ pub fn extract(config_path: &Path, output_filename: &str) -> Result<ExtractionPl
         &mut modified_files,
         &mut visited,
         &mut source_exists,
+        0,
     )?;
 
-    let output_content = if extracted.is_empty() {
-        String::new()
-    } else {
-        let mut content = String::from("# Generated by xwlm. Do not edit manually.\n\n");
-        for line in &extracted {
-            content.push_str(line);
-            content.push('\n');
-        }
-        content
-    };
+    let mut output_content = String::new();
+    for line in &extracted {
+        output_content.push_str(line);
+        output_content.push('\n');
+    }
 
     let source_line = if !source_exists && !extracted.is_empty() {
         Some(format!("include {}", output_filename))
@@ -52,6 +58,7 @@ pub fn extract(config_path: &Path, output_filename: &str) -> Result<ExtractionPl
         modified_files,
         source_line,
         main_config: config_path,
+        output_path,
         source_exists,
     })
 }
@@ -60,16 +67,26 @@ fn parse_file(
     path: &Path,
     output_path: &Path,
     extracted: &mut Vec<String>,
-    modified_files: &mut Vec<(PathBuf, String)>,
+    modified_files: &mut Vec<(PathBuf, String, usize)>,
     visited: &mut HashSet<PathBuf>,
     source_exists: &mut bool,
+    depth: usize,
 ) -> Result<(), String> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(format!(
+            "Includes nested deeper than {} levels at {}",
+            MAX_INCLUDE_DEPTH,
+            path.display()
+        ));
+    }
+
     let content = std::fs::read_to_string(path)
         .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
 
     let lines: Vec<&str> = content.lines().collect();
     let mut kept_lines = Vec::new();
     let mut file_modified = false;
+    let mut monitor_count = 0;
     let Some(base_dir) = path.parent() else {
         return Err(format!(
             "can't find the parent directory {}",
@@ -95,9 +112,11 @@ fn parse_file(
         }
 
         if is_output_line(trimmed) {
+            monitor_count += 1;
             if trimmed.contains('{') {
                 let mut block = vec![lines[i].to_string()];
-                let mut depth = count_char(trimmed, '{') as i32 - count_char(trimmed, '}') as i32;
+                let mut depth = count_char(trimmed, '{') as i32
+                    - count_char(trimmed, '}') as i32;
                 i += 1;
                 while i < lines.len() && depth > 0 {
                     depth += count_char(lines[i], '{') as i32;
@@ -120,28 +139,33 @@ fn parse_file(
         if let Some(include_path_str) = parse_include_line(trimmed) {
             let resolved = resolve_path(base_dir, &include_path_str);
 
-            if let Ok(canonical) = resolved.canonicalize() {
-                if canonical == output_path.canonicalize().unwrap_or_default() {
+            for target in expand_include(&resolved) {
+                if let Ok(canonical) = target.canonicalize() {
+                    if canonical
+                        == output_path.canonicalize().unwrap_or_default()
+                    {
+                        *source_exists = true;
+                    }
+
+                    if !visited.contains(&canonical) {
+                        visited.insert(canonical.clone());
+                        parse_file(
+                            &canonical,
+                            output_path,
+                            extracted,
+                            modified_files,
+                            visited,
+                            source_exists,
+                            depth + 1,
+                        )?;
+                    }
+                } else if target
+                    .file_name()
+                    .and_then(|f| output_path.file_name().map(|o| f == o))
+                    .unwrap_or(false)
+                {
                     *source_exists = true;
                 }
-
-                if !visited.contains(&canonical) {
-                    visited.insert(canonical.clone());
-                    parse_file(
-                        &canonical,
-                        output_path,
-                        extracted,
-                        modified_files,
-                        visited,
-                        source_exists,
-                    )?;
-                }
-            } else if resolved
-                .file_name()
-                .and_then(|f| output_path.file_name().map(|o| f == o))
-                .unwrap_or(false)
-            {
-                *source_exists = true;
             }
         }
 
@@ -151,7 +175,7 @@ fn parse_file(
 
     if file_modified {
         let new_content = kept_lines.join("\n");
-        modified_files.push((path.to_path_buf(), new_content));
+        modified_files.push((path.to_path_buf(), new_content, monitor_count));
     }
 
     Ok(())
@@ -188,11 +212,239 @@ fn is_output_line(line: &str) -> bool {
 
 fn parse_include_line(line: &str) -> Option<String> {
     let rest = line.strip_prefix("include")?;
-    let path = rest.trim_start();
-    if path.is_empty() {
+    if !rest.starts_with(char::is_whitespace) {
         return None;
     }
-    Some(path.to_string())
+    directive_argument(rest)
+}
+
+/// Expands a glob in an `include` argument (e.g. `config.d/*`) into the
+/// matching files in sorted order. Plain paths are returned unchanged.
+fn expand_include(path: &Path) -> Vec<PathBuf> {
+    let pattern = path.to_string_lossy();
+    if !pattern.contains(['*', '?', '[']) {
+        return vec![path.to_path_buf()];
+    }
+    let Ok(paths) = glob::glob(&pattern) else {
+        return Vec::new();
+    };
+    paths
+        .filter_map(Result::ok)
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+const MODE_KEYS: &[&str] = &["mode", "resolution", "res"];
+const POSITION_KEYS: &[&str] = &["pos", "position"];
+const SCALE_KEYS: &[&str] = &["scale"];
+const TRANSFORM_KEYS: &[&str] = &["transform"];
+const MAX_RENDER_TIME_KEYS: &[&str] = &["max_render_time"];
+const RENDER_BIT_DEPTH_KEYS: &[&str] = &["render_bit_depth"];
+const POWER_KEYS: &[&str] = &["enable", "disable"];
+
+/// Per-output tuning that only Sway understands. `None` leaves the key out
+/// of the block so Sway uses its default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SwayTuning {
+    /// `off` or milliseconds.
+    pub max_render_time: Option<String>,
+    pub render_bit_depth: Option<u8>,
+}
+
+/// Tuning set in the `output` blocks of `content`, by output name. Outputs
+/// without any are left out.
+pub fn parse_tuning(content: &str) -> HashMap<String, SwayTuning> {
+    SwayOutputBlock::parse_all(content)
+        .iter()
+        .filter_map(|block| {
+            let tuning = SwayTuning {
+                max_render_time: block
+                    .get("max_render_time")
+                    .map(str::to_string),
+                render_bit_depth: block
+                    .get("render_bit_depth")
+                    .and_then(|d| d.parse().ok()),
+            };
+            (tuning != SwayTuning::default())
+                .then(|| (block.name.clone(), tuning))
+        })
+        .collect()
+}
+
+/// A multi-line `output NAME { ... }` block. Lines xwlm doesn't manage
+/// (comments, `bg`, `adaptive_sync`, ...) are kept verbatim, so a block can
+/// be edited and written back unchanged apart from the managed fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwayOutputBlock {
+    pub name: String,
+    header: String,
+    body: Vec<String>,
+    footer: String,
+}
+
+impl SwayOutputBlock {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            header: format!("output {name} {{"),
+            body: Vec::new(),
+            footer: "}".to_string(),
+        }
+    }
+
+    /// Collects every `output NAME {` block in `content`. Single-line
+    /// `output` directives are skipped.
+    pub fn parse_all(content: &str) -> Vec<Self> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut blocks = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let header = lines[i];
+            let trimmed = header.trim();
+            i += 1;
+            if !trimmed.ends_with('{') {
+                continue;
+            }
+            let Some(name) = output_name(trimmed) else {
+                continue;
+            };
+
+            let mut depth = 1;
+            let mut body = Vec::new();
+            let mut footer = None;
+            while i < lines.len() {
+                let line = lines[i];
+                i += 1;
+                depth += count_char(line, '{') as i32;
+                depth -= count_char(line, '}') as i32;
+                if depth <= 0 {
+                    footer = Some(line.to_string());
+                    break;
+                }
+                body.push(line.to_string());
+            }
+
+            if let Some(footer) = footer {
+                blocks.push(Self {
+                    name: name.to_string(),
+                    header: header.to_string(),
+                    body,
+                    footer,
+                });
+            }
+        }
+
+        blocks
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.body.iter().find_map(|line| {
+            let rest = line.trim().strip_prefix(key)?;
+            rest.starts_with(char::is_whitespace).then(|| rest.trim())
+        })
+    }
+
+    pub fn set_mode(&mut self, width: i32, height: i32, refresh: i32) {
+        self.set(MODE_KEYS, &format!("{width}x{height}@{refresh}Hz"));
+    }
+
+    /// Drops the mode, for an output that reports none.
+    pub fn clear_mode(&mut self) {
+        self.remove(MODE_KEYS);
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.set(POSITION_KEYS, &format!("{x} {y}"));
+    }
+
+    pub fn set_scale(&mut self, scale: &str) {
+        self.set(SCALE_KEYS, scale);
+    }
+
+    pub fn set_transform(&mut self, transform: &str) {
+        self.set(TRANSFORM_KEYS, transform);
+    }
+
+    /// Writes the tuning keys that are set and drops the ones that aren't.
+    pub fn set_tuning(&mut self, tuning: &SwayTuning) {
+        match &tuning.max_render_time {
+            Some(time) => self.set(MAX_RENDER_TIME_KEYS, time),
+            None => self.remove(MAX_RENDER_TIME_KEYS),
+        }
+        match tuning.render_bit_depth {
+            Some(depth) => self.set(RENDER_BIT_DEPTH_KEYS, &depth.to_string()),
+            None => self.remove(RENDER_BIT_DEPTH_KEYS),
+        }
+    }
+
+    /// Adds `disable` to the block, or drops it (and any `enable`) so the
+    /// output comes back on.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.remove(POWER_KEYS);
+        if !enabled {
+            let indent = self.indent();
+            self.body.push(format!("{indent}disable"));
+        }
+    }
+
+    fn remove(&mut self, keys: &[&str]) {
+        self.body.retain(|line| {
+            line.split_whitespace()
+                .next()
+                .is_none_or(|key| !keys.contains(&key))
+        });
+    }
+
+    /// Rewrites the first line using one of `keys`, keeping its indentation
+    /// and spelling, or appends a new line if the block has none.
+    fn set(&mut self, keys: &[&str], value: &str) {
+        for line in &mut self.body {
+            let trimmed = line.trim_start();
+            let Some(key) = trimmed.split_whitespace().next() else {
+                continue;
+            };
+            if keys.contains(&key) {
+                let indent = &line[..line.len() - trimmed.len()];
+                *line = format!("{indent}{key} {value}");
+                return;
+            }
+        }
+
+        let indent = self.indent();
+        self.body.push(format!("{indent}{} {value}", keys[0]));
+    }
+
+    /// The indentation of the first non-empty line, for appended lines.
+    fn indent(&self) -> String {
+        self.body
+            .iter()
+            .find(|l| !l.trim().is_empty())
+            .map(|l| l[..l.len() - l.trim_start().len()].to_string())
+            .unwrap_or_else(|| "    ".to_string())
+    }
+}
+
+impl std::fmt::Display for SwayOutputBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.header)?;
+        for line in &self.body {
+            writeln!(f, "{line}")?;
+        }
+        write!(f, "{}", self.footer)
+    }
+}
+
+fn output_name(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("output")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = rest.trim_start();
+    if let Some(stripped) = rest.strip_prefix('"') {
+        return stripped.find('"').map(|end| &stripped[..end]);
+    }
+    rest.split_whitespace().next()
 }
 
 fn is_workspace_line(line: &str) -> bool {
@@ -209,7 +461,10 @@ fn count_char(s: &str, c: char) -> usize {
     s.chars().filter(|&ch| ch == c).count()
 }
 
-pub fn config_position(content: &str, monitor_name: &str) -> Option<ConfigPosition> {
+pub fn config_position(
+    content: &str,
+    monitor_name: &str,
+) -> Option<ConfigPosition> {
     let mut current_output: Option<String> = None;
     let mut in_output_block = false;
 
@@ -239,8 +494,10 @@ pub fn config_position(content: &str, monitor_name: &str) -> Option<ConfigPositi
             for i in 0..parts.len() {
                 if parts[i] == "pos"
                     && i + 2 < parts.len()
-                    && let (Ok(x), Ok(y)) =
-                        (parts[i + 1].parse::<i32>(), parts[i + 2].parse::<i32>())
+                    && let (Ok(x), Ok(y)) = (
+                        parts[i + 1].parse::<i32>(),
+                        parts[i + 2].parse::<i32>(),
+                    )
                 {
                     return Some(ConfigPosition { x, y });
                 }
@@ -250,3 +507,266 @@ pub fn config_position(content: &str, monitor_name: &str) -> Option<ConfigPositi
 
     None
 }
+
+/// The output each existing workspace is on, from
+/// `swaymsg -p -t get_workspaces`.
+pub fn live_workspaces() -> HashMap<usize, String> {
+    ipc_command("swaymsg")
+        .args(["-p", "-t", "get_workspaces"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| parse_live_workspaces(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+/// Parses `Workspace 2 (focused)` headers followed by an indented
+/// `Output: DP-1` line. Named workspaces are skipped.
+fn parse_live_workspaces(output: &str) -> HashMap<usize, String> {
+    let mut live = HashMap::new();
+    let mut current = None;
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("Workspace ") {
+            current = rest
+                .split_whitespace()
+                .next()
+                .and_then(|id| id.parse().ok());
+        } else if let Some(id) = current
+            && let Some(output) = line.trim().strip_prefix("Output: ")
+        {
+            live.insert(id, output.to_string());
+            current = None;
+        }
+    }
+    live
+}
+
+/// `workspace N output M` commands for the rules whose output differs from
+/// `before`. Rules without an output are left alone.
+pub fn workspace_commands(
+    before: &[WorkspaceRule],
+    after: &[WorkspaceRule],
+) -> Vec<String> {
+    after
+        .iter()
+        .filter(|rule| !rule.monitor.is_empty())
+        .filter(|rule| {
+            before
+                .iter()
+                .find(|old| old.id == rule.id)
+                .is_none_or(|old| old.monitor != rule.monitor)
+        })
+        .map(|rule| format!("workspace {} output {}", rule.id, rule.monitor))
+        .collect()
+}
+
+/// Sends workspace assignments to the running Sway, so they take effect
+/// without a config reload.
+pub fn apply_workspaces(commands: &[String]) -> io::Result<()> {
+    if commands.is_empty() {
+        return Ok(());
+    }
+    run_checked(ipc_command("swaymsg").arg(commands.join("; ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compositor::temp_config_dir;
+    use std::fs;
+
+    #[test]
+    fn test_extract_detects_quoted_include() {
+        let dir = temp_config_dir("sway-quoted");
+        fs::write(
+            dir.join("config"),
+            "include \"monitors\" # xwlm\noutput DP-1 mode 1920x1080\n",
+        )
+        .unwrap();
+
+        let plan = extract(&dir.join("config"), "monitors").unwrap();
+
+        assert!(plan.source_exists);
+        assert_eq!(plan.source_line, None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_expands_include_glob() {
+        let dir = temp_config_dir("sway-glob");
+        fs::create_dir_all(dir.join("config.d")).unwrap();
+        fs::write(dir.join("config"), "include config.d/*\nbar {\n}\n")
+            .unwrap();
+        fs::write(
+            dir.join("config.d/10-outputs"),
+            "output DP-1 {\n    mode 2560x1440@144Hz\n    pos 0 0\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("config.d/20-laptop"),
+            "output eDP-1 pos 2560 0\nworkspace 1 output eDP-1\n",
+        )
+        .unwrap();
+        fs::write(dir.join("config.d/30-input"), "input * xkb_layout us\n")
+            .unwrap();
+
+        let plan = extract(&dir.join("config"), "monitors").unwrap();
+
+        assert!(plan.output_content.contains("output DP-1 {"));
+        assert!(plan.output_content.contains("output eDP-1 pos 2560 0"));
+        assert!(plan.output_content.contains("workspace 1 output eDP-1"));
+        assert_eq!(plan.source_line.as_deref(), Some("include monitors"));
+
+        let modified: Vec<PathBuf> = plan
+            .modified_files
+            .iter()
+            .map(|(p, ..)| p.clone())
+            .collect();
+        let fragment = |name: &str| {
+            dir.join("config.d").join(name).canonicalize().unwrap()
+        };
+        assert_eq!(
+            modified,
+            vec![fragment("10-outputs"), fragment("20-laptop")]
+        );
+        let counts: Vec<usize> =
+            plan.modified_files.iter().map(|(.., n)| *n).collect();
+        assert_eq!(counts, vec![1, 1]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    const CUSTOM_BLOCK: &str = "output DP-1 {
+    # main screen
+    mode 1920x1080@60Hz
+    adaptive_sync on
+    position 0 0
+    bg ~/wall.png fill
+}";
+
+    #[test]
+    fn test_output_block_round_trip() {
+        let content =
+            format!("# outputs\n{CUSTOM_BLOCK}\noutput eDP-1 disable\n");
+        let blocks = SwayOutputBlock::parse_all(&content);
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].name, "DP-1");
+        assert_eq!(blocks[0].to_string(), CUSTOM_BLOCK);
+        assert_eq!(blocks[0].get("mode"), Some("1920x1080@60Hz"));
+    }
+
+    #[test]
+    fn test_output_block_edit_keeps_unmanaged_lines() {
+        let mut block = SwayOutputBlock::parse_all(CUSTOM_BLOCK).remove(0);
+        block.set_mode(2560, 1440, 144);
+        block.set_position(1920, 0);
+        block.set_scale("1.25");
+        block.set_transform("90");
+
+        assert_eq!(
+            block.to_string(),
+            "output DP-1 {
+    # main screen
+    mode 2560x1440@144Hz
+    adaptive_sync on
+    position 1920 0
+    bg ~/wall.png fill
+    scale 1.25
+    transform 90
+}"
+        );
+    }
+
+    #[test]
+    fn test_disabled_output_block_keeps_its_lines() {
+        let mut block = SwayOutputBlock::parse_all(CUSTOM_BLOCK).remove(0);
+        block.set_enabled(false);
+        assert_eq!(
+            block.to_string(),
+            format!(
+                "{}    disable\n}}",
+                CUSTOM_BLOCK.strip_suffix('}').unwrap()
+            )
+        );
+
+        block.set_enabled(true);
+        assert_eq!(block.to_string(), CUSTOM_BLOCK);
+    }
+
+    #[test]
+    fn test_output_block_tuning() {
+        let content = "output DP-1 {
+    mode 2560x1440@144Hz
+    max_render_time 4
+}";
+        let tuning = parse_tuning(content);
+        assert_eq!(tuning["DP-1"].max_render_time.as_deref(), Some("4"));
+        assert_eq!(tuning["DP-1"].render_bit_depth, None);
+
+        let mut block = SwayOutputBlock::parse_all(content).remove(0);
+        block.set_tuning(&SwayTuning {
+            max_render_time: None,
+            render_bit_depth: Some(10),
+        });
+        assert_eq!(
+            block.to_string(),
+            "output DP-1 {\n    mode 2560x1440@144Hz\n    render_bit_depth 10\n}"
+        );
+    }
+
+    #[test]
+    fn test_new_output_block() {
+        let mut block = SwayOutputBlock::new("HDMI-A-1");
+        block.set_mode(1920, 1080, 60);
+        block.set_position(0, 0);
+        block.set_scale("1");
+        block.set_transform("normal");
+
+        assert_eq!(
+            block.to_string(),
+            "output HDMI-A-1 {\n    mode 1920x1080@60Hz\n    pos 0 0\n    scale 1\n    transform normal\n}"
+        );
+    }
+
+    #[test]
+    fn test_workspace_commands_only_for_changes() {
+        let rule = |id, monitor: &str| WorkspaceRule {
+            id,
+            monitor: monitor.to_string(),
+            is_default: false,
+            is_persistent: false,
+        };
+        let before = [rule(1, "DP-1"), rule(2, "DP-1"), rule(3, "")];
+        let after = [
+            rule(1, "DP-1"),
+            rule(2, "HDMI-A-1"),
+            rule(3, "DP-1"),
+            rule(4, ""),
+        ];
+
+        assert_eq!(
+            workspace_commands(&before, &after),
+            vec!["workspace 2 output HDMI-A-1", "workspace 3 output DP-1"]
+        );
+        assert!(workspace_commands(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_parse_live_workspaces() {
+        let output = "Workspace 1 (focused)\n  Output: DP-1\n  Layout: splith\n\n\
+                      Workspace mail\n  Output: DP-1\n\n\
+                      Workspace 3\n  Output: HDMI-A-1\n";
+        let live = parse_live_workspaces(output);
+        assert_eq!(live.len(), 2);
+        assert_eq!(live[&1], "DP-1");
+        assert_eq!(live[&3], "HDMI-A-1");
+    }
+
+    #[test]
+    fn test_expand_include_plain_path() {
+        let path = Path::new("/etc/sway/config.d/outputs");
+        assert_eq!(expand_include(path), vec![path.to_path_buf()]);
+    }
+}
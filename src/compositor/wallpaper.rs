@@ -0,0 +1,154 @@
+use std::{
+    io::{self, BufRead, BufReader},
+    os::unix::process::CommandExt,
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::compositor::ipc_command;
+
+/// A wallpaper command running in its own process group, so stopping it
+/// also stops the setter it started, whatever shell it took to get there.
+#[derive(Debug)]
+pub struct Job {
+    child: Child,
+    /// The last line the command printed to stderr.
+    stderr: Arc<Mutex<String>>,
+    reader: Option<JoinHandle<()>>,
+    reported: bool,
+}
+
+/// Starts the wallpaper command for one output without waiting for it, as
+/// setters like swaybg keep running to hold the image. `{name}` and
+/// `{image}` in the template are replaced with the shell-quoted output name
+/// and image path.
+pub fn spawn(template: &str, output: &str, image: &str) -> io::Result<Job> {
+    let mut child = ipc_command("sh")
+        .arg("-c")
+        .arg(expand(template, output, image))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+
+    // Drained as it comes so a long-running setter never blocks on a full
+    // pipe
+    let stderr = Arc::new(Mutex::new(String::new()));
+    let reader = child.stderr.take().map(|pipe| {
+        let last = Arc::clone(&stderr);
+        thread::spawn(move || {
+            for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                if !line.trim().is_empty()
+                    && let Ok(mut last) = last.lock()
+                {
+                    *last = line;
+                }
+            }
+        })
+    });
+    Ok(Job {
+        child,
+        stderr,
+        reader,
+        reported: false,
+    })
+}
+
+impl Job {
+    /// How the command ended, once, the first time it's seen to have
+    /// ended. `None` while it runs and after that.
+    pub fn finished(&mut self) -> Option<io::Result<()>> {
+        if self.reported {
+            return None;
+        }
+        let status = match self.child.try_wait() {
+            Ok(Some(status)) => status,
+            Ok(None) => return None,
+            Err(e) => return Some(Err(e)),
+        };
+        self.reported = true;
+        if status.success() {
+            return Some(Ok(()));
+        }
+        // Give the reader a moment to catch the last line; it can't finish
+        // if a background process still holds the pipe
+        let deadline = Instant::now() + Duration::from_millis(100);
+        while self.reader.as_ref().is_some_and(|r| !r.is_finished())
+            && Instant::now() < deadline
+        {
+            thread::sleep(Duration::from_millis(5));
+        }
+        let reason = self
+            .stderr
+            .lock()
+            .map(|last| last.trim().to_string())
+            .unwrap_or_default();
+        Some(Err(io::Error::other(if reason.is_empty() {
+            status.to_string()
+        } else {
+            reason
+        })))
+    }
+
+    /// Stops everything the command started, including a setter it left
+    /// running in the background, before the output gets a new one.
+    pub fn stop(mut self) {
+        let _ = Command::new("kill")
+            .args(["-TERM", "--", &format!("-{}", self.child.id())])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn expand(template: &str, output: &str, image: &str) -> String {
+    template
+        .replace("{name}", &shell_quote(output))
+        .replace("{image}", &shell_quote(image))
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_quotes_values() {
+        assert_eq!(
+            expand(
+                "swaybg -o {name} -i {image}",
+                "DP-1",
+                "/home/me/My Pics/a.png"
+            ),
+            "swaybg -o 'DP-1' -i '/home/me/My Pics/a.png'"
+        );
+        assert_eq!(
+            expand("setbg {image}", "DP-1", "it's.png"),
+            r"setbg 'it'\''s.png'"
+        );
+    }
+
+    #[test]
+    fn test_failed_job_reports_stderr_once() {
+        let mut job =
+            spawn("echo {name} is gone >&2; exit 3", "DP-9", "").unwrap();
+        let result = loop {
+            if let Some(result) = job.finished() {
+                break result;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(result.unwrap_err().to_string(), "DP-9 is gone");
+        assert!(job.finished().is_none());
+        job.stop();
+    }
+}
@@ -10,7 +10,10 @@ pub struct WorkspaceRule {
     pub is_persistent: bool,
 }
 
-pub fn parse_workspace_config(compositor: Compositor, path: &PathBuf) -> Vec<WorkspaceRule> {
+pub fn parse_workspace_config(
+    compositor: Compositor,
+    path: &PathBuf,
+) -> Vec<WorkspaceRule> {
     let content = match std::fs::read_to_string(path) {
         Ok(c) => c,
         Err(_) => return Vec::new(),
@@ -54,18 +57,32 @@ fn parse_hyprland_workspaces(content: &str) -> Vec<WorkspaceRule> {
 fn extract_monitor_name(rules: &str) -> String {
     if let Some(monitor_part) = rules.strip_prefix("monitor:") {
         let monitor_part = monitor_part.trim();
-        if let Some((name, _)) = monitor_part.split_once(',') {
-            return name.trim().trim_matches('"').trim_matches(':').to_string();
-        }
-        return monitor_part
-            .trim()
-            .trim_matches('"')
-            .trim_matches(':')
-            .to_string();
+        let name = match monitor_part.split_once(',') {
+            // A description may contain commas; it runs until the next rule
+            Some(_) if monitor_part.starts_with("desc:") => {
+                let end = monitor_part
+                    .match_indices(',')
+                    .map(|(i, _)| i)
+                    .find(|&i| is_rule(&monitor_part[i + 1..]))
+                    .unwrap_or(monitor_part.len());
+                &monitor_part[..end]
+            }
+            Some((name, _)) => name,
+            None => monitor_part,
+        };
+        return name.trim().trim_matches('"').trim_matches(':').to_string();
     }
     rules.split(',').next().unwrap_or(rules).trim().to_string()
 }
 
+/// Whether `rest` starts with a `key:value` workspace rule.
+fn is_rule(rest: &str) -> bool {
+    rest.trim_start().split_once(':').is_some_and(|(key, _)| {
+        !key.is_empty()
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
 fn parse_sway_workspaces(content: &str) -> Vec<WorkspaceRule> {
     content
         .lines()
@@ -77,7 +94,8 @@ fn parse_sway_workspaces(content: &str) -> Vec<WorkspaceRule> {
             let rest = trimmed.strip_prefix("workspace")?.trim_start();
             let (id_str, rest) = rest.split_once(char::is_whitespace)?;
             let id: usize = id_str.trim().parse().ok()?;
-            let monitor = rest.trim().strip_prefix("output")?.trim().to_string();
+            let monitor =
+                rest.trim().strip_prefix("output")?.trim().to_string();
             Some(WorkspaceRule {
                 id,
                 monitor,
@@ -131,7 +149,9 @@ workspace = 2, monitor:eDP-1
     #[test]
     fn test_extract_monitor_name() {
         assert_eq!(
-            extract_monitor_name(r#"monitor:"HDMI-A-1",default:true,persistent:true"#),
+            extract_monitor_name(
+                r#"monitor:"HDMI-A-1",default:true,persistent:true"#
+            ),
             "HDMI-A-1"
         );
         assert_eq!(
@@ -140,4 +160,13 @@ workspace = 2, monitor:eDP-1
         );
         assert_eq!(extract_monitor_name("monitor:eDP-1"), "eDP-1");
     }
+
+    #[test]
+    fn test_parse_hyprland_workspace_desc_with_commas() {
+        let content =
+            "workspace = 1, monitor:desc:Acme, Inc. X27, default:true\n";
+        let result = parse_hyprland_workspaces(content);
+        assert_eq!(result[0].monitor, "desc:Acme, Inc. X27");
+        assert!(result[0].is_default);
+    }
 }
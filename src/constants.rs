@@ -1,3 +1,4 @@
+use ratatui::style::Color;
 use wlx_monitors::WlTransform;
 
 pub const TRANSFORMS: [WlTransform; 8] = [
@@ -12,3 +13,52 @@ pub const TRANSFORMS: [WlTransform; 8] = [
 ];
 
 pub const REPEAT_WINDOW_MS: u128 = 200;
+
+/// How long a batch of actions may wait for the compositor to confirm every
+/// monitor before the config is saved anyway.
+pub const BATCH_TIMEOUT_MS: u128 = 2000;
+
+/// Seconds a safe-applied change waits to be kept before it is undone.
+pub const SAFE_APPLY_SECS: u64 = 15;
+
+/// How long identify highlights each monitor on the map in turn.
+pub const IDENTIFY_STEP_MS: u128 = 1500;
+
+/// A non-preferred mode refreshing this many times faster than the
+/// preferred one needs a second Enter, since it may leave the monitor
+/// without a signal.
+pub const RISKY_REFRESH_RATIO: f64 = 1.25;
+
+/// Colors assigned to monitors by index so the map, the active-monitor line
+/// and the workspace list can be correlated at a glance. Yellow and red are
+/// left out since they mark pending changes and errors.
+pub const MONITOR_PALETTE: [Color; 9] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Green,
+    Color::LightBlue,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightMagenta,
+    Color::Blue,
+    Color::LightCyan,
+];
+
+/// Upper bounds for a typed custom mode. Anything larger is a typo.
+pub const MAX_CUSTOM_DIMENSION: i32 = 16384;
+pub const MAX_CUSTOM_REFRESH: i32 = 1000;
+
+/// Color temperature range of the Color panel, in Kelvin. 6500K leaves the
+/// output untinted.
+pub const NEUTRAL_COLOR_TEMP: u32 = 6500;
+pub const MIN_COLOR_TEMP: u32 = 3000;
+pub const COLOR_TEMP_STEP: u32 = 100;
+
+/// Monitors facing each other across at most this many pixels get a gap
+/// marker on the map; further apart they are just separate.
+pub const SEAM_MAX_GAP: i32 = 400;
+
+/// Map cells the zoomed layout map scrolls per Shift+arrow press. Cells are
+/// about twice as tall as wide, so vertical steps are half as many.
+pub const MAP_PAN_STEP_X: i32 = 4;
+pub const MAP_PAN_STEP_Y: i32 = 2;
@@ -0,0 +1,122 @@
+/// One line of a line-based diff.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+impl DiffLine {
+    pub fn is_change(&self) -> bool {
+        !matches!(self, DiffLine::Same(_))
+    }
+}
+
+/// Diffs `old` against `new` line by line, keeping the longest run of
+/// unchanged lines in place. Config files are small enough for the
+/// quadratic table.
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j]: length of the longest common run of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    diff.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    diff
+}
+
+/// The changed lines with up to `context` unchanged lines around each.
+/// `None` marks unchanged lines that were left out.
+pub fn with_context(
+    diff: &[DiffLine],
+    context: usize,
+) -> Vec<Option<&DiffLine>> {
+    let near_change = |i: usize| {
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(diff.len());
+        diff[start..end].iter().any(DiffLine::is_change)
+    };
+    let mut lines = Vec::new();
+    for (i, line) in diff.iter().enumerate() {
+        if near_change(i) {
+            lines.push(Some(line));
+        } else if lines.last().is_some_and(Option::is_some) {
+            lines.push(None);
+        }
+    }
+    if lines.last().is_some_and(Option::is_none) {
+        lines.pop();
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff_marks_removed_and_added_lines() {
+        let old = "general {\nmonitor = DP-1,preferred,auto,1\n}\n";
+        let new = "general {\n}\nsource = monitors.conf\n";
+        assert_eq!(
+            line_diff(old, new),
+            [
+                DiffLine::Same("general {".into()),
+                DiffLine::Removed("monitor = DP-1,preferred,auto,1".into()),
+                DiffLine::Same("}".into()),
+                DiffLine::Added("source = monitors.conf".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_context_collapses_unchanged_runs() {
+        let old = "a\nb\nc\nd\ne\nf\ng\n";
+        let new = "a\nb\nc\nd\ne\nf\nG\n";
+        let diff = line_diff(old, new);
+        let shown: Vec<Option<String>> = with_context(&diff, 1)
+            .into_iter()
+            .map(|line| {
+                line.map(|l| match l {
+                    DiffLine::Same(s) => format!(" {s}"),
+                    DiffLine::Added(s) => format!("+{s}"),
+                    DiffLine::Removed(s) => format!("-{s}"),
+                })
+            })
+            .collect();
+        assert_eq!(
+            shown,
+            [
+                Some(" f".to_string()),
+                Some("-g".to_string()),
+                Some("+G".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, io};
+use thiserror::Error;
+
+use crate::{state::WorkspaceAssignment, utils};
+
+const DRAFT_PATH: &str = "~/.local/state/xwlm/draft.toml";
+
+#[derive(Error, Debug)]
+pub enum DraftError {
+    #[error("invalid state path: {0}")]
+    Path(#[from] utils::UtilsError),
+
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid toml in draft: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("failed to serialize draft: {0}")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// Edits that haven't been applied yet, written as they are made so an
+/// unexpected exit doesn't lose them.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Draft {
+    /// Monitor names in index order when the draft was written, so the
+    /// `monitor_idx` of each workspace can be mapped onto today's order.
+    #[serde(default)]
+    pub monitors: Vec<String>,
+    /// Pending positions by monitor name.
+    #[serde(default)]
+    pub positions: BTreeMap<String, (i32, i32)>,
+    #[serde(default)]
+    pub workspaces: Vec<WorkspaceAssignment>,
+}
+
+impl Draft {
+    /// Loads the draft left by a previous run, if there is one.
+    pub fn load() -> Option<Self> {
+        load_from_path(DRAFT_PATH).ok().filter(|d| !d.is_empty())
+    }
+
+    pub fn save(&self) -> Result<(), DraftError> {
+        save_to_path(DRAFT_PATH, self)
+    }
+
+    pub fn discard() -> Result<(), DraftError> {
+        discard_path(DRAFT_PATH)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty() && self.workspaces.is_empty()
+    }
+
+    pub fn change_count(&self) -> usize {
+        self.positions.len() + self.workspaces.len()
+    }
+}
+
+fn load_from_path(path: &str) -> Result<Draft, DraftError> {
+    let expanded_path = utils::expand_tilde(path)?;
+    let content = fs::read_to_string(expanded_path)?;
+    Ok(toml::from_str(&content)?)
+}
+
+fn save_to_path(path: &str, draft: &Draft) -> Result<(), DraftError> {
+    let expanded_path = utils::expand_tilde(path)?;
+    if let Some(parent) = expanded_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&expanded_path, toml::to_string_pretty(draft)?)?;
+    Ok(())
+}
+
+fn discard_path(path: &str) -> Result<(), DraftError> {
+    let expanded_path = utils::expand_tilde(path)?;
+    match fs::remove_file(expanded_path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_then_load_draft_works() {
+        let path = "~/.config/test-xwlm/draft.toml";
+        let draft = Draft {
+            monitors: vec!["DP-1".to_string(), "HDMI-A-1".to_string()],
+            positions: BTreeMap::from([("HDMI-A-1".to_string(), (2560, 0))]),
+            workspaces: vec![WorkspaceAssignment {
+                id: 3,
+                monitor_idx: Some(1),
+                is_default: true,
+                is_persistent: false,
+            }],
+        };
+
+        save_to_path(path, &draft).unwrap();
+        let loaded = load_from_path(path).unwrap();
+        assert_eq!(loaded, draft);
+
+        discard_path(path).unwrap();
+        assert!(matches!(load_from_path(path), Err(DraftError::Io(_))));
+        // Discarding a draft that is already gone is fine
+        discard_path(path).unwrap();
+    }
+
+    #[test]
+    fn empty_draft_has_no_changes() {
+        assert!(Draft::default().is_empty());
+        assert_eq!(Draft::default().change_count(), 0);
+    }
+}
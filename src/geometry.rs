@@ -0,0 +1,329 @@
+/// An output's footprint in the layout, in the same pixels as its position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorRect {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+impl MonitorRect {
+    pub fn new(pos: (i32, i32), size: (i32, i32)) -> Self {
+        Self {
+            x: pos.0,
+            y: pos.1,
+            w: size.0,
+            h: size.1,
+        }
+    }
+
+    /// Axis-aligned overlap. Rectangles that only share an edge don't
+    /// overlap.
+    pub fn overlaps(self, other: MonitorRect) -> bool {
+        self.x < other.x + other.w
+            && self.x + self.w > other.x
+            && self.y < other.y + other.h
+            && self.y + self.h > other.y
+    }
+}
+
+pub fn overlaps_any(rect: MonitorRect, others: &[MonitorRect]) -> bool {
+    others.iter().any(|&other| rect.overlaps(other))
+}
+
+/// The spot right of every other monitor, on the top edge.
+pub fn free_position(others: &[MonitorRect]) -> (i32, i32) {
+    let max_right = others.iter().map(|r| r.x + r.w).max().unwrap_or(0);
+    (max_right, 0)
+}
+
+/// The free spot nearest to `preferred` among the four edges of the layout:
+/// left of, right of, above or below every other monitor.
+pub fn closest_free_position(
+    others: &[MonitorRect],
+    preferred: (i32, i32),
+    size: (i32, i32),
+) -> (i32, i32) {
+    if others.is_empty() {
+        return preferred;
+    }
+    let (w, h) = size;
+
+    let min_left = others.iter().map(|r| r.x).min().unwrap_or(0);
+    let max_right = others.iter().map(|r| r.x + r.w).max().unwrap_or(0);
+    let min_top = others.iter().map(|r| r.y).min().unwrap_or(0);
+    let max_bottom = others.iter().map(|r| r.y + r.h).max().unwrap_or(0);
+
+    [
+        (min_left - w, 0),
+        (max_right, 0),
+        (0, min_top - h),
+        (0, max_bottom),
+    ]
+    .into_iter()
+    .filter(|&pos| !overlaps_any(MonitorRect::new(pos, size), others))
+    .min_by_key(|pos| (pos.0 - preferred.0).abs() + (pos.1 - preferred.1).abs())
+    .unwrap_or((max_right, 0))
+}
+
+/// Where `moving` lands when it jumps the nearest monitor in the direction
+/// of the unit step `(dx, dy)`, flush against that monitor's far side. Any
+/// monitor it would then land on is jumped as well. `None` when no monitor
+/// lies that way.
+pub fn hop_past(
+    moving: MonitorRect,
+    others: &[MonitorRect],
+    (dx, dy): (i32, i32),
+) -> Option<(i32, i32)> {
+    let centre = |r: MonitorRect| (2 * r.x + r.w, 2 * r.y + r.h);
+    let (mx, my) = centre(moving);
+    let past = |r: MonitorRect| {
+        let x = match dx {
+            1 => r.x + r.w,
+            -1 => r.x - moving.w,
+            _ => moving.x,
+        };
+        let y = match dy {
+            1 => r.y + r.h,
+            -1 => r.y - moving.h,
+            _ => moving.y,
+        };
+        MonitorRect::new((x, y), (moving.w, moving.h))
+    };
+
+    let first = others
+        .iter()
+        .filter(|r| {
+            let shares_span = if dx != 0 {
+                r.y < moving.y + moving.h && r.y + r.h > moving.y
+            } else {
+                r.x < moving.x + moving.w && r.x + r.w > moving.x
+            };
+            let (rx, ry) = centre(**r);
+            shares_span && (rx - mx) * dx + (ry - my) * dy > 0
+        })
+        .min_by_key(|r| {
+            let (rx, ry) = centre(**r);
+            (rx - mx) * dx + (ry - my) * dy
+        })?;
+
+    let mut landed = past(*first);
+    for _ in 0..others.len() {
+        match others.iter().find(|r| landed.overlaps(**r)) {
+            Some(&blocker) => landed = past(blocker),
+            None => break,
+        }
+    }
+    Some((landed.x, landed.y))
+}
+
+/// How two monitors meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contact {
+    /// Edges touch, so the cursor crosses straight from one to the other.
+    Flush,
+    /// Facing each other with this many pixels between them.
+    Gap(i32),
+    Overlap,
+}
+
+/// Where two monitors meet or face each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seam {
+    pub contact: Contact,
+    /// The middle of the stretch they share, on the line between them.
+    pub at: (i32, i32),
+    /// Left and right of each other rather than above and below.
+    pub side_by_side: bool,
+}
+
+/// The seam between `a` and `b`, if they overlap, touch, or face each
+/// other across at most `max_gap` pixels. Rectangles meeting only at a
+/// corner share no edge and have no seam.
+pub fn seam(a: MonitorRect, b: MonitorRect, max_gap: i32) -> Option<Seam> {
+    let x_lo = a.x.max(b.x);
+    let x_hi = (a.x + a.w).min(b.x + b.w);
+    let y_lo = a.y.max(b.y);
+    let y_hi = (a.y + a.h).min(b.y + b.h);
+
+    if a.overlaps(b) {
+        return Some(Seam {
+            contact: Contact::Overlap,
+            at: ((x_lo + x_hi) / 2, (y_lo + y_hi) / 2),
+            side_by_side: x_hi - x_lo <= y_hi - y_lo,
+        });
+    }
+    // With no overlap, the spans overlap on at most one axis; the other
+    // axis's "overlap" is minus the gap
+    let (gap, at, side_by_side) = if y_hi > y_lo {
+        (x_lo - x_hi, ((x_lo + x_hi) / 2, (y_lo + y_hi) / 2), true)
+    } else if x_hi > x_lo {
+        (y_lo - y_hi, ((x_lo + x_hi) / 2, (y_lo + y_hi) / 2), false)
+    } else {
+        return None;
+    };
+    if gap > max_gap {
+        return None;
+    }
+    let contact = if gap == 0 {
+        Contact::Flush
+    } else {
+        Contact::Gap(gap)
+    };
+    Some(Seam {
+        contact,
+        at,
+        side_by_side,
+    })
+}
+
+/// Indices of the first two rectangles that overlap.
+pub fn first_overlap(rects: &[MonitorRect]) -> Option<(usize, usize)> {
+    rects.iter().enumerate().find_map(|(i, &a)| {
+        rects[i + 1..]
+            .iter()
+            .position(|&b| a.overlaps(b))
+            .map(|j| (i, i + 1 + j))
+    })
+}
+
+/// Positions with the overlaps removed. Rectangles are placed in order and
+/// any that overlaps one already placed moves to the closest free spot.
+pub fn resolve_overlaps(rects: &[MonitorRect]) -> Vec<(i32, i32)> {
+    let mut placed: Vec<MonitorRect> = Vec::with_capacity(rects.len());
+    for &rect in rects {
+        let pos = if overlaps_any(rect, &placed) {
+            closest_free_position(&placed, (rect.x, rect.y), (rect.w, rect.h))
+        } else {
+            (rect.x, rect.y)
+        };
+        placed.push(MonitorRect::new(pos, (rect.w, rect.h)));
+    }
+    placed.iter().map(|r| (r.x, r.y)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::transformed_dimensions;
+    use wlx_monitors::WlTransform;
+
+    #[test]
+    fn test_rotation_swaps_dimensions() {
+        assert_eq!(
+            transformed_dimensions(2560, 1440, WlTransform::Normal),
+            (2560, 1440)
+        );
+        assert_eq!(
+            transformed_dimensions(2560, 1440, WlTransform::Rotate90),
+            (1440, 2560)
+        );
+        assert_eq!(
+            transformed_dimensions(2560, 1440, WlTransform::Rotate180),
+            (2560, 1440)
+        );
+        assert_eq!(
+            transformed_dimensions(2560, 1440, WlTransform::Flipped270),
+            (1440, 2560)
+        );
+    }
+
+    #[test]
+    fn test_overlap_is_aabb() {
+        let a = MonitorRect::new((0, 0), (1920, 1080));
+        assert!(a.overlaps(MonitorRect::new((1919, 0), (1920, 1080))));
+        assert!(a.overlaps(MonitorRect::new((100, 100), (10, 10))));
+        // Touching edges are adjacent, not overlapping
+        assert!(!a.overlaps(MonitorRect::new((1920, 0), (1920, 1080))));
+        assert!(!a.overlaps(MonitorRect::new((0, 1080), (1920, 1080))));
+        assert!(!a.overlaps(MonitorRect::new((1920, 1080), (10, 10))));
+    }
+
+    #[test]
+    fn test_free_position_is_right_of_everything() {
+        let others = [
+            MonitorRect::new((0, 0), (1920, 1080)),
+            MonitorRect::new((1920, 0), (2560, 1440)),
+        ];
+        assert_eq!(free_position(&others), (4480, 0));
+        assert_eq!(free_position(&[]), (0, 0));
+    }
+
+    #[test]
+    fn test_closest_free_position_avoids_collisions() {
+        let others = [MonitorRect::new((0, 0), (1920, 1080))];
+
+        // Preferred spot is taken; left of the layout is closest
+        let pos = closest_free_position(&others, (-1000, 0), (1280, 1024));
+        assert_eq!(pos, (-1280, 0));
+        assert!(!overlaps_any(MonitorRect::new(pos, (1280, 1024)), &others));
+
+        let pos = closest_free_position(&others, (0, 1500), (1280, 1024));
+        assert_eq!(pos, (0, 1080));
+
+        assert_eq!(closest_free_position(&[], (50, 50), (10, 10)), (50, 50));
+    }
+
+    #[test]
+    fn test_seam_tells_flush_gap_and_overlap_apart() {
+        let left = MonitorRect::new((0, 0), (1920, 1080));
+
+        let right = MonitorRect::new((1920, 0), (1920, 1080));
+        let flush = seam(left, right, 200).unwrap();
+        assert_eq!(flush.contact, Contact::Flush);
+        assert_eq!(flush.at, (1920, 540));
+        assert!(flush.side_by_side);
+
+        let below = MonitorRect::new((960, 1130), (1920, 1080));
+        let gap = seam(left, below, 200).unwrap();
+        assert_eq!(gap.contact, Contact::Gap(50));
+        assert_eq!(gap.at, (1440, 1105));
+        assert!(!gap.side_by_side);
+        assert_eq!(seam(left, below, 40), None);
+
+        let over = MonitorRect::new((1820, 0), (1920, 1080));
+        assert_eq!(seam(left, over, 200).unwrap().contact, Contact::Overlap);
+
+        let corner = MonitorRect::new((1920, 1080), (1920, 1080));
+        assert_eq!(seam(left, corner, 200), None);
+    }
+
+    #[test]
+    fn test_hop_past_lands_flush_beyond_neighbour() {
+        let moving = MonitorRect::new((0, 0), (1920, 1080));
+        let next = MonitorRect::new((1920, 0), (2560, 1440));
+        assert_eq!(hop_past(moving, &[next], (1, 0)), Some((4480, 0)));
+        assert_eq!(hop_past(moving, &[next], (-1, 0)), None);
+
+        // Back again lands flush on the near side
+        let moved = MonitorRect::new((4480, 0), (1920, 1080));
+        assert_eq!(hop_past(moved, &[next], (-1, 0)), Some((0, 0)));
+
+        // A monitor right behind the first is jumped too
+        let behind = MonitorRect::new((4480, 0), (1280, 1024));
+        assert_eq!(hop_past(moving, &[next, behind], (1, 0)), Some((5760, 0)));
+
+        let below = MonitorRect::new((500, 1080), (1920, 1080));
+        assert_eq!(hop_past(moving, &[next, below], (0, 1)), Some((0, 2160)));
+    }
+
+    #[test]
+    fn test_resolve_overlaps_moves_only_overlapping() {
+        let rects = [
+            MonitorRect::new((0, 0), (1920, 1080)),
+            MonitorRect::new((1000, 0), (1920, 1080)),
+            MonitorRect::new((0, 1080), (1920, 1080)),
+        ];
+        assert_eq!(first_overlap(&rects), Some((0, 1)));
+
+        let resolved = resolve_overlaps(&rects);
+        assert_eq!(resolved[0], (0, 0));
+        assert_eq!(resolved[2], (0, 1080));
+        let fixed: Vec<MonitorRect> = resolved
+            .iter()
+            .zip(&rects)
+            .map(|(&pos, r)| MonitorRect::new(pos, (r.w, r.h)))
+            .collect();
+        assert_eq!(first_overlap(&fixed), None);
+    }
+}
@@ -1,16 +1,34 @@
+mod cli;
 mod compositor;
 mod constants;
+mod diff;
+mod draft;
+mod geometry;
+mod monitor_memory;
 mod setup;
 mod state;
+mod text_input;
 mod tui;
 mod utils;
 mod xwlm_config;
 
-use std::{error::Error, io, sync::mpsc};
+use std::{
+    env,
+    error::Error,
+    io::{self, Write},
+    sync::mpsc::{self, Receiver},
+};
 
-use wlx_monitors::{WlMonitorManager, WlMonitorManagerError};
+use wlx_monitors::{
+    WlMonitor, WlMonitorEvent, WlMonitorManager, WlMonitorManagerError,
+};
 
-use crate::{state::App, xwlm_config::Config};
+use crate::{
+    cli::{Command, initial_monitors},
+    state::App,
+    tui::keymap::Keymap,
+    xwlm_config::Config,
+};
 
 fn main() {
     if let Err(e) = run() {
@@ -20,38 +38,104 @@ fn main() {
 }
 
 fn run() -> Result<(), Box<dyn Error>> {
+    let command = Command::parse(env::args().skip(1))?;
+    // Detect before any thread starts; it may export the compositor's socket
+    let comp = compositor::detect();
+    if command == Command::Version {
+        for line in cli::version_lines(comp) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
     let (wlx_emitter, wlx_events) = mpsc::sync_channel(16);
     let (wlx_action_handler, wlx_action_rx) = mpsc::sync_channel(16);
-    let (wlx_manager, wlx_eq) = WlMonitorManager::new_connection(wlx_emitter, wlx_action_rx)?;
+    let (wlx_manager, wlx_eq) =
+        WlMonitorManager::new_connection(wlx_emitter, wlx_action_rx)?;
 
     std::thread::spawn(move || -> Result<(), WlMonitorManagerError> {
         wlx_manager.run(wlx_eq)?;
         Ok(())
     });
 
-    let Some(config) = load()? else { return Ok(()) };
+    match command {
+        Command::Save { dry_run } => {
+            cli::save(&wlx_events, dry_run)?;
+            return Ok(());
+        }
+        Command::Toggle { name, dry_run } => {
+            cli::toggle(&wlx_events, wlx_action_handler, &name, dry_run)?;
+            return Ok(());
+        }
+        Command::Tui | Command::Version => {}
+    }
+
+    let mut setup_monitors = None;
+    let Some(config) = load(&wlx_events, &mut setup_monitors)? else {
+        return Ok(());
+    };
+    config.validate()?;
+    let keymap = Keymap::from_config(&config.keybinds)?;
 
-    let mut app = App::new(
-        wlx_action_handler,
-        config.monitor_config_path,
-        config.workspace_count,
-    );
-    tui::run(&mut app, wlx_events)?;
+    let mut app = App::new(wlx_action_handler, &config);
+    app.restore_color_temps();
+    // Setup already took the initial state off the channel; if it timed out
+    // waiting, the state is still on its way.
+    if let Some(monitors) = setup_monitors.filter(|m| !m.is_empty()) {
+        app.set_monitors(monitors);
+    }
+    tui::run(&mut app, wlx_events, &keymap)?;
     Ok(())
 }
 
-fn load() -> io::Result<Option<Config>> {
+fn load(
+    wlx_events: &Receiver<WlMonitorEvent>,
+    setup_monitors: &mut Option<Vec<WlMonitor>>,
+) -> io::Result<Option<Config>> {
     let comp = compositor::detect();
-    let Ok(cfg) = xwlm_config::load_config() else {
-        return setup::run(comp).map_err(io::Error::other);
+    let mut run_setup = || {
+        let monitors =
+            setup_monitors.get_or_insert_with(|| initial_monitors(wlx_events));
+        setup::run(comp, monitors).map_err(io::Error::other)
+    };
+    // Only a missing config starts setup; setup would overwrite one that
+    // exists but doesn't load, with the user's keybinds and wallpapers in it
+    let cfg = match xwlm_config::load_config() {
+        Ok(cfg) => cfg,
+        Err(e) if e.is_missing() => return run_setup(),
+        Err(e) => return Err(io::Error::other(e)),
     };
 
     let path_str = cfg.monitor_config_path.to_string_lossy();
     if !utils::monitor_config_exists(&path_str) {
         eprintln!("Monitor config file not found: {}", path_str);
         eprintln!("Re-running setup...");
-        return setup::run(comp).map_err(io::Error::other);
+        return run_setup();
+    }
+
+    // Saving would write the wrong format into another compositor's config
+    if comp != compositor::Compositor::Unknown
+        && let Some(written_for) =
+            compositor::config_compositor(&cfg.monitor_config_path)
+        && written_for != comp
+    {
+        eprintln!(
+            "Warning: {} looks like a {} config, but xwlm is running under {}.",
+            path_str,
+            written_for.label(),
+            comp.label()
+        );
+        if ask(&format!("Re-run setup for {}? [y/N] ", comp.label()))? {
+            return run_setup();
+        }
     }
 
     Ok(Some(cfg))
 }
+
+fn ask(prompt: &str) -> io::Result<bool> {
+    eprint!("{}", prompt);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
@@ -0,0 +1,143 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+};
+use thiserror::Error;
+use wlx_monitors::WlMonitor;
+
+use crate::utils;
+
+const MEMORY_PATH: &str = "~/.local/state/xwlm/monitors.toml";
+
+#[derive(Error, Debug)]
+pub enum MemoryError {
+    #[error("invalid state path: {0}")]
+    Path(#[from] utils::UtilsError),
+
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid toml in monitor memory: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("failed to serialize monitor memory: {0}")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// The last applied placement of a monitor, kept so it can be restored
+/// after the monitor was disabled or unplugged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnownMonitor {
+    pub x: i32,
+    pub y: i32,
+    pub scale: f64,
+    pub transform: String,
+}
+
+/// Sidecar record of monitor placements, independent of the compositor
+/// config which may only say `disable` for a monitor that is off.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MonitorMemory {
+    #[serde(default)]
+    pub monitors: BTreeMap<String, KnownMonitor>,
+    /// Monitors pinned in place while arranging the others.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub locked: BTreeSet<String>,
+}
+
+impl MonitorMemory {
+    /// Loads the record, starting empty if it is missing or unreadable.
+    pub fn load() -> Self {
+        load_from_path(MEMORY_PATH).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), MemoryError> {
+        save_to_path(MEMORY_PATH, self)
+    }
+
+    /// Records every enabled monitor. Disabled ones keep their last entry.
+    pub fn record(&mut self, monitors: &[WlMonitor]) {
+        for m in monitors.iter().filter(|m| m.enabled) {
+            self.monitors.insert(
+                m.name.clone(),
+                KnownMonitor {
+                    x: m.position.x,
+                    y: m.position.y,
+                    scale: m.scale,
+                    transform: utils::transform_label(m.transform).to_string(),
+                },
+            );
+        }
+    }
+
+    pub fn position(&self, name: &str) -> Option<(i32, i32)> {
+        self.monitors.get(name).map(|m| (m.x, m.y))
+    }
+
+    pub fn is_locked(&self, name: &str) -> bool {
+        self.locked.contains(name)
+    }
+
+    /// Locks or unlocks the monitor, returning whether it is now locked.
+    pub fn toggle_lock(&mut self, name: &str) -> bool {
+        if self.locked.remove(name) {
+            return false;
+        }
+        self.locked.insert(name.to_string());
+        true
+    }
+}
+
+fn load_from_path(path: &str) -> Result<MonitorMemory, MemoryError> {
+    let expanded_path = utils::expand_tilde(path)?;
+    let content = fs::read_to_string(expanded_path)?;
+    Ok(toml::from_str(&content)?)
+}
+
+fn save_to_path(path: &str, memory: &MonitorMemory) -> Result<(), MemoryError> {
+    let expanded_path = utils::expand_tilde(path)?;
+    if let Some(parent) = expanded_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&expanded_path, toml::to_string_pretty(memory)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_then_load_memory_works() {
+        let path = "~/.config/test-xwlm/monitors.toml";
+        let mut memory = MonitorMemory::default();
+        memory.monitors.insert(
+            "DP-1".to_string(),
+            KnownMonitor {
+                x: 2560,
+                y: 0,
+                scale: 1.5,
+                transform: "Normal".to_string(),
+            },
+        );
+
+        memory.toggle_lock("eDP-1");
+
+        save_to_path(path, &memory).unwrap();
+        let loaded = load_from_path(path).unwrap();
+
+        assert_eq!(loaded.monitors, memory.monitors);
+        assert!(loaded.is_locked("eDP-1"));
+        assert!(!loaded.is_locked("DP-1"));
+        assert_eq!(loaded.position("DP-1"), Some((2560, 0)));
+        assert_eq!(loaded.position("HDMI-A-1"), None);
+    }
+
+    #[test]
+    fn load_fails_when_memory_missing() {
+        let result = load_from_path("~/.config/test-xwlm/no-monitors.toml");
+        assert!(matches!(result, Err(MemoryError::Io(_))));
+    }
+}
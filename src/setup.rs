@@ -1,11 +1,14 @@
 use std::io::{self};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crossterm::event::{self, Event, KeyCode};
 use crossterm::{
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode,
+        enable_raw_mode,
+    },
 };
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::prelude::CrosstermBackend;
@@ -14,50 +17,54 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
 use ratatui::{DefaultTerminal, Frame, Terminal};
 
+use wlx_monitors::WlMonitor;
+
 use crate::compositor::Compositor;
-use crate::compositor::extraction::{ExtractionPlan, extract_monitors, main_config_path};
-use crate::utils::expand_tilde;
-use crate::xwlm_config::{self, Config, save_config};
+use crate::compositor::extraction::{
+    ExtractionPlan, extract_monitors, main_config_path, resolve_path,
+};
+use crate::compositor::format::starter_config;
+use crate::diff::{self, DiffLine};
+use crate::text_input::TextInput;
+use crate::utils::{WriteTarget, check_writable, expand_tilde};
+use crate::xwlm_config::{self, Config, DEFAULT_HEADER, save_config};
 
 enum SetupPhase {
     Extraction,
+    /// What the extraction will change in each file, awaiting a final
+    /// confirm.
+    Preview,
     Manual,
 }
 
 struct ExtractionResult {
     plan: ExtractionPlan,
+    /// The file name as chosen, relative to the main config's directory.
+    output_name: String,
     output_path: String,
-    source_files: Vec<String>,
+    /// Files the lines come from, with the monitor entries in each.
+    source_files: Vec<(String, usize)>,
     monitor_count: usize,
     already_consolidated: bool,
 }
 
 struct SetupState {
-    input: String,
-    cursor: usize,
+    input: TextInput,
     compositor: Compositor,
     error: Option<String>,
     phase: SetupPhase,
     extraction: Option<ExtractionResult>,
+    /// A different file to consolidate into, being typed.
+    rename: Option<TextInput>,
+    /// Each file the extraction writes, diffed against what is on disk.
+    preview: Vec<(PathBuf, Vec<DiffLine>)>,
+    preview_scroll: u16,
     warned: bool,
-}
-
-impl SetupState {
-    fn prev_cursor(&self) -> usize {
-        self.input[..self.cursor]
-            .char_indices()
-            .next_back()
-            .map(|(i, _)| i)
-            .unwrap_or(0)
-    }
-
-    fn next_cursor(&self) -> usize {
-        self.input[self.cursor..]
-            .char_indices()
-            .nth(1)
-            .map(|(i, _)| self.cursor + i)
-            .unwrap_or(self.input.len())
-    }
+    /// The entered file is missing and the user was offered to create it.
+    create_offered: bool,
+    /// The live monitor state in config form, written into a newly created
+    /// file.
+    starter: String,
 }
 
 fn default_config_path(compositor: Compositor) -> String {
@@ -82,43 +89,36 @@ fn get_outputfile_name(compositor: Compositor) -> String {
     get_monitors_config_name(compositor).to_string()
 }
 
-fn attempt_extraction(compositor: Compositor) -> Option<ExtractionResult> {
+fn attempt_extraction(
+    compositor: Compositor,
+    output_filename: &str,
+) -> Option<ExtractionResult> {
     let main_config = main_config_path(compositor)?;
-    let output_filename = get_outputfile_name(compositor);
 
-    let plan = extract_monitors(&main_config, compositor, &output_filename).ok()?;
+    let plan =
+        extract_monitors(&main_config, compositor, output_filename).ok()?;
 
     if !plan.has_monitors() {
         return None;
     }
 
-    let output_path = main_config
-        .parent()?
-        .join(output_filename)
-        .to_string_lossy()
-        .to_string();
+    let output_path = plan.output_path.to_string_lossy().to_string();
 
-    let source_files: Vec<String> = plan
+    let source_files: Vec<(String, usize)> = plan
         .modified_files
         .iter()
-        .map(|(p, _)| p.to_string_lossy().to_string())
+        .map(|(p, _, count)| (p.to_string_lossy().to_string(), *count))
         .collect();
 
-    let monitor_count = plan
-        .output_content
-        .lines()
-        .filter(|l| {
-            let trimmed = l.trim();
-            !trimmed.is_empty() && !trimmed.starts_with('#')
-        })
-        .count();
+    let monitor_count = source_files.iter().map(|(_, count)| count).sum();
 
     let already_consolidated = plan.source_exists
         && source_files.len() <= 1
-        && source_files.first().is_some_and(|f| f == &output_path);
+        && source_files.first().is_some_and(|(f, _)| f == &output_path);
 
     Some(ExtractionResult {
         plan,
+        output_name: output_filename.to_string(),
         output_path,
         source_files,
         monitor_count,
@@ -126,8 +126,11 @@ fn attempt_extraction(compositor: Compositor) -> Option<ExtractionResult> {
     })
 }
 
-pub fn run(compositor: Compositor) -> Result<Option<Config>, xwlm_config::ConfigError> {
-    let result = run_setup(compositor).map_err(io::Error::other)?;
+pub fn run(
+    compositor: Compositor,
+    monitors: &[WlMonitor],
+) -> Result<Option<Config>, xwlm_config::ConfigError> {
+    let result = run_setup(compositor, monitors).map_err(io::Error::other)?;
     match result {
         Some(cfg) => {
             save_config(&cfg)?;
@@ -137,7 +140,10 @@ pub fn run(compositor: Compositor) -> Result<Option<Config>, xwlm_config::Config
     }
 }
 
-fn run_setup(compositor: Compositor) -> io::Result<Option<Config>> {
+fn run_setup(
+    compositor: Compositor,
+    monitors: &[WlMonitor],
+) -> io::Result<Option<Config>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
@@ -145,31 +151,38 @@ fn run_setup(compositor: Compositor) -> io::Result<Option<Config>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = init(&mut terminal, compositor);
+    let result = init(&mut terminal, compositor, monitors);
 
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     result
 }
 
-pub fn init(terminal: &mut DefaultTerminal, compositor: Compositor) -> io::Result<Option<Config>> {
-    let extraction = attempt_extraction(compositor);
+pub fn init(
+    terminal: &mut DefaultTerminal,
+    compositor: Compositor,
+    monitors: &[WlMonitor],
+) -> io::Result<Option<Config>> {
+    let extraction =
+        attempt_extraction(compositor, &get_outputfile_name(compositor));
 
     let (phase, config_path) = match &extraction {
         Some(result) => (SetupPhase::Extraction, result.output_path.clone()),
         None => (SetupPhase::Manual, default_config_path(compositor)),
     };
 
-    let cursor = config_path.clone().len();
-
     let mut state = SetupState {
-        input: config_path.clone(),
-        cursor,
+        input: TextInput::new(config_path),
         compositor,
         error: None,
         phase,
         extraction,
+        rename: None,
+        preview: Vec::new(),
+        preview_scroll: 0,
         warned: false,
+        create_offered: false,
+        starter: starter_config(compositor, monitors),
     };
 
     loop {
@@ -178,70 +191,139 @@ pub fn init(terminal: &mut DefaultTerminal, compositor: Compositor) -> io::Resul
         if event::poll(Duration::from_millis(50))?
             && let Event::Key(k) = event::read()?
         {
+            if let Some(input) = state.rename.as_mut() {
+                match k.code {
+                    KeyCode::Enter => rename_output(&mut state),
+                    KeyCode::Esc => {
+                        state.rename = None;
+                        state.error = None;
+                    }
+                    code => {
+                        input.handle_key(code);
+                        state.error = None;
+                    }
+                }
+                continue;
+            }
+
             match (&state.phase, k.code) {
                 (SetupPhase::Extraction, KeyCode::Enter) => {
                     let Some(ref result) = state.extraction else {
                         continue;
                     };
-                    if !result.already_consolidated
-                        && let Err(e) = result.plan.apply()
+                    if let Err(e) =
+                        check_writable(Path::new(&result.output_path))
                     {
-                        state.error = Some(format!("Extraction failed: {e}"));
+                        state.error = Some(format!(
+                            "xwlm couldn't save monitor settings there: {e}"
+                        ));
                         state.phase = SetupPhase::Manual;
                         continue;
                     }
-                    return Ok(Some(Config {
-                        monitor_config_path: PathBuf::from(config_path),
-                        workspace_count: 10,
-                    }));
+                    if result.already_consolidated {
+                        return Ok(Some(Config::new(PathBuf::from(
+                            &result.output_path,
+                        ))));
+                    }
+                    match result.plan.planned_writes(DEFAULT_HEADER) {
+                        Ok(writes) => {
+                            state.preview = writes
+                                .into_iter()
+                                .map(|(path, content)| {
+                                    let current =
+                                        std::fs::read_to_string(&path)
+                                            .unwrap_or_default();
+                                    (path, diff::line_diff(&current, &content))
+                                })
+                                .collect();
+                            state.preview_scroll = 0;
+                            state.phase = SetupPhase::Preview;
+                        }
+                        Err(e) => {
+                            state.error =
+                                Some(format!("Extraction failed: {e}"));
+                            state.phase = SetupPhase::Manual;
+                        }
+                    }
+                }
+                (SetupPhase::Extraction, KeyCode::Char('e')) => {
+                    if let Some(ref result) = state.extraction {
+                        state.rename =
+                            Some(TextInput::new(result.output_name.clone()));
+                        state.error = None;
+                    }
                 }
                 (SetupPhase::Extraction, KeyCode::Char('m')) => {
                     state.phase = SetupPhase::Manual;
-                    state.input = default_config_path(compositor);
-                    state.cursor = state.input.len();
+                    state.input =
+                        TextInput::new(default_config_path(compositor));
                     state.error = None;
                     state.warned = false;
+                    state.create_offered = false;
                 }
                 (SetupPhase::Extraction, KeyCode::Esc) => return Ok(None),
 
-                // --- Manual phase ---
-                (SetupPhase::Manual, KeyCode::Esc) => return Ok(None),
-                (SetupPhase::Manual, KeyCode::Char(c)) => {
-                    state.input.insert(state.cursor, c);
-                    state.cursor += c.len_utf8();
-                    state.error = None;
-                    state.warned = false;
-                }
-                (SetupPhase::Manual, KeyCode::Backspace) => {
-                    if state.cursor > 0 {
-                        let prev = state.prev_cursor();
-                        state.input.remove(prev);
-                        state.cursor = prev;
+                // --- Preview phase ---
+                (SetupPhase::Preview, KeyCode::Enter) => {
+                    let Some(ref result) = state.extraction else {
+                        continue;
+                    };
+                    if let Err(e) = result.plan.apply(DEFAULT_HEADER) {
+                        state.error = Some(format!("Extraction failed: {e}"));
+                        state.phase = SetupPhase::Manual;
+                        continue;
                     }
+                    return Ok(Some(Config::new(PathBuf::from(
+                        &result.output_path,
+                    ))));
+                }
+                (SetupPhase::Preview, KeyCode::Esc) => {
+                    state.phase = SetupPhase::Manual;
+                    state.input =
+                        TextInput::new(default_config_path(compositor));
                     state.error = None;
                     state.warned = false;
+                    state.create_offered = false;
                 }
-                (SetupPhase::Manual, KeyCode::Delete) => {
-                    if state.cursor < state.input.len() {
-                        state.input.remove(state.cursor);
-                    }
+                (SetupPhase::Preview, KeyCode::Up | KeyCode::Char('k')) => {
+                    state.preview_scroll =
+                        state.preview_scroll.saturating_sub(1);
+                }
+                (SetupPhase::Preview, KeyCode::Down | KeyCode::Char('j')) => {
+                    state.preview_scroll =
+                        state.preview_scroll.saturating_add(1);
+                }
+                (SetupPhase::Preview, KeyCode::PageUp) => {
+                    state.preview_scroll =
+                        state.preview_scroll.saturating_sub(10);
+                }
+                (SetupPhase::Preview, KeyCode::PageDown) => {
+                    state.preview_scroll =
+                        state.preview_scroll.saturating_add(10);
+                }
+
+                // --- Manual phase ---
+                (SetupPhase::Manual, KeyCode::Esc) => return Ok(None),
+                (
+                    SetupPhase::Manual,
+                    KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete,
+                ) => {
+                    state.input.handle_key(k.code);
                     state.error = None;
                     state.warned = false;
+                    state.create_offered = false;
                 }
-                (SetupPhase::Manual, KeyCode::Left) => {
-                    if state.cursor > 0 {
-                        state.cursor = state.prev_cursor();
-                    }
-                }
-                (SetupPhase::Manual, KeyCode::Right) => {
-                    if state.cursor < state.input.len() {
-                        state.cursor = state.next_cursor();
-                    }
+                (
+                    SetupPhase::Manual,
+                    KeyCode::Left
+                    | KeyCode::Right
+                    | KeyCode::Home
+                    | KeyCode::End,
+                ) => {
+                    state.input.handle_key(k.code);
                 }
-                (SetupPhase::Manual, KeyCode::Home) => state.cursor = 0,
-                (SetupPhase::Manual, KeyCode::End) => state.cursor = state.input.len(),
                 (SetupPhase::Manual, KeyCode::Enter) => {
-                    let path = state.input.trim();
+                    let path = state.input.value().trim();
                     if path.is_empty() {
                         state.error = Some("Path cannot be empty".to_string());
                         continue;
@@ -253,17 +335,42 @@ pub fn init(terminal: &mut DefaultTerminal, compositor: Compositor) -> io::Resul
                         continue;
                     }
 
-                    let expanded = expand_tilde(path).map_err(io::Error::other)?;
-                    if !expanded.exists() {
-                        state.error = Some("File does not exist. Please enter a valid path.".to_string());
-                        state.warned = false;
-                        continue;
+                    let expanded =
+                        expand_tilde(path).map_err(io::Error::other)?;
+                    let target = match check_writable(&expanded) {
+                        Ok(target) => target,
+                        Err(e) => {
+                            state.error = Some(format!(
+                                "xwlm couldn't save monitor settings there: {e}"
+                            ));
+                            continue;
+                        }
+                    };
+                    if target == WriteTarget::Creatable {
+                        if !state.create_offered {
+                            state.create_offered = true;
+                            state.error = Some(if state.starter.is_empty() {
+                                "File does not exist. Press Enter again to create it, or edit the path.".to_string()
+                            } else {
+                                "File does not exist. Press Enter again to create it with your current setup, or edit the path.".to_string()
+                            });
+                            continue;
+                        }
+                        if let Err(e) = create_config_file(
+                            &expanded,
+                            compositor,
+                            &state.starter,
+                        ) {
+                            state.error = Some(format!(
+                                "Could not create {}: {e}",
+                                expanded.display()
+                            ));
+                            state.create_offered = false;
+                            continue;
+                        }
                     }
 
-                    return Ok(Some(Config {
-                        monitor_config_path: expanded,
-                        workspace_count: 10,
-                    }));
+                    return Ok(Some(Config::new(expanded)));
                 }
                 _ => {}
             }
@@ -271,6 +378,79 @@ pub fn init(terminal: &mut DefaultTerminal, compositor: Compositor) -> io::Resul
     }
 }
 
+/// Re-plans the extraction into the typed file. The name is checked like a
+/// manually entered path, and must not be the main config itself.
+fn rename_output(state: &mut SetupState) {
+    let (Some(input), Some(result)) = (&state.rename, &state.extraction) else {
+        return;
+    };
+    let name = input.value().trim().to_string();
+    if name.is_empty() {
+        state.error = Some("File name cannot be empty".to_string());
+        return;
+    }
+    let main_config = &result.plan.main_config;
+    let Some(config_dir) = main_config.parent() else {
+        return;
+    };
+    let path = resolve_path(config_dir, &name);
+    if path.canonicalize().unwrap_or_else(|_| path.clone()) == *main_config {
+        state.error = Some(
+            "That is your main config. Pick a separate file like monitors.conf"
+                .to_string(),
+        );
+        return;
+    }
+    if let Err(e) = check_writable(&path) {
+        state.error =
+            Some(format!("xwlm couldn't save monitor settings there: {e}"));
+        return;
+    }
+    match attempt_extraction(state.compositor, &name) {
+        Some(result) => {
+            state.input = TextInput::new(result.output_path.clone());
+            state.extraction = Some(result);
+            state.rename = None;
+            state.error = None;
+        }
+        None => {
+            state.error = Some(format!(
+                "Couldn't plan the extraction into {}",
+                path.display()
+            ));
+        }
+    }
+}
+
+/// Creates the monitor config, with its parent directories, carrying a note
+/// on how to load it from the main config followed by `starter`.
+fn create_config_file(
+    path: &Path,
+    compositor: Compositor,
+    starter: &str,
+) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let display = path.display();
+    let load_hint = match compositor {
+        Compositor::Hyprland => {
+            format!("# Load it from hyprland.conf with: source = {display}\n")
+        }
+        Compositor::Sway => {
+            format!("# Load it from the sway config with: include {display}\n")
+        }
+        _ => String::new(),
+    };
+    std::fs::write(
+        path,
+        format!("# Monitor settings managed by xwlm.\n{load_hint}\n{starter}"),
+    )
+}
+
+/// Lines of the starter config shown before the file is created.
+const PREVIEW_LINES: usize = 10;
+
 const LOGO: &[&str] = &[
     r"░██    ░██ ░██       ░██ ░██         ░███     ░███ ",
     r" ░██  ░██  ░██       ░██ ░██         ░████   ░████ ",
@@ -285,6 +465,7 @@ const LOGO: &[&str] = &[
 fn render(frame: &mut Frame, state: &SetupState) {
     match state.phase {
         SetupPhase::Extraction => render_extraction(frame, state),
+        SetupPhase::Preview => render_preview(frame, state),
         SetupPhase::Manual => render_manual(frame, state),
     }
 }
@@ -292,7 +473,9 @@ fn render(frame: &mut Frame, state: &SetupState) {
 fn render_logo(frame: &mut Frame, area: Rect) {
     let logo_lines: Vec<Line> = LOGO
         .iter()
-        .map(|line| Line::from(Span::styled(*line, Style::default().fg(Color::Cyan))))
+        .map(|line| {
+            Line::from(Span::styled(*line, Style::default().fg(Color::Cyan)))
+        })
         .collect();
     frame.render_widget(Paragraph::new(logo_lines), area);
 }
@@ -367,13 +550,17 @@ fn render_extraction(frame: &mut Frame, state: &SetupState) {
             Style::default().fg(Color::Cyan),
         ));
         frame.render_widget(Paragraph::new(path_line), files_area);
-
-        frame.render_widget(Paragraph::new(""), output_area);
     } else {
         let desc = Paragraph::new(Line::from(Span::styled(
             format!(
-                "Found {} monitor config line(s) in:",
-                extraction.monitor_count
+                "Found {} monitor entr{} in {} file(s):",
+                extraction.monitor_count,
+                if extraction.monitor_count == 1 {
+                    "y"
+                } else {
+                    "ies"
+                },
+                extraction.source_files.len()
             ),
             Style::default().fg(Color::White),
         )));
@@ -382,18 +569,49 @@ fn render_extraction(frame: &mut Frame, state: &SetupState) {
         let file_lines: Vec<Line> = extraction
             .source_files
             .iter()
-            .map(|f| {
-                Line::from(Span::styled(
-                    format!("  {f}"),
-                    Style::default().fg(Color::Cyan),
-                ))
+            .map(|(f, count)| {
+                let detail = match count {
+                    0 => "  workspace rules only".to_string(),
+                    1 => "  1 monitor".to_string(),
+                    n => format!("  {n} monitors"),
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("  {f}"),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(detail, Style::default().fg(Color::DarkGray)),
+                ])
             })
             .collect();
         frame.render_widget(Paragraph::new(file_lines), files_area);
+    }
 
+    if let Some(ref input) = state.rename {
+        let (before, cursor_char, rest) = input.split_at_cursor();
         let output = Paragraph::new(Line::from(vec![
-            Span::styled("Consolidate to: ", Style::default().fg(Color::DarkGray)),
-            Span::styled(&extraction.output_path, Style::default().fg(Color::Cyan)),
+            Span::styled(
+                "Consolidate to: ",
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(before, Style::default().fg(Color::White)),
+            Span::styled(
+                cursor_char,
+                Style::default().fg(Color::Black).bg(Color::White),
+            ),
+            Span::styled(rest, Style::default().fg(Color::White)),
+        ]));
+        frame.render_widget(output, output_area);
+    } else if !extraction.already_consolidated {
+        let output = Paragraph::new(Line::from(vec![
+            Span::styled(
+                "Consolidate to: ",
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                &extraction.output_path,
+                Style::default().fg(Color::Cyan),
+            ),
         ]));
         frame.render_widget(output, output_area);
     }
@@ -406,11 +624,27 @@ fn render_extraction(frame: &mut Frame, state: &SetupState) {
             ))),
             info_area,
         );
+    } else if state.rename.is_some() {
+        let hints = vec![
+            Span::styled("Enter ", Style::default().fg(Color::Cyan)),
+            Span::styled(
+                "use this file, relative to the main config  ",
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled("Esc ", Style::default().fg(Color::Cyan)),
+            Span::styled("cancel", Style::default().fg(Color::DarkGray)),
+        ];
+        frame.render_widget(Paragraph::new(Line::from(hints)), info_area);
     } else {
         let mut hints = vec![
             Span::styled("Enter ", Style::default().fg(Color::Cyan)),
             Span::styled("confirm  ", Style::default().fg(Color::DarkGray)),
         ];
+        hints.push(Span::styled("e ", Style::default().fg(Color::Cyan)));
+        hints.push(Span::styled(
+            "other file  ",
+            Style::default().fg(Color::DarkGray),
+        ));
         hints.push(Span::styled("m ", Style::default().fg(Color::Cyan)));
         hints.push(Span::styled(
             "manual  ",
@@ -422,10 +656,122 @@ fn render_extraction(frame: &mut Frame, state: &SetupState) {
     }
 }
 
+/// Unchanged lines shown around each change in the extraction preview.
+const PREVIEW_CONTEXT: usize = 2;
+
+fn render_preview(frame: &mut Frame, state: &SetupState) {
+    let [_, center, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Max(100),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+
+    let [title_area, desc_area, diff_area, info_area] = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Length(2),
+        Constraint::Fill(1),
+        Constraint::Length(2),
+    ])
+    .areas(center);
+
+    render_title(frame, title_area);
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            format!(
+                "Extraction will write {} file(s). Review the changes before confirming:",
+                state.preview.len()
+            ),
+            Style::default().fg(Color::White),
+        ))),
+        desc_area,
+    );
+
+    let mut lines = Vec::new();
+    for (path, file_diff) in &state.preview {
+        let is_new = file_diff.iter().all(|l| matches!(l, DiffLine::Added(_)));
+        lines.push(Line::from(vec![
+            Span::styled(
+                path.display().to_string(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                if is_new { "  (new content)" } else { "" },
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+        let shown = diff::with_context(file_diff, PREVIEW_CONTEXT);
+        if shown.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  no changes",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for line in shown {
+            lines.push(match line {
+                Some(DiffLine::Same(text)) => Line::from(Span::styled(
+                    format!("   {text}"),
+                    Style::default().fg(Color::DarkGray),
+                )),
+                Some(DiffLine::Removed(text)) => Line::from(Span::styled(
+                    format!(" - {text}"),
+                    Style::default().fg(Color::Red),
+                )),
+                Some(DiffLine::Added(text)) => Line::from(Span::styled(
+                    format!(" + {text}"),
+                    Style::default().fg(Color::Green),
+                )),
+                None => Line::from(Span::styled(
+                    "   ⋯",
+                    Style::default().fg(Color::DarkGray),
+                )),
+            });
+        }
+        lines.push(Line::from(""));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((state.preview_scroll, 0)),
+        diff_area,
+    );
+
+    let hints = vec![
+        Span::styled("Enter ", Style::default().fg(Color::Cyan)),
+        Span::styled("write files  ", Style::default().fg(Color::DarkGray)),
+        Span::styled("↑↓ ", Style::default().fg(Color::Cyan)),
+        Span::styled("scroll  ", Style::default().fg(Color::DarkGray)),
+        Span::styled("Esc ", Style::default().fg(Color::Cyan)),
+        Span::styled(
+            "cancel, pick a file manually",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+    frame.render_widget(Paragraph::new(Line::from(hints)), info_area);
+}
+
 fn render_manual(frame: &mut Frame, state: &SetupState) {
+    let preview: Vec<&str> = if state.create_offered {
+        state.starter.lines().take(PREVIEW_LINES).collect()
+    } else {
+        Vec::new()
+    };
+    let preview_height = if preview.is_empty() {
+        0
+    } else {
+        preview.len() as u16 + 2
+    };
+
     let [_, center_v, _] = Layout::vertical([
         Constraint::Fill(1),
-        Constraint::Max(19),
+        Constraint::Max(19 + preview_height),
         Constraint::Fill(1),
     ])
     .areas(frame.area());
@@ -437,12 +783,21 @@ fn render_manual(frame: &mut Frame, state: &SetupState) {
     ])
     .areas(center_v);
 
-    let [logo_area, title_area, desc_area, warning_area, input_area, info_area] = Layout::vertical([
+    let [
+        logo_area,
+        title_area,
+        desc_area,
+        warning_area,
+        input_area,
+        preview_area,
+        info_area,
+    ] = Layout::vertical([
         Constraint::Length(9),
         Constraint::Length(2),
         Constraint::Length(2),
         Constraint::Length(1),
         Constraint::Length(3),
+        Constraint::Length(preview_height),
         Constraint::Length(2),
     ])
     .areas(center);
@@ -465,9 +820,7 @@ fn render_manual(frame: &mut Frame, state: &SetupState) {
     )));
     frame.render_widget(warning, warning_area);
 
-    let (before, after) = state.input.split_at(state.cursor);
-    let cursor_char = if after.is_empty() { " " } else { &after[..1] };
-    let rest = if after.len() > 1 { &after[1..] } else { "" };
+    let (before, cursor_char, rest) = state.input.split_at_cursor();
 
     let input_line = Line::from(vec![
         Span::styled(before, Style::default().fg(Color::White)),
@@ -484,7 +837,28 @@ fn render_manual(frame: &mut Frame, state: &SetupState) {
         .border_style(Style::default().fg(Color::Blue))
         .title(" Path ");
 
-    frame.render_widget(Paragraph::new(input_line).block(input_block), input_area);
+    frame.render_widget(
+        Paragraph::new(input_line).block(input_block),
+        input_area,
+    );
+
+    if !preview.is_empty() {
+        let lines: Vec<Line> = preview
+            .iter()
+            .map(|l| {
+                Line::from(Span::styled(*l, Style::default().fg(Color::White)))
+            })
+            .collect();
+        let preview_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(" Proposed contents ");
+        frame.render_widget(
+            Paragraph::new(lines).block(preview_block),
+            preview_area,
+        );
+    }
 
     if let Some(ref err) = state.error {
         frame.render_widget(
@@ -1,30 +1,52 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     path::PathBuf,
+    process::Child,
     sync::mpsc::{SendError, SyncSender},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
-use ratatui::widgets::ListState;
-use wlx_monitors::{WlMonitor, WlMonitorAction};
+use ratatui::{style::Color, widgets::ListState};
+use serde::{Deserialize, Serialize};
+use wlx_monitors::{WlMonitor, WlMonitorAction, WlTransform};
 
 use crate::{
     compositor::{
-        self,
-        format::{reload, save_monitor_config},
-        position::get_position,
+        self, Compositor, ScaleLimits, SwayTuning, color_temp,
+        custom_mode::{self, CustomMode},
+        format::{SaveContext, reload, save_monitor_config},
+        identify,
+        monitor_config::{
+            MonitorDrift, find_drift, parse_monitor_config, saved_entry,
+        },
+        position::{get_mirror_source, get_position},
+        wallpaper,
         workspace_config::{WorkspaceRule, parse_workspace_config},
     },
-    constants::{REPEAT_WINDOW_MS, TRANSFORMS},
-    utils::effective_dimensions,
+    constants::{
+        BATCH_TIMEOUT_MS, COLOR_TEMP_STEP, IDENTIFY_STEP_MS, MAP_PAN_STEP_X,
+        MAP_PAN_STEP_Y, MIN_COLOR_TEMP, NEUTRAL_COLOR_TEMP, REPEAT_WINDOW_MS,
+        RISKY_REFRESH_RATIO, SAFE_APPLY_SECS, TRANSFORMS,
+    },
+    draft::Draft,
+    geometry::{self, MonitorRect},
+    monitor_memory::MonitorMemory,
+    text_input::TextInput,
+    utils::{
+        ModeRow, WriteAccessError, basic_color, check_writable,
+        effective_dimensions, expand_tilde, logical_size, mode_rows,
+        monitor_resolution, transformed_dimensions,
+    },
+    xwlm_config::{self, Config},
 };
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Panel {
     Monitor,
     Mode,
     Workspace,
     Scale,
+    Color,
     Transform,
 }
 
@@ -36,7 +58,111 @@ pub enum PositionDirection {
     Down,
 }
 
+/// A jump in the focused list, bigger than one arrow press.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ListJump {
+    PageUp,
+    PageDown,
+    First,
+    Last,
+}
+
+/// A sweeping command waiting for the user to press `y`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Confirm {
+    SoloMonitor,
+    RotateAll,
+    RestoreDraft,
+    RevertToFile,
+    ResolveOverlaps,
+    CollapseDuplicates,
+}
+
+/// What a safe-applied change is undone to if it isn't kept in time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Revert {
+    Mode {
+        width: i32,
+        height: i32,
+        refresh_rate: i32,
+    },
+    Scale(f64),
+    Transform(WlTransform),
+    ModeAndScale {
+        width: i32,
+        height: i32,
+        refresh_rate: i32,
+        scale: f64,
+    },
+}
+
+/// An arrangement stored in a slot for quick recall: where each monitor
+/// was (or was about to be moved) and its scale.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    pub positions: HashMap<String, (i32, i32)>,
+    pub scales: HashMap<String, f64>,
+}
+
+impl Snapshot {
+    /// The moves that bring `monitors` (name and current position, in index
+    /// order) back to this arrangement. Monitors that aren't in the
+    /// snapshot, or are already in place, stay where they are.
+    fn pending_positions(
+        &self,
+        monitors: &[(&str, (i32, i32))],
+    ) -> HashMap<usize, (i32, i32)> {
+        monitors
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, (name, current))| {
+                let pos = *self.positions.get(*name)?;
+                (pos != *current).then_some((idx, pos))
+            })
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug)]
+pub struct PendingRevert {
+    pub monitor: String,
+    pub revert: Revert,
+    pub deadline: Instant,
+}
+
+/// Monitors being pointed out one after another, on the map and on the
+/// screens themselves.
+#[derive(Debug)]
+pub struct Identify {
+    pub started: Instant,
+    /// Monitor indices, left to right.
+    pub order: Vec<usize>,
+    /// Name bars shown on the outputs.
+    bars: Vec<Child>,
+}
+
+/// Edits made in the panels that haven't been applied yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PendingSummary {
+    pub moved: usize,
+    pub workspaces: usize,
+    pub scale: bool,
+    pub transform: bool,
+    /// Other monitors with a scale or transform waiting.
+    pub staged: usize,
+}
+
+impl PendingSummary {
+    pub fn count(self) -> usize {
+        self.moved
+            + self.workspaces
+            + usize::from(self.scale)
+            + usize::from(self.transform)
+            + self.staged
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct WorkspaceAssignment {
     pub id: usize,
     pub monitor_idx: Option<usize>,
@@ -47,9 +173,12 @@ pub struct WorkspaceAssignment {
 #[derive(Debug)]
 pub struct App {
     pub monitors: Vec<WlMonitor>,
+    /// Set once the backend has sent its initial monitor list.
+    pub monitors_received: bool,
     pub selected_monitor: usize,
     pub panel: Panel,
     pub compositor: compositor::Compositor,
+    pub compositor_version: Option<&'static str>,
     pub wlx_action_handler: SyncSender<WlMonitorAction>,
     pub workspace_assignments: Vec<WorkspaceAssignment>,
     pub comp_monitor_config_path: PathBuf,
@@ -58,12 +187,127 @@ pub struct App {
     pub pending_positions: HashMap<usize, (i32, i32)>,
     pub pending_workspaces: HashMap<usize, WorkspaceAssignment>,
     pub pending_scale: f64,
+    /// Scales and transforms picked for monitors while another one is
+    /// selected, restored when they are selected again.
+    pub pending_scales: HashMap<usize, f64>,
+    pub pending_transforms: HashMap<usize, WlTransform>,
+    /// Color temperature in Kelvin set on each monitor through xwlm.
+    pub color_temps: BTreeMap<String, u32>,
+    pub wallpaper_command: Option<String>,
+    /// Wallpaper image per monitor name.
+    pub wallpapers: BTreeMap<String, String>,
+    /// Wallpaper commands started per monitor, checked for failures.
+    wallpaper_jobs: HashMap<String, wallpaper::Job>,
+    pub pending_color_temp: u32,
+    pub scale_limits: ScaleLimits,
+    pub position_step: i32,
+    pub scale_step: f64,
+    /// Monitor whose position the others are shown relative to.
+    pub relative_to: Option<String>,
+    /// Comment written at the top of the monitor config.
+    config_header: String,
+    /// Where each monitor was when it first appeared, so config entries
+    /// placed with `auto` stay that way until the monitor is moved.
+    startup_positions: HashMap<String, (i32, i32)>,
+    /// Show the monitors as a table instead of the spatial map.
+    pub table_view: bool,
+    /// Show the workspaces assigned to each monitor inside its rectangle.
+    pub show_workspace_badges: bool,
+    /// Show every global key and the panel's keys over several footer rows.
+    pub expanded_footer: bool,
+    /// Version and build details shown in a modal.
+    pub show_version: bool,
+    /// The map legend is open.
+    pub show_legend: bool,
+    /// Quit once the changes sent by apply-and-quit have settled and the
+    /// config is written.
+    pub quit_after_apply: bool,
     pub map_zoom: f64,
+    /// How far the zoomed map is scrolled, in map cells.
+    pub map_pan_x: i32,
+    pub map_pan_y: i32,
     pub transform_state: ListState,
     pub mode_state: ListState,
+    /// List every mode the driver reports, duplicates included.
+    pub show_raw_modes: bool,
+    /// List modes under a header per resolution.
+    pub group_modes: bool,
     pub workspace_state: ListState,
+    /// Visible rows of each list as last rendered, used as the page size.
+    pub mode_list_height: usize,
+    pub transform_list_height: usize,
+    pub workspace_list_height: usize,
     pub pending_last_toggle_monitor: bool,
+    /// A disabled monitor shown as a ghost at the position it will be
+    /// enabled at, waiting for Enter.
+    pub pending_enable: Option<usize>,
+    /// Monitors picked with Space to move together with the selected one.
+    pub group: BTreeSet<usize>,
+    /// Arrangements stored with Shift+1–9 this session.
+    pub slots: HashMap<u8, Snapshot>,
+    pub identify: Option<Identify>,
+    pub pending_confirm: Option<Confirm>,
+    /// A risky mode (by index into the selected monitor's modes) that was
+    /// warned about and applies on the next Enter.
+    pub pending_risky_mode: Option<usize>,
+    /// Undo mode, scale and transform changes unless kept in time.
+    pub safe_apply: bool,
+    /// Write the config as soon as a change is applied, rather than on `s`.
+    pub autosave: bool,
+    /// Lists wrap around at their ends rather than stopping there.
+    pub wrap_lists: bool,
+    /// Draw RGB colors with the nearest basic terminal color instead.
+    pub basic_colors: bool,
+    /// The last safe-applied change, waiting to be kept or reverted.
+    pub pending_revert: Option<PendingRevert>,
+    /// Unapplied edits left by a previous run, offered for restoring.
+    pub restorable_draft: Option<Draft>,
+    /// The pending edits as last written to the draft file.
+    draft: Draft,
     pub error_message: Option<String>,
+    pub status_message: Option<String>,
+    pub count_prefix: Option<u32>,
+    pub search: Option<TextInput>,
+    /// The custom mode being typed for the selected monitor.
+    pub custom_mode: Option<TextInput>,
+    /// Prompt for the name of a monitor to turn on or off.
+    pub toggle_prompt: Option<TextInput>,
+    /// The wallpaper path being typed for the selected monitor.
+    pub wallpaper_prompt: Option<TextInput>,
+    /// Monitors the saved config mirrors onto another output, keyed by name.
+    pub config_mirrors: HashMap<String, String>,
+    /// Last applied placement per monitor, used when the config has none.
+    pub monitor_memory: MonitorMemory,
+    /// Whether drafts and monitor memory are read from and written to
+    /// disk. Off for apps built by tests.
+    persist_state: bool,
+    /// Write monitors by description rather than connector name.
+    pub match_by_description: bool,
+    /// Monitor descriptions by connector name, where the compositor has them.
+    pub monitor_descriptions: HashMap<String, String>,
+    /// Sway's `max_render_time` and `render_bit_depth` by monitor name.
+    pub sway_tuning: HashMap<String, SwayTuning>,
+    /// Where each existing workspace actually is, by workspace id.
+    pub live_workspaces: HashMap<usize, String>,
+    /// Monitors changed by a multi-monitor action that the compositor hasn't
+    /// confirmed yet, with the number of changes each still owes. Saving
+    /// (and the reload it triggers) waits for all of them so one
+    /// arrangement change reloads the compositor once.
+    pub in_flight: HashMap<String, usize>,
+    /// Differences between the live monitors and the saved config, found
+    /// at startup. Advisory only; cleared once the user picks a side.
+    pub drift: Vec<MonitorDrift>,
+    /// The drift the user last dismissed, kept hidden until it changes.
+    dismissed_drift: Vec<MonitorDrift>,
+    /// Hyprland monitors with more than one entry in the monitor config,
+    /// with their entry counts.
+    pub duplicate_entries: Vec<(String, usize)>,
+    /// The user agreed to collapse the duplicates, done at the next write.
+    collapse_pending: bool,
+    /// The 90° or 270° rotation each monitor was last in, so the portrait
+    /// toggle turns it back the same way.
+    portrait_transforms: HashMap<String, WlTransform>,
+    in_flight_since: Option<Instant>,
 
     last_move_time: Instant,
     move_repeat_count: u32,
@@ -74,13 +318,35 @@ pub struct App {
 impl App {
     pub fn new(
         wlx_action_handler: SyncSender<WlMonitorAction>,
-        comp_monitor_config_path: PathBuf,
-        comp_workspace_count: usize,
+        config: &Config,
     ) -> Self {
         let comp = compositor::detect();
-        let initial_workspaces = Some(parse_workspace_config(comp, &comp_monitor_config_path));
+        let mut app = Self::build(wlx_action_handler, config, comp);
+        app.compositor_version = compositor::version(comp);
+        app.monitor_memory = MonitorMemory::load();
+        app.persist_state = true;
+        app.initial_workspaces =
+            Some(parse_workspace_config(comp, &app.comp_monitor_config_path));
+        // Warn now rather than after the user has arranged everything
+        app.error_message = check_writable(&app.comp_monitor_config_path)
+            .err()
+            .map(|e| format!("Changes can't be saved: {e}"));
+        app
+    }
+
+    /// The app for `comp` without querying the compositor or reading
+    /// anything from disk, which `new` adds on top.
+    fn build(
+        wlx_action_handler: SyncSender<WlMonitorAction>,
+        config: &Config,
+        comp: Compositor,
+    ) -> Self {
+        let comp_monitor_config_path = config.monitor_config_path.clone();
+        let scale_limits = comp
+            .scale_limits()
+            .with_overrides(config.min_scale, config.max_scale);
 
-        let workspace_assignments = (1..=comp_workspace_count)
+        let workspace_assignments = (1..=config.workspace_count(comp))
             .map(|id| WorkspaceAssignment {
                 id,
                 monitor_idx: None,
@@ -91,31 +357,98 @@ impl App {
 
         Self {
             monitors: Vec::new(),
+            monitors_received: false,
             selected_monitor: 0,
             panel: Panel::Monitor,
             compositor: comp,
+            compositor_version: None,
             wlx_action_handler,
             needs_save: false,
             pending_positions: HashMap::new(),
             pending_workspaces: HashMap::new(),
             workspace_assignments,
             workspace_state: ListState::default().with_selected(Some(0)),
+            mode_list_height: 0,
+            transform_list_height: 0,
+            workspace_list_height: 0,
             map_zoom: 1.0,
+            map_pan_x: 0,
+            map_pan_y: 0,
             pending_scale: 1.0,
+            pending_scales: HashMap::new(),
+            pending_transforms: HashMap::new(),
+            color_temps: config.color_temperature.clone(),
+            wallpaper_command: config.wallpaper_command.clone(),
+            wallpapers: config.wallpapers.clone(),
+            wallpaper_jobs: HashMap::new(),
+            pending_color_temp: NEUTRAL_COLOR_TEMP,
+            scale_limits,
+            position_step: config.position_step,
+            scale_step: config.scale_step,
+            relative_to: config.relative_to.clone(),
+            config_header: config.header().to_string(),
+            startup_positions: HashMap::new(),
+            table_view: false,
+            show_workspace_badges: false,
+            expanded_footer: false,
+            show_version: false,
+            show_legend: false,
+            quit_after_apply: false,
             transform_state: ListState::default().with_selected(Some(0)),
             mode_state: ListState::default().with_selected(Some(0)),
+            show_raw_modes: false,
+            group_modes: false,
             pending_last_toggle_monitor: false,
+            pending_enable: None,
+            group: BTreeSet::new(),
+            slots: HashMap::new(),
+            identify: None,
+            pending_confirm: None,
+            pending_risky_mode: None,
+            safe_apply: config.safe_apply,
+            autosave: config.autosave,
+            wrap_lists: config.wrap_lists,
+            basic_colors: config.basic_colors,
+            pending_revert: None,
+            restorable_draft: None,
+            draft: Draft::default(),
             error_message: None,
+            status_message: None,
+            count_prefix: None,
+            search: None,
+            custom_mode: None,
+            toggle_prompt: None,
+            wallpaper_prompt: None,
+            config_mirrors: HashMap::new(),
+            monitor_memory: MonitorMemory::default(),
+            persist_state: false,
+            match_by_description: config.match_by_description,
+            monitor_descriptions: HashMap::new(),
+            sway_tuning: HashMap::new(),
+            live_workspaces: HashMap::new(),
+            in_flight: HashMap::new(),
+            drift: Vec::new(),
+            dismissed_drift: Vec::new(),
+            duplicate_entries: Vec::new(),
+            collapse_pending: false,
+            portrait_transforms: HashMap::new(),
+            in_flight_since: None,
             comp_monitor_config_path,
             last_move_time: Instant::now(),
             last_move_direction: None,
             move_repeat_count: 0,
-            initial_workspaces,
+            initial_workspaces: None,
         }
     }
 
     pub fn set_monitors(&mut self, monitors: Vec<WlMonitor>) {
+        let first_state = !self.monitors_received;
+        let previous = self.monitor_names();
+        self.stage_panel_values();
         self.monitors = monitors;
+        self.reindex_pending(&previous);
+        self.record_startup_positions();
+        self.monitors_received = true;
         if !self.monitors.is_empty() {
             self.selected_monitor = 0;
             self.mode_state.select(Some(0));
@@ -123,729 +456,3292 @@ impl App {
         }
         self.resolve_initial_workspaces();
         self.validate_workspace_assignments();
+        if first_state {
+            self.offer_draft();
+        }
+        self.refresh_external_state();
     }
 
-    pub fn update_monitor(&mut self, monitor: WlMonitor) {
-        if let Some(existing_monitor) = self.monitors.iter_mut().find(|m| m.name == monitor.name) {
-            *existing_monitor = monitor;
+    /// Re-reads what xwlm learns outside the Wayland protocol: monitor
+    /// descriptions and the saved config, which may have been edited while
+    /// the terminal was in the background.
+    pub fn refresh_external_state(&mut self) {
+        if !self.monitors_received {
+            return;
+        }
+        self.load_monitor_descriptions();
+        self.sway_tuning = compositor::sway_tuning(
+            self.compositor,
+            &self.comp_monitor_config_path,
+        );
+        self.live_workspaces = compositor::live_workspaces(self.compositor);
+        self.load_config_mirrors();
+        let drift = find_drift(
+            &parse_monitor_config(
+                self.compositor,
+                &self.comp_monitor_config_path,
+            ),
+            &self.monitors,
+            &self.monitor_descriptions,
+        );
+        if drift == self.dismissed_drift {
+            self.drift.clear();
         } else {
-            self.monitors.push(monitor);
-            self.sanitize_selection();
-        };
-    }
-
-    pub fn remove_monitor(&mut self, name: &str) {
-        let removed_idx = self.monitors.iter().position(|m| m.name == name);
-        self.monitors.retain(|m| m.name != name);
-
-        if let Some(idx) = removed_idx {
-            self.pending_positions.remove(&idx);
-            for key in self.pending_positions.keys().copied().collect::<Vec<_>>() {
-                if key > idx
-                    && let Some(pos) = self.pending_positions.remove(&key)
-                {
-                    self.pending_positions.insert(key - 1, pos);
-                }
-            }
-
-            if self.selected_monitor >= self.monitors.len() {
-                self.selected_monitor = self.monitors.len().saturating_sub(1);
-            }
-            self.sync_panel_state();
+            self.dismissed_drift.clear();
+            self.drift = drift;
         }
+        self.find_duplicate_entries();
     }
 
-    fn sanitize_selection(&mut self) {
-        if self.monitors.is_empty() {
-            self.selected_monitor = 0;
-        } else if self.selected_monitor >= self.monitors.len() {
-            self.selected_monitor = self.monitors.len() - 1;
+    /// Looks for monitors listed more than once in the monitor config and
+    /// offers to collapse them when the list changed. The list is only
+    /// taken once the offer is shown, so one held back by another prompt
+    /// is made on a later refresh.
+    fn find_duplicate_entries(&mut self) {
+        let duplicates = compositor::duplicate_monitors(
+            self.compositor,
+            &self.comp_monitor_config_path,
+        );
+        if duplicates == self.duplicate_entries {
+            return;
+        }
+        if duplicates.is_empty() {
+            self.duplicate_entries.clear();
+            self.collapse_pending = false;
+        } else if self.pending_confirm.is_none() {
+            self.duplicate_entries = duplicates;
+            self.pending_confirm = Some(Confirm::CollapseDuplicates);
         }
     }
 
-    pub fn selected_monitor(&self) -> Option<&WlMonitor> {
-        self.monitors.get(self.selected_monitor)
+    /// Collapses the duplicates when the config is next written.
+    fn accept_collapse(&mut self) {
+        self.collapse_pending = true;
+        self.needs_save = true;
+        self.set_status("Overridden monitor entries go at the next save");
     }
 
-    pub fn display_position(&self, idx: usize) -> (i32, i32) {
-        if let Some(&pos) = self.pending_positions.get(&idx) {
-            return pos;
+    /// Drops the entries Hyprland overrides from the monitor config, so the
+    /// write that follows keeps the ones it uses.
+    fn collapse_duplicate_entries(&mut self) -> bool {
+        let path = &self.comp_monitor_config_path;
+        match compositor::collapse_duplicates(self.compositor, path) {
+            Ok(_) => {
+                self.collapse_pending = false;
+                self.duplicate_entries.clear();
+                true
+            }
+            Err(e) => {
+                let e = WriteAccessError::new(path, e);
+                self.set_error(format!("Failed to collapse duplicates: {e}"));
+                false
+            }
         }
-        self.monitors
-            .get(idx)
-            .map(|m| (m.position.x, m.position.y))
-            .unwrap_or((0, 0))
     }
 
-    pub fn has_pending_positions(&self) -> bool {
-        !self.pending_positions.is_empty()
+    /// Asks whether to restore edits a previous run didn't get to apply.
+    fn offer_draft(&mut self) {
+        if !self.persist_state {
+            return;
+        }
+        let Some(draft) = Draft::load() else {
+            return;
+        };
+        self.restorable_draft = Some(draft);
+        self.pending_confirm = Some(Confirm::RestoreDraft);
     }
 
-    pub fn set_error(&mut self, msg: impl Into<String>) {
-        self.error_message = Some(msg.into());
+    fn restore_draft(&mut self) {
+        let Some(draft) = self.restorable_draft.take() else {
+            return;
+        };
+        for (name, &pos) in &draft.positions {
+            if let Some(idx) =
+                self.monitors.iter().position(|m| &m.name == name)
+            {
+                self.pending_positions.insert(idx, pos);
+            }
+        }
+        for ws in &draft.workspaces {
+            let Some(ws_idx) = self
+                .workspace_assignments
+                .iter()
+                .position(|w| w.id == ws.id)
+            else {
+                continue;
+            };
+            let monitor_idx = ws
+                .monitor_idx
+                .and_then(|idx| draft.monitors.get(idx))
+                .and_then(|name| {
+                    self.monitors.iter().position(|m| &m.name == name)
+                });
+            self.pending_workspaces.insert(
+                ws_idx,
+                WorkspaceAssignment {
+                    monitor_idx,
+                    ..ws.clone()
+                },
+            );
+        }
+        self.set_status(format!(
+            "Restored {} unapplied change(s)",
+            draft.change_count()
+        ));
     }
 
-    pub fn clear_error(&mut self) {
-        self.error_message = None;
+    fn current_draft(&self) -> Draft {
+        Draft {
+            monitors: self.monitors.iter().map(|m| m.name.clone()).collect(),
+            positions: self
+                .pending_positions
+                .iter()
+                .filter_map(|(&idx, &pos)| {
+                    Some((self.monitors.get(idx)?.name.clone(), pos))
+                })
+                .collect(),
+            workspaces: self.pending_workspaces.values().cloned().collect(),
+        }
     }
 
-    pub fn zoom_in(&mut self) {
-        self.map_zoom = (self.map_zoom + 0.1).min(5.0);
+    /// Writes the pending edits to the draft file, or removes it once
+    /// nothing is pending.
+    pub fn sync_draft(&mut self) {
+        // Leave the previous run's draft alone until the user decides
+        if !self.persist_state || self.restorable_draft.is_some() {
+            return;
+        }
+        let draft = self.current_draft();
+        if draft.positions == self.draft.positions
+            && draft.workspaces == self.draft.workspaces
+        {
+            return;
+        }
+        let result = if draft.is_empty() {
+            Draft::discard()
+        } else {
+            draft.save()
+        };
+        if let Err(e) = result {
+            self.set_error(format!("Failed to save draft: {e}"));
+        }
+        self.draft = draft;
     }
 
-    pub fn zoom_out(&mut self) {
-        self.map_zoom = (self.map_zoom - 0.1).max(0.2);
+    /// Has the compositor re-read the saved config, undoing the drift.
+    pub fn reapply_config(&mut self) {
+        if self.drift.is_empty() {
+            return;
+        }
+        self.drift.clear();
+        match reload(self.compositor) {
+            Ok(()) => self.set_status("Reloaded the saved monitor config"),
+            Err(e) => self.set_error(format!(
+                "Failed to reload {}: {e}",
+                self.compositor.label()
+            )),
+        }
     }
 
-    pub fn scale_up(&mut self) {
-        self.pending_scale = (self.pending_scale + 0.01).min(10.0);
+    /// Steps the selected monitor's Sway `render_bit_depth` through unset,
+    /// 6, 8 and 10 and saves it.
+    pub fn cycle_render_bit_depth(&mut self) {
+        if !self.compositor.capabilities().bitdepth() {
+            self.set_error(format!(
+                "{} has no render bit depth setting",
+                self.compositor.label()
+            ));
+            return;
+        }
+        self.edit_sway_tuning(|tuning| {
+            tuning.render_bit_depth = match tuning.render_bit_depth {
+                None => Some(6),
+                Some(6) => Some(8),
+                Some(8) => Some(10),
+                _ => None,
+            };
+            match tuning.render_bit_depth {
+                Some(depth) => format!("render_bit_depth {depth}"),
+                None => "render_bit_depth unset".to_string(),
+            }
+        });
     }
 
-    pub fn scale_down(&mut self) {
-        self.pending_scale = (self.pending_scale - 0.01).max(0.5);
+    /// Steps the selected monitor's Sway `max_render_time` through unset,
+    /// off, 1, 2, 4 and 8 ms and saves it.
+    pub fn cycle_max_render_time(&mut self) {
+        if !self.compositor.capabilities().max_render_time() {
+            self.set_error(format!(
+                "{} has no max render time setting",
+                self.compositor.label()
+            ));
+            return;
+        }
+        self.edit_sway_tuning(|tuning| {
+            tuning.max_render_time = match tuning.max_render_time.as_deref() {
+                None => Some("off"),
+                Some("off") => Some("1"),
+                Some("1") => Some("2"),
+                Some("2") => Some("4"),
+                Some("4") => Some("8"),
+                _ => None,
+            }
+            .map(str::to_string);
+            match &tuning.max_render_time {
+                Some(time) => format!("max_render_time {time}"),
+                None => "max_render_time unset".to_string(),
+            }
+        });
     }
 
-    fn enabled_count(&self) -> usize {
-        self.monitors.iter().filter(|m| m.enabled).count()
+    /// Applies `edit` to the selected monitor's tuning and writes the
+    /// config. `edit` returns the status line describing the new value.
+    fn edit_sway_tuning(
+        &mut self,
+        edit: impl FnOnce(&mut SwayTuning) -> String,
+    ) {
+        let Some(monitor) = self.selected_monitor() else {
+            return;
+        };
+        let name = monitor.name.clone();
+        let tuning = self.sway_tuning.entry(name.clone()).or_default();
+        let msg = edit(tuning);
+        self.needs_save = true;
+        self.save_config();
+        self.set_status(format!("{name}: {msg}"));
     }
 
-    pub fn dismiss_warning(&mut self) {
-        self.pending_last_toggle_monitor = false;
+    /// Asks for confirmation before restoring every monitor to the config.
+    pub fn request_revert_to_file(&mut self) {
+        if self.monitors.is_empty() {
+            return;
+        }
+        self.pending_confirm = Some(Confirm::RevertToFile);
     }
 
-    pub fn toggle_monitor(&mut self) -> Result<(), SendError<WlMonitorAction>> {
-        if self.pending_last_toggle_monitor {
-            self.pending_last_toggle_monitor = false;
-            let Some(monitor) = self.monitors.get(self.selected_monitor) else {
-                return Ok(());
-            };
-            self.perform_toggle(&monitor.name.clone(), monitor.enabled)?;
+    /// Re-applies the saved config to the live monitors: power state, mode,
+    /// position and scale, as far as the file sets them. Drops pending edits.
+    fn revert_to_file(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        let saved = parse_monitor_config(
+            self.compositor,
+            &self.comp_monitor_config_path,
+        );
+        if saved.is_empty() {
+            self.set_error("The monitor config has no monitors to restore");
             return Ok(());
         }
+        self.reset_positions();
 
-        let Some(monitor) = self.monitors.get(self.selected_monitor) else {
-            return Ok(());
-        };
+        let mut actions = Vec::new();
+        for monitor in &self.monitors {
+            let Some(entry) =
+                saved_entry(&saved, monitor, &self.monitor_descriptions)
+            else {
+                continue;
+            };
+            let name = monitor.name.clone();
+            if entry.enabled != monitor.enabled {
+                actions.push(WlMonitorAction::Toggle {
+                    name: name.clone(),
+                    mode: None,
+                    position: entry.position.filter(|_| entry.enabled),
+                });
+            }
+            if !entry.enabled {
+                continue;
+            }
+            if let Some((width, height, refresh)) = entry.mode_for(monitor)
+                && let Some(mode) = monitor
+                    .modes
+                    .iter()
+                    .filter(|m| {
+                        m.resolution.width == width
+                            && m.resolution.height == height
+                    })
+                    .min_by_key(|m| match refresh {
+                        Some(r) => {
+                            ((m.refresh_rate as f64 - r).abs() * 1000.0) as i64
+                        }
+                        None => -(m.refresh_rate as i64),
+                    })
+                && !mode.is_current
+            {
+                actions.push(WlMonitorAction::SwitchMode {
+                    name: name.clone(),
+                    width,
+                    height,
+                    refresh_rate: mode.refresh_rate,
+                });
+            }
+            if let Some(transform) = entry.transform
+                && transform != monitor.transform
+            {
+                actions.push(WlMonitorAction::SetTransform {
+                    name: name.clone(),
+                    transform,
+                });
+            }
+            if let Some((x, y)) = entry.position
+                && (x, y) != (monitor.position.x, monitor.position.y)
+            {
+                actions.push(WlMonitorAction::SetPosition {
+                    name: name.clone(),
+                    x,
+                    y,
+                });
+            }
+            if let Some(scale) = entry.scale
+                && (scale - monitor.scale).abs() > 0.001
+            {
+                actions.push(WlMonitorAction::SetScale { name, scale });
+            }
+        }
 
-        if monitor.enabled && self.enabled_count() == 1 {
-            self.pending_last_toggle_monitor = true;
+        if actions.is_empty() {
+            self.set_status("Monitors already match the saved config");
             return Ok(());
         }
-        self.perform_toggle(&monitor.name.clone(), monitor.enabled)?;
-
+        for action in actions {
+            self.wlx_action_handler.send(action)?;
+        }
+        self.drift.clear();
+        self.set_status("Restored monitors from the saved config");
         Ok(())
     }
 
-    fn perform_toggle(
-        &mut self,
-        monitor_name: &str,
-        currently_enabled: bool,
-    ) -> Result<(), SendError<WlMonitorAction>> {
-        let will_enable = !currently_enabled;
-        let position = if will_enable {
-            let saved_pos = get_position(
-                self.compositor,
-                &self.comp_monitor_config_path,
-                monitor_name,
-            );
-
-            let (w, h) = self
-                .monitors
-                .iter()
-                .find(|m| m.name == monitor_name)
-                .map(effective_dimensions)
-                .unwrap_or((1920, 1080));
-
-            if let Some(saved) = saved_pos {
-                let pos = (saved.x, saved.y);
-                if self.position_overlaps(monitor_name, pos, (w, h)) {
-                    Some(self.calculate_closest_non_overlapping_position(monitor_name, pos, (w, h)))
-                } else {
-                    Some(pos)
-                }
-            } else {
-                Some(self.calculate_non_overlapping_position(monitor_name))
-            }
-        } else {
-            None
-        };
-
-        self.wlx_action_handler.send(WlMonitorAction::Toggle {
-            name: monitor_name.to_string(),
-            mode: None,
-            position,
-        })?;
-
+    /// Overwrites the saved config with the live monitor state.
+    pub fn write_live_config(&mut self) {
+        if self.drift.is_empty() {
+            return;
+        }
+        self.drift.clear();
         self.needs_save = true;
+        self.write_config(true);
+        self.set_status("Saved the live monitor state to the config");
+    }
 
-        Ok(())
+    pub fn dismiss_drift(&mut self) {
+        self.dismissed_drift = std::mem::take(&mut self.drift);
     }
 
-    fn position_overlaps(&self, exclude_name: &str, pos: (i32, i32), size: (i32, i32)) -> bool {
-        let (x1, y1) = pos;
-        let (w1, h1) = size;
+    /// Waits for the compositor to confirm a change to each of `names`. A
+    /// monitor listed twice needs two confirmations.
+    fn start_batch(&mut self, names: impl IntoIterator<Item = String>) {
+        for name in names {
+            *self.in_flight.entry(name).or_default() += 1;
+        }
+        self.in_flight_since = Some(Instant::now());
+        self.needs_save = true;
+    }
 
-        self.monitors.iter().any(|m| {
-            if m.name == exclude_name || !m.enabled {
-                return false;
-            }
-            let (x2, y2) = (m.position.x, m.position.y);
-            let (w2, h2) = effective_dimensions(m);
+    pub fn batch_in_flight(&self) -> bool {
+        !self.in_flight.is_empty()
+    }
 
-            x1 < x2 + w2 && x1 + w1 > x2 && y1 < y2 + h2 && y1 + h1 > y2
-        })
+    /// Gives up on confirmations that never arrived (e.g. a position the
+    /// compositor already had). Returns true if a batch was dropped.
+    pub fn expire_batch(&mut self) -> bool {
+        let expired = self.in_flight_since.is_some_and(|since| {
+            since.elapsed().as_millis() > BATCH_TIMEOUT_MS
+        });
+        if expired {
+            self.abort_batch();
+        }
+        expired
     }
 
-    fn calculate_closest_non_overlapping_position(
-        &self,
-        exclude_name: &str,
-        preferred_pos: (i32, i32),
-        size: (i32, i32),
-    ) -> (i32, i32) {
-        let (w, h) = size;
-        let enabled_monitors: Vec<&WlMonitor> = self
-            .monitors
-            .iter()
-            .filter(|m| m.enabled && m.name != exclude_name)
-            .collect();
+    pub fn abort_batch(&mut self) {
+        self.in_flight.clear();
+        self.in_flight_since = None;
+    }
 
-        if enabled_monitors.is_empty() {
-            return preferred_pos;
+    /// Asks the compositor for monitor descriptions, which are only used
+    /// when monitors are written by description.
+    fn load_monitor_descriptions(&mut self) {
+        if self.match_by_description {
+            self.monitor_descriptions =
+                compositor::monitor_descriptions(self.compositor);
         }
+    }
 
-        let mut candidates: Vec<(i32, i32)> = Vec::new();
-
-        let min_left = enabled_monitors
-            .iter()
-            .map(|m| m.position.x)
-            .min()
-            .unwrap_or(0);
-        candidates.push((min_left - w, 0));
+    fn monitor_description(&self, name: &str) -> Option<&str> {
+        self.monitor_descriptions.get(name).map(String::as_str)
+    }
 
-        let max_right = enabled_monitors
+    fn load_config_mirrors(&mut self) {
+        self.config_mirrors = self
+            .monitors
             .iter()
-            .map(|m| {
-                let (mw, _) = effective_dimensions(m);
-                m.position.x + mw
+            .filter_map(|m| {
+                let source = get_mirror_source(
+                    self.compositor,
+                    &self.comp_monitor_config_path,
+                    &m.name,
+                    self.monitor_description(&m.name),
+                )?;
+                Some((m.name.clone(), source))
             })
-            .max()
-            .unwrap_or(0);
-        candidates.push((max_right, 0));
-
-        let min_top = enabled_monitors
-            .iter()
-            .map(|m| m.position.y)
-            .min()
-            .unwrap_or(0);
-        candidates.push((0, min_top - h));
+            .collect();
+    }
 
-        let max_bottom = enabled_monitors
-            .iter()
-            .map(|m| {
-                let (_, mh) = effective_dimensions(m);
-                m.position.y + mh
-            })
-            .max()
-            .unwrap_or(0);
-        candidates.push((0, max_bottom));
+    pub fn update_monitor(&mut self, monitor: WlMonitor) {
+        self.confirm_in_flight(&monitor.name);
+        if let Some(existing_monitor) =
+            self.monitors.iter_mut().find(|m| m.name == monitor.name)
+        {
+            *existing_monitor = monitor;
+        } else {
+            let previous = self.monitor_names();
+            self.monitors.push(monitor);
+            self.reindex_pending(&previous);
+            self.record_startup_positions();
+            self.sanitize_selection();
+            self.load_monitor_descriptions();
+        };
+    }
 
-        candidates
-            .into_iter()
-            .filter(|pos| !self.position_overlaps(exclude_name, *pos, size))
-            .map(|pos| {
-                let dist = (pos.0 - preferred_pos.0).abs() + (pos.1 - preferred_pos.1).abs();
-                (dist, pos)
-            })
-            .min_by_key(|(d, _)| *d)
-            .map(|(_, pos)| pos)
-            .unwrap_or((max_right, 0))
+    fn monitor_names(&self) -> Vec<String> {
+        self.monitors.iter().map(|m| m.name.clone()).collect()
     }
 
-    fn calculate_non_overlapping_position(&self, exclude_name: &str) -> (i32, i32) {
-        let enabled_monitors: Vec<&WlMonitor> = self
-            .monitors
+    /// Keeps state keyed by monitor index with its monitor after the list
+    /// changed from `previous`, so a pending move made before a hotplug
+    /// still applies to the monitor it was made on. State for monitors
+    /// that are gone is dropped.
+    fn reindex_pending(&mut self, previous: &[String]) {
+        if previous.is_empty() {
+            return;
+        }
+        let current: Vec<&str> =
+            self.monitors.iter().map(|m| m.name.as_str()).collect();
+        self.pending_positions =
+            reindex(&self.pending_positions, previous, &current);
+        self.pending_scales = reindex(&self.pending_scales, previous, &current);
+        self.pending_transforms =
+            reindex(&self.pending_transforms, previous, &current);
+        self.group = self
+            .group
             .iter()
-            .filter(|m| m.enabled && m.name != exclude_name)
+            .filter_map(|&idx| remap_index(idx, previous, &current))
             .collect();
-
-        if enabled_monitors.is_empty() {
-            return (0, 0);
+        self.pending_enable = self
+            .pending_enable
+            .and_then(|idx| remap_index(idx, previous, &current));
+        for ws in self
+            .workspace_assignments
+            .iter_mut()
+            .chain(self.pending_workspaces.values_mut())
+        {
+            ws.monitor_idx = ws
+                .monitor_idx
+                .and_then(|idx| remap_index(idx, previous, &current));
         }
-
-        let max_right = enabled_monitors
-            .iter()
-            .map(|m| {
-                let (w, _) = effective_dimensions(m);
-                m.position.x + w
-            })
-            .max()
-            .unwrap_or(0);
-
-        (max_right, 0)
     }
 
-    pub fn move_monitor(&mut self, direction: PositionDirection) {
-        let Some(selected) = self.monitors.get(self.selected_monitor) else {
+    /// Counts one confirmed change to `name` towards the batch in flight.
+    fn confirm_in_flight(&mut self, name: &str) {
+        let Some(left) = self.in_flight.get_mut(name) else {
             return;
         };
-        if !selected.enabled {
-            return;
+        *left -= 1;
+        if *left == 0 {
+            self.in_flight.remove(name);
+            if self.in_flight.is_empty() {
+                self.in_flight_since = None;
+            }
         }
+    }
 
-        let now = Instant::now();
-        let elapsed = now.duration_since(self.last_move_time).as_millis();
-        let same_direction = self
-            .last_move_direction
-            .as_ref()
-            .map(|d| std::mem::discriminant(d) == std::mem::discriminant(&direction))
-            .unwrap_or(false);
-
-        if elapsed < REPEAT_WINDOW_MS && same_direction {
-            self.move_repeat_count += 1;
-        } else {
-            self.move_repeat_count = 0;
+    fn record_startup_positions(&mut self) {
+        for m in &self.monitors {
+            self.startup_positions
+                .entry(m.name.clone())
+                .or_insert((m.position.x, m.position.y));
         }
-        self.last_move_time = now;
-        self.last_move_direction = Some(direction.clone());
-
-        let step = 1 + (self.move_repeat_count * 2) as i32;
-
-        let (cur_x, cur_y) = self.display_position(self.selected_monitor);
-        let (sel_w, sel_h) = effective_dimensions(selected);
+    }
 
-        let (new_x, new_y) = match direction {
-            PositionDirection::Left => (cur_x - step, cur_y),
-            PositionDirection::Right => (cur_x + step, cur_y),
-            PositionDirection::Up => (cur_x, cur_y - step),
-            PositionDirection::Down => (cur_x, cur_y + step),
-        };
+    pub fn remove_monitor(&mut self, name: &str) {
+        let previous = self.monitor_names();
+        let removed = self.monitors.iter().any(|m| m.name == name);
+        if removed {
+            self.stage_panel_values();
+        }
+        self.monitors.retain(|m| m.name != name);
 
-        let new_x = new_x.max(0);
-        let new_y = new_y.max(0);
+        if removed {
+            self.cancel_enable();
+            self.group.clear();
+            self.stop_identify();
+            self.reindex_pending(&previous);
 
-        let collided = self.monitors.iter().enumerate().find(|(i, m)| {
-            if *i == self.selected_monitor || !m.enabled {
-                return false;
+            if self.selected_monitor >= self.monitors.len() {
+                self.selected_monitor = self.monitors.len().saturating_sub(1);
             }
-            let (mx, my) = self.display_position(*i);
-            let (mw, mh) = effective_dimensions(m);
-            new_x < mx + mw && new_x + sel_w > mx && new_y < my + mh && new_y + sel_h > my
-        });
+            // The last-monitor warning was about whichever monitor was
+            // selected before the indices shifted.
+            self.pending_last_toggle_monitor = false;
+            if !self.monitors.is_empty() && !self.has_active_outputs() {
+                self.pending_positions.clear();
+                self.set_status("No active outputs");
+            }
+            self.sync_panel_state();
+        }
+    }
 
-        if let Some((other_idx, other_mon)) = collided {
-            let (other_x, other_y) = self.display_position(other_idx);
-            let (other_w, other_h) = effective_dimensions(other_mon);
+    /// Whether at least one monitor is enabled. Hotplug can leave every
+    /// remaining monitor disabled, e.g. when undocking a laptop with its lid
+    /// shut.
+    pub fn has_active_outputs(&self) -> bool {
+        self.enabled_count() > 0
+    }
 
-            let (new_pos_selected, new_pos_other) = match direction {
-                PositionDirection::Left => ((other_x, other_y), (other_x + sel_w, other_y)),
-                PositionDirection::Right => ((cur_x + other_w, cur_y), (cur_x, cur_y)),
-                PositionDirection::Up => ((other_x, other_y), (other_x, other_y + sel_h)),
-                PositionDirection::Down => ((cur_x, cur_y + other_h), (cur_x, cur_y)),
-            };
+    fn sanitize_selection(&mut self) {
+        if self.monitors.is_empty() {
+            self.selected_monitor = 0;
+        } else if self.selected_monitor >= self.monitors.len() {
+            self.selected_monitor = self.monitors.len() - 1;
+        }
+    }
 
-            let new_pos_selected = (new_pos_selected.0.max(0), new_pos_selected.1.max(0));
-            let new_pos_other = (new_pos_other.0.max(0), new_pos_other.1.max(0));
+    pub fn selected_monitor(&self) -> Option<&WlMonitor> {
+        self.monitors.get(self.selected_monitor)
+    }
 
-            self.pending_positions
-                .insert(self.selected_monitor, new_pos_selected);
-            self.pending_positions.insert(other_idx, new_pos_other);
-        } else {
-            self.pending_positions
-                .insert(self.selected_monitor, (new_x, new_y));
+    pub fn display_position(&self, idx: usize) -> (i32, i32) {
+        if let Some(&pos) = self.pending_positions.get(&idx) {
+            return pos;
         }
+        self.monitors
+            .get(idx)
+            .map(|m| (m.position.x, m.position.y))
+            .unwrap_or((0, 0))
     }
 
-    pub fn previous(&mut self) {
-        match self.panel {
-            Panel::Mode => {
-                let len = self.selected_monitor().map(|m| m.modes.len()).unwrap_or(0);
-                if len == 0 {
-                    return;
-                }
-                let i = self
-                    .mode_state
-                    .selected()
-                    .map(|i| if i == 0 { len - 1 } else { i - 1 })
-                    .unwrap_or(0);
-                self.mode_state.select(Some(i));
-            }
-            Panel::Monitor => {
-                self.move_monitor(PositionDirection::Up);
-            }
-            Panel::Scale => {
-                self.scale_down();
-            }
-            Panel::Transform => {
-                let len = TRANSFORMS.len();
-                let i = self
-                    .transform_state
-                    .selected()
-                    .map(|i| if i == 0 { len - 1 } else { i - 1 })
-                    .unwrap_or(0);
-                self.transform_state.select(Some(i));
-            }
-            Panel::Workspace => {
-                let len = self.workspace_assignments.len();
-                if len == 0 {
-                    return;
-                }
-                let i = self
-                    .workspace_state
-                    .selected()
-                    .map(|i| if i == 0 { len - 1 } else { i - 1 })
-                    .unwrap_or(0);
-                self.workspace_state.select(Some(i));
-            }
+    /// The resolution of the mode highlighted in the Mode panel, if it differs
+    /// from the selected monitor's current one.
+    pub fn pending_mode_resolution(&self) -> Option<(i32, i32)> {
+        if self.panel != Panel::Mode {
+            return None;
         }
+        let monitor = self.selected_monitor()?;
+        let mode = monitor.modes.get(self.selected_mode_index()?)?;
+        let res = (mode.resolution.width, mode.resolution.height);
+        (res != monitor_resolution(monitor)).then_some(res)
     }
 
-    pub fn next(&mut self) {
-        match self.panel {
-            Panel::Mode => {
-                let len = self.selected_monitor().map(|m| m.modes.len()).unwrap_or(0);
-                if len == 0 {
-                    return;
-                }
-                let i = self
-                    .mode_state
-                    .selected()
-                    .map(|i| (i + 1) % len)
-                    .unwrap_or(0);
-                self.mode_state.select(Some(i));
-            }
-            Panel::Monitor => {
-                self.move_monitor(PositionDirection::Down);
-            }
-            Panel::Scale => {
-                self.scale_up();
-            }
-            Panel::Transform => {
-                let len = TRANSFORMS.len();
-                let i = self
-                    .transform_state
-                    .selected()
-                    .map(|i| (i + 1) % len)
-                    .unwrap_or(0);
-                self.transform_state.select(Some(i));
-            }
-            Panel::Workspace => {
-                let len = self.workspace_assignments.len();
-                if len == 0 {
-                    return;
-                }
-                let i = self
-                    .workspace_state
-                    .selected()
-                    .map(|i| (i + 1) % len)
-                    .unwrap_or(0);
-                self.workspace_state.select(Some(i));
-            }
+    /// Size of a monitor as drawn on the map. The selected monitor previews
+    /// the pending mode while the Mode panel is focused.
+    pub fn display_dimensions(&self, idx: usize) -> (i32, i32) {
+        let Some(monitor) = self.monitors.get(idx) else {
+            return (0, 0);
+        };
+        if idx == self.selected_monitor
+            && let Some((w, h)) = self.pending_mode_resolution()
+        {
+            return transformed_dimensions(w, h, monitor.transform);
         }
+        effective_dimensions(monitor)
     }
 
-    pub fn cycle_workspace_monitor(&mut self, forward: bool) {
-        let Some(ws_idx) = self.workspace_state.selected() else {
-            return;
+    /// Whether the pending mode would make the selected monitor overlap
+    /// another enabled one.
+    pub fn pending_mode_overlaps(&self) -> bool {
+        let Some(monitor) = self.selected_monitor() else {
+            return false;
         };
+        monitor.enabled
+            && self.pending_mode_resolution().is_some()
+            && self.position_overlaps(
+                &monitor.name,
+                self.display_position(self.selected_monitor),
+                self.display_dimensions(self.selected_monitor),
+            )
+    }
 
-        let Some(effective) = self.get_effective_workspace(ws_idx) else {
-            return;
-        };
+    /// Bounding box of all enabled monitors in logical (scaled) pixels,
+    /// using pending positions where they exist.
+    pub fn virtual_desktop_size(&self) -> Option<(i32, i32)> {
+        let rects: Vec<(i32, i32, i32, i32)> = self
+            .monitors
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.enabled)
+            .map(|(idx, m)| {
+                let (x, y) = self.display_position(idx);
+                let (w, h) = self.display_dimensions(idx);
+                let (lw, lh) = logical_size(w, h, m.scale);
+                (x, y, x + lw, y + lh)
+            })
+            .collect();
 
-        let monitors: Vec<usize> = self.monitors.iter().enumerate().map(|(i, _)| i).collect();
+        let min_x = rects.iter().map(|r| r.0).min()?;
+        let min_y = rects.iter().map(|r| r.1).min()?;
+        let max_x = rects.iter().map(|r| r.2).max()?;
+        let max_y = rects.iter().map(|r| r.3).max()?;
+        Some((max_x - min_x, max_y - min_y))
+    }
 
-        if monitors.is_empty() {
-            return;
+    /// A monitor's position for display: absolute, or as an offset from the
+    /// reference monitor while one is set and connected.
+    pub fn position_label(&self, idx: usize) -> String {
+        let (x, y) = self.display_position(idx);
+        let reference = self.relative_to.as_ref().and_then(|name| {
+            self.monitors
+                .iter()
+                .position(|m| &m.name == name && m.enabled)
+        });
+        match reference {
+            Some(ref_idx) if ref_idx == idx => "(ref)".to_string(),
+            Some(ref_idx) => {
+                let (rx, ry) = self.display_position(ref_idx);
+                format!("({:+},{:+})", x - rx, y - ry)
+            }
+            None => format!("({},{})", x, y),
         }
+    }
 
-        let new_monitor_idx = match effective.monitor_idx {
-            None => {
-                if forward {
-                    Some(monitors[0])
-                } else {
-                    Some(monitors[monitors.len() - 1])
-                }
-            }
-            Some(idx) => {
-                let pos = monitors.iter().position(|&i| i == idx);
-                match pos {
-                    Some(p) => {
-                        if forward {
-                            if p + 1 >= monitors.len() {
-                                None
-                            } else {
-                                Some(monitors[p + 1])
-                            }
-                        } else if p == 0 {
-                            None
-                        } else {
-                            Some(monitors[p - 1])
-                        }
-                    }
-                    None => {
-                        if forward {
-                            Some(monitors[0])
-                        } else {
-                            Some(monitors[monitors.len() - 1])
-                        }
-                    }
-                }
-            }
+    /// Shows positions relative to the selected monitor, or back in absolute
+    /// coordinates if it already is the reference. The choice is saved to
+    /// the xwlm config.
+    pub fn toggle_relative_positions(&mut self) {
+        let Some(monitor) = self.selected_monitor() else {
+            return;
         };
+        if self.relative_to.as_ref() == Some(&monitor.name) {
+            self.relative_to = None;
+            self.set_status("Showing absolute positions");
+        } else {
+            let name = monitor.name.clone();
+            self.set_status(format!("Showing positions relative to {}", name));
+            self.relative_to = Some(name);
+        }
 
-        let mut new_ws = effective;
-        new_ws.monitor_idx = new_monitor_idx;
-        self.pending_workspaces.insert(ws_idx, new_ws);
+        let saved = xwlm_config::patch_config(
+            None,
+            "relative_to",
+            self.relative_to.clone().map(toml::Value::String),
+        );
+        if let Err(e) = saved {
+            self.set_error(format!(
+                "Failed to remember position display: {}",
+                e
+            ));
+        }
     }
 
-    pub fn get_effective_workspace(&self, idx: usize) -> Option<WorkspaceAssignment> {
-        if let Some(ws) = self.pending_workspaces.get(&idx) {
-            return Some(ws.clone());
+    pub fn toggle_basic_colors(&mut self) {
+        self.basic_colors = !self.basic_colors;
+        self.set_status(if self.basic_colors {
+            "Using basic terminal colors"
+        } else {
+            "Using RGB colors"
+        });
+
+        let saved = xwlm_config::patch_config(
+            None,
+            "basic_colors",
+            Some(toml::Value::Boolean(self.basic_colors)),
+        );
+        if let Err(e) = saved {
+            self.set_error(format!("Failed to remember color mode: {}", e));
         }
-        self.workspace_assignments.get(idx).cloned()
     }
 
-    pub fn has_pending_workspaces(&self) -> bool {
-        !self.pending_workspaces.is_empty()
+    /// `color`, or the nearest basic terminal color when RGB is turned off.
+    pub fn color(&self, color: Color) -> Color {
+        if self.basic_colors {
+            basic_color(color)
+        } else {
+            color
+        }
     }
 
-    pub fn nav_left(&mut self) {
-        match self.panel {
-            Panel::Monitor => self.move_monitor(PositionDirection::Left),
-            Panel::Scale => self.scale_down(),
-            Panel::Workspace => self.cycle_workspace_monitor(false),
-            _ => {}
-        }
+    pub fn has_pending_positions(&self) -> bool {
+        !self.pending_positions.is_empty()
     }
 
-    pub fn nav_right(&mut self) {
-        match self.panel {
-            Panel::Monitor => self.move_monitor(PositionDirection::Right),
-            Panel::Scale => self.scale_up(),
-            Panel::Workspace => self.cycle_workspace_monitor(true),
-            _ => {}
+    pub fn pending_summary(&self) -> PendingSummary {
+        let monitor = self.selected_monitor().filter(|m| m.enabled);
+        PendingSummary {
+            moved: self.pending_positions.len(),
+            workspaces: self.pending_workspaces.len(),
+            scale: monitor
+                .is_some_and(|m| (m.scale - self.pending_scale).abs() > 0.001),
+            transform: monitor
+                .zip(self.selected_transform())
+                .is_some_and(|(m, t)| m.transform != t),
+            staged: self.staged_monitors().len(),
         }
     }
 
-    pub fn toggle_panel(&mut self) {
-        self.panel = match self.panel {
-            Panel::Monitor => Panel::Mode,
-            Panel::Mode => Panel::Workspace,
-            Panel::Workspace => Panel::Scale,
-            Panel::Scale => Panel::Transform,
-            Panel::Transform => Panel::Monitor,
-        };
+    /// Monitors other than the selected one with a scale or transform
+    /// waiting, in order.
+    fn staged_monitors(&self) -> Vec<usize> {
+        let mut staged: Vec<usize> = self
+            .pending_scales
+            .keys()
+            .chain(self.pending_transforms.keys())
+            .copied()
+            .collect();
+        staged.sort_unstable();
+        staged.dedup();
+        staged
     }
 
-    pub fn save_config(&mut self) {
-        if !self.needs_save {
+    /// Sets aside the selected monitor's unapplied scale and transform
+    /// before another monitor is selected.
+    fn stage_panel_values(&mut self) {
+        let idx = self.selected_monitor;
+        let Some(monitor) = self.monitors.get(idx) else {
             return;
+        };
+        if (monitor.scale - self.pending_scale).abs() > 0.001 {
+            self.pending_scales.insert(idx, self.pending_scale);
         }
-        self.needs_save = false;
+        if let Some(transform) = self.selected_transform()
+            && transform != monitor.transform
+        {
+            self.pending_transforms.insert(idx, transform);
+        }
+    }
 
-        let workspace_rules: Vec<WorkspaceRule> = self
-            .workspace_assignments
+    /// Sends the scales set aside on other monitors, saving once each is
+    /// confirmed. Safe apply only covers the selected monitor. Returns how
+    /// many were sent; one the compositor would refuse stays pending.
+    fn apply_staged_scales(
+        &mut self,
+    ) -> Result<usize, SendError<WlMonitorAction>> {
+        let capabilities = self.compositor.capabilities();
+        let mut staged: Vec<(usize, f64)> = self
+            .pending_scales
             .iter()
-            .map(|ws| {
-                let monitor_name = ws
-                    .monitor_idx
-                    .and_then(|idx| self.monitors.get(idx))
-                    .map(|m| m.name.clone())
-                    .unwrap_or_default();
-                WorkspaceRule {
-                    id: ws.id,
-                    monitor: monitor_name,
-                    is_default: ws.is_default,
-                    is_persistent: ws.is_persistent,
-                }
-            })
+            .map(|(&idx, &scale)| (idx, scale))
             .collect();
+        staged.sort_by_key(|&(idx, _)| idx);
+        let mut names = Vec::new();
+        for (idx, scale) in staged {
+            let Some(monitor) = self.monitors.get(idx) else {
+                continue;
+            };
+            let name = monitor.name.clone();
+            if let Err(msg) = capabilities.check_scale(scale, self.scale_limits)
+            {
+                self.set_error(format!(
+                    "{} would not apply {:.2} on {}: {}",
+                    self.compositor.label(),
+                    scale,
+                    name,
+                    msg
+                ));
+                continue;
+            }
+            self.wlx_action_handler.send(WlMonitorAction::SetScale {
+                name: name.clone(),
+                scale,
+            })?;
+            self.pending_scales.remove(&idx);
+            names.push(name);
+        }
+        let sent = names.len();
+        if sent > 0 {
+            self.start_batch(names);
+        }
+        Ok(sent)
+    }
 
-        if let Err(e) = save_monitor_config(
+    /// Like `apply_staged_scales`, for transforms.
+    fn apply_staged_transforms(
+        &mut self,
+    ) -> Result<usize, SendError<WlMonitorAction>> {
+        let mut staged: Vec<(usize, WlTransform)> = self
+            .pending_transforms
+            .iter()
+            .map(|(&idx, &t)| (idx, t))
+            .collect();
+        staged.sort_by_key(|&(idx, _)| idx);
+        let mut names = Vec::new();
+        for (idx, transform) in staged {
+            let Some(monitor) = self.monitors.get(idx) else {
+                continue;
+            };
+            let name = monitor.name.clone();
+            self.send_transform(&name, transform)?;
+            self.pending_transforms.remove(&idx);
+            names.push(name);
+        }
+        let sent = names.len();
+        if sent > 0 {
+            self.start_batch(names);
+        }
+        Ok(sent)
+    }
+
+    pub fn set_error(&mut self, msg: impl Into<String>) {
+        self.error_message = Some(msg.into());
+    }
+
+    pub fn clear_error(&mut self) {
+        self.error_message = None;
+    }
+
+    pub fn set_status(&mut self, msg: impl Into<String>) {
+        self.status_message = Some(msg.into());
+    }
+
+    pub fn clear_status(&mut self) {
+        self.status_message = None;
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.map_zoom = (self.map_zoom + 0.1).min(5.0);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.map_zoom = (self.map_zoom - 0.1).max(0.2);
+        // Everything fits again, so there is nothing to scroll to
+        if self.map_zoom < 1.001 {
+            self.map_pan_x = 0;
+            self.map_pan_y = 0;
+        }
+    }
+
+    /// Scrolls the map when zoomed in past the point where it all fits.
+    pub fn pan_map(&mut self, direction: PositionDirection) {
+        if self.map_zoom < 1.001 {
+            return;
+        }
+        match direction {
+            PositionDirection::Left => self.map_pan_x -= MAP_PAN_STEP_X,
+            PositionDirection::Right => self.map_pan_x += MAP_PAN_STEP_X,
+            PositionDirection::Up => self.map_pan_y -= MAP_PAN_STEP_Y,
+            PositionDirection::Down => self.map_pan_y += MAP_PAN_STEP_Y,
+        }
+    }
+
+    pub fn scale_up(&mut self) {
+        self.set_pending_scale(self.pending_scale + self.scale_step);
+    }
+
+    pub fn scale_down(&mut self) {
+        self.set_pending_scale(self.pending_scale - self.scale_step);
+    }
+
+    fn set_pending_scale(&mut self, scale: f64) {
+        if let Err(msg) = self.scale_limits.check(scale) {
+            self.set_error(msg);
+        }
+        self.pending_scale = self.compositor.capabilities().snap_scale(
+            self.pending_scale,
+            scale,
+            self.scale_limits,
+        );
+    }
+
+    /// The temperature last set on `name`, neutral if it was never tinted.
+    pub fn color_temp(&self, name: &str) -> u32 {
+        self.color_temps
+            .get(name)
+            .copied()
+            .unwrap_or(NEUTRAL_COLOR_TEMP)
+    }
+
+    pub fn warmer(&mut self) {
+        self.pending_color_temp = self
+            .pending_color_temp
+            .saturating_sub(COLOR_TEMP_STEP)
+            .max(MIN_COLOR_TEMP);
+    }
+
+    pub fn cooler(&mut self) {
+        self.pending_color_temp =
+            (self.pending_color_temp + COLOR_TEMP_STEP).min(NEUTRAL_COLOR_TEMP);
+    }
+
+    fn apply_color_temp(&mut self) {
+        let Some(monitor) = self.selected_monitor() else {
+            return;
+        };
+        let name = monitor.name.clone();
+        let kelvin = self.pending_color_temp;
+        if let Err(e) = color_temp::apply(self.compositor, &name, kelvin) {
+            self.set_error(format!("Failed to set color temperature: {}", e));
+            return;
+        }
+
+        let targets: Vec<String> = if color_temp::is_global(self.compositor) {
+            self.monitors.iter().map(|m| m.name.clone()).collect()
+        } else {
+            vec![name.clone()]
+        };
+        let mut saved = Ok(());
+        for target in targets {
+            let value = if kelvin >= NEUTRAL_COLOR_TEMP {
+                self.color_temps.remove(&target);
+                None
+            } else {
+                self.color_temps.insert(target.clone(), kelvin);
+                Some(toml::Value::Integer(kelvin.into()))
+            };
+            if saved.is_ok() {
+                saved = xwlm_config::patch_config(
+                    Some("color_temperature"),
+                    &target,
+                    value,
+                );
+            }
+        }
+        self.set_status(format!("{} → {}K", name, kelvin));
+
+        if let Err(e) = saved {
+            self.set_error(format!(
+                "Failed to remember color temperature: {}",
+                e
+            ));
+        }
+    }
+
+    /// Re-tints outputs with the temperatures saved last time, since
+    /// neither hyprsunset nor wlsunset outlive the session.
+    pub fn restore_color_temps(&mut self) {
+        let mut temps: Vec<(String, u32)> = self
+            .color_temps
+            .iter()
+            .map(|(name, &kelvin)| (name.clone(), kelvin))
+            .collect();
+        if color_temp::is_global(self.compositor) {
+            temps.truncate(1);
+        }
+        for (name, kelvin) in temps {
+            if let Err(e) = color_temp::apply(self.compositor, &name, kelvin) {
+                self.set_error(format!(
+                    "Failed to restore color temperature: {}",
+                    e
+                ));
+                return;
+            }
+        }
+    }
+
+    /// Starts the wallpaper command for every enabled monitor that has an
+    /// image set.
+    pub fn run_wallpapers(&mut self) {
+        let names: Vec<String> = self
+            .monitors
+            .iter()
+            .filter(|m| m.enabled && self.wallpapers.contains_key(&m.name))
+            .map(|m| m.name.clone())
+            .collect();
+        for name in names {
+            self.run_wallpaper(&name);
+        }
+    }
+
+    fn run_wallpaper(&mut self, name: &str) {
+        let (Some(template), Some(image)) =
+            (&self.wallpaper_command, self.wallpapers.get(name))
+        else {
+            return;
+        };
+        let image = expand_tilde(image)
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| image.clone());
+        let started = wallpaper::spawn(template, name, &image);
+        if let Some(previous) = self.wallpaper_jobs.remove(name) {
+            previous.stop();
+        }
+        match started {
+            Ok(job) => {
+                self.wallpaper_jobs.insert(name.to_string(), job);
+            }
+            Err(e) => self.set_error(format!(
+                "Failed to set wallpaper on {}: {}",
+                name, e
+            )),
+        }
+    }
+
+    /// Reports wallpaper commands that exited with an error. Finished jobs
+    /// are kept so a setter they left in the background is stopped with
+    /// them next time.
+    pub fn check_wallpapers(&mut self) {
+        let mut failed = None;
+        for (name, job) in &mut self.wallpaper_jobs {
+            if let Some(Err(e)) = job.finished() {
+                failed =
+                    Some(format!("Failed to set wallpaper on {}: {}", name, e));
+            }
+        }
+        if let Some(msg) = failed {
+            self.set_error(msg);
+        }
+    }
+
+    /// Opens the wallpaper prompt for the selected monitor, prefilled with
+    /// its current image.
+    pub fn start_wallpaper_prompt(&mut self) {
+        let Some(monitor) = self.selected_monitor() else {
+            return;
+        };
+        let current = self
+            .wallpapers
+            .get(&monitor.name)
+            .cloned()
+            .unwrap_or_default();
+        self.wallpaper_prompt = Some(TextInput::new(current));
+    }
+
+    pub fn cancel_wallpaper_prompt(&mut self) {
+        self.wallpaper_prompt = None;
+    }
+
+    /// Remembers the typed image for the selected monitor and sets it right
+    /// away. An empty path forgets the monitor's wallpaper.
+    pub fn submit_wallpaper_prompt(&mut self) {
+        let Some(input) = self.wallpaper_prompt.take() else {
+            return;
+        };
+        let Some(monitor) = self.selected_monitor() else {
+            return;
+        };
+        let name = monitor.name.clone();
+        let image = input.value().trim().to_string();
+        if image.is_empty() {
+            self.wallpapers.remove(&name);
+            self.set_status(format!("{} has no wallpaper", name));
+        } else {
+            self.wallpapers.insert(name.clone(), image.clone());
+            if self.wallpaper_command.is_some() {
+                self.set_status(format!("{} → {}", name, image));
+                self.run_wallpaper(&name);
+            } else {
+                self.set_status(format!(
+                    "Remembered {} for {}; set wallpaper_command in the xwlm config to apply it",
+                    image, name
+                ));
+            }
+        }
+
+        let saved = xwlm_config::patch_config(
+            Some("wallpapers"),
+            &name,
+            self.wallpapers.get(&name).cloned().map(toml::Value::String),
+        );
+        if let Err(e) = saved {
+            self.set_error(format!("Failed to remember wallpaper: {}", e));
+        }
+    }
+
+    fn enabled_count(&self) -> usize {
+        self.monitors.iter().filter(|m| m.enabled).count()
+    }
+
+    pub fn dismiss_warning(&mut self) {
+        self.pending_last_toggle_monitor = false;
+    }
+
+    pub fn toggle_monitor(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        if self.pending_last_toggle_monitor {
+            self.pending_last_toggle_monitor = false;
+            let Some(monitor) = self.monitors.get(self.selected_monitor) else {
+                return Ok(());
+            };
+            self.perform_toggle(&monitor.name.clone(), monitor.enabled)?;
+            return Ok(());
+        }
+
+        let Some(monitor) = self.monitors.get(self.selected_monitor) else {
+            return Ok(());
+        };
+
+        if monitor.enabled && self.enabled_count() == 1 {
+            self.pending_last_toggle_monitor = true;
+            return Ok(());
+        }
+        if !monitor.enabled {
+            if self.pending_enable == Some(self.selected_monitor) {
+                self.cancel_enable();
+            } else {
+                self.preview_enable();
+            }
+            return Ok(());
+        }
+        self.perform_toggle(&monitor.name.clone(), monitor.enabled)?;
+
+        Ok(())
+    }
+
+    /// Shows the selected disabled monitor where enabling would put it, so
+    /// it can be moved before it lights up. Enter enables it there.
+    fn preview_enable(&mut self) {
+        let idx = self.selected_monitor;
+        let Some(name) = self.monitors.get(idx).map(|m| m.name.clone()) else {
+            return;
+        };
+        let position = self.enable_position(&name);
+        self.pending_positions.insert(idx, position);
+        self.pending_enable = Some(idx);
+        self.panel = Panel::Monitor;
+        self.set_status(format!(
+            "Place {name} with the arrows; Enter enables it there, Esc cancels"
+        ));
+    }
+
+    pub fn cancel_enable(&mut self) {
+        if let Some(idx) = self.pending_enable.take() {
+            self.pending_positions.remove(&idx);
+        }
+    }
+
+    fn confirm_enable(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        let Some(idx) = self.pending_enable.take() else {
+            return Ok(());
+        };
+        // Toggle flips the state, so skip a monitor enabled meanwhile
+        let Some(name) = self
+            .monitors
+            .get(idx)
+            .filter(|m| !m.enabled)
+            .map(|m| m.name.clone())
+        else {
+            return Ok(());
+        };
+        let position = self
+            .pending_positions
+            .remove(&idx)
+            .unwrap_or_else(|| self.enable_position(&name));
+        self.wlx_action_handler.send(WlMonitorAction::Toggle {
+            name,
+            mode: None,
+            position: Some(position),
+        })?;
+        self.needs_save = true;
+        Ok(())
+    }
+
+    /// The monitor called `name`, ignoring case.
+    pub fn monitor_named(&self, name: &str) -> Result<usize, String> {
+        self.monitors
+            .iter()
+            .position(|m| m.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let names: Vec<&str> =
+                    self.monitors.iter().map(|m| m.name.as_str()).collect();
+                format!(
+                    "No monitor named '{}' (connected: {})",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+
+    /// What toggling the monitor at `idx` without a preview does: `None`
+    /// turns it off, otherwise it is enabled at the returned position.
+    /// Turning off the last enabled monitor is refused.
+    pub fn plan_toggle(
+        &self,
+        idx: usize,
+    ) -> Result<Option<(i32, i32)>, String> {
+        let Some(monitor) = self.monitors.get(idx) else {
+            return Err("No monitor selected".to_string());
+        };
+        if !monitor.enabled {
+            return Ok(Some(self.enable_position(&monitor.name)));
+        }
+        if self.enabled_count() == 1 {
+            return Err(format!(
+                "{} is the only enabled monitor; turning it off would leave no display",
+                monitor.name
+            ));
+        }
+        Ok(None)
+    }
+
+    pub fn perform_toggle(
+        &mut self,
+        monitor_name: &str,
+        currently_enabled: bool,
+    ) -> Result<(), SendError<WlMonitorAction>> {
+        let will_enable = !currently_enabled;
+        let position = if will_enable {
+            Some(self.enable_position(monitor_name))
+        } else {
+            None
+        };
+
+        self.wlx_action_handler.send(WlMonitorAction::Toggle {
+            name: monitor_name.to_string(),
+            mode: None,
+            position,
+        })?;
+
+        self.needs_save = true;
+
+        Ok(())
+    }
+
+    /// Picks where a monitor goes when it is enabled: its saved position from
+    /// the config (or the last one xwlm applied) if that is free, otherwise
+    /// the closest free spot.
+    fn enable_position(&self, monitor_name: &str) -> (i32, i32) {
+        let saved_pos = get_position(
             self.compositor,
             &self.comp_monitor_config_path,
-            &self.monitors,
-            &workspace_rules,
-        ) {
-            self.set_error(format!("Failed to save config: {e}"));
+            monitor_name,
+            self.monitor_description(monitor_name),
+        )
+        .map(|p| (p.x, p.y))
+        .or_else(|| self.monitor_memory.position(monitor_name));
+
+        let (w, h) = self
+            .monitors
+            .iter()
+            .find(|m| m.name == monitor_name)
+            .map(effective_dimensions)
+            .unwrap_or((1920, 1080));
+
+        if let Some(pos) = saved_pos {
+            if self.position_overlaps(monitor_name, pos, (w, h)) {
+                self.calculate_closest_non_overlapping_position(
+                    monitor_name,
+                    pos,
+                    (w, h),
+                )
+            } else {
+                pos
+            }
         } else {
-            reload(self.compositor);
+            self.calculate_non_overlapping_position(monitor_name)
+        }
+    }
+
+    /// Asks for confirmation before turning off every monitor except the
+    /// selected one.
+    pub fn request_solo_monitor(&mut self) {
+        let Some(selected) = self.selected_monitor() else {
+            return;
+        };
+        if !selected.enabled {
+            self.set_error(
+                "Enable the selected monitor before turning the others off",
+            );
+            return;
+        }
+        if self.enabled_count() == 1 {
+            self.set_error(format!(
+                "{} is already the only enabled monitor",
+                selected.name
+            ));
+            return;
+        }
+        self.pending_confirm = Some(Confirm::SoloMonitor);
+    }
+
+    pub fn dismiss_confirm(&mut self) {
+        if self.pending_confirm.take() == Some(Confirm::RestoreDraft) {
+            self.restorable_draft = None;
+            if let Err(e) = Draft::discard() {
+                self.set_error(format!("Failed to discard draft: {e}"));
+            }
+        }
+    }
+
+    pub fn run_confirmed(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        match self.pending_confirm.take() {
+            Some(Confirm::SoloMonitor) => self.solo_monitor(),
+            Some(Confirm::RotateAll) => self.rotate_all(),
+            Some(Confirm::RevertToFile) => self.revert_to_file(),
+            Some(Confirm::ResolveOverlaps) => {
+                self.resolve_overlaps();
+                Ok(())
+            }
+            Some(Confirm::CollapseDuplicates) => {
+                self.accept_collapse();
+                Ok(())
+            }
+            Some(Confirm::RestoreDraft) => {
+                self.restore_draft();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn solo_monitor(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        let Some(selected) = self.selected_monitor() else {
+            return Ok(());
+        };
+        // The selected monitor stays on, so this can never leave zero
+        // enabled displays.
+        if !selected.enabled {
+            return Ok(());
+        }
+        let others: Vec<String> = self
+            .monitors
+            .iter()
+            .enumerate()
+            .filter(|(i, m)| *i != self.selected_monitor && m.enabled)
+            .map(|(_, m)| m.name.clone())
+            .collect();
+
+        for name in others {
+            self.perform_toggle(&name, true)?;
+        }
+        Ok(())
+    }
+
+    pub fn enable_all_monitors(
+        &mut self,
+    ) -> Result<(), SendError<WlMonitorAction>> {
+        let disabled: Vec<usize> = self
+            .monitors
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| !m.enabled)
+            .map(|(i, _)| i)
+            .collect();
+
+        for idx in disabled {
+            let name = self.monitors[idx].name.clone();
+            let (x, y) = self.enable_position(&name);
+            self.wlx_action_handler.send(WlMonitorAction::Toggle {
+                name,
+                mode: None,
+                position: Some((x, y)),
+            })?;
+
+            // Mark it enabled locally so the next monitor is placed around
+            // it; the compositor's Changed event confirms this shortly.
+            let monitor = &mut self.monitors[idx];
+            monitor.enabled = true;
+            monitor.position.x = x;
+            monitor.position.y = y;
+            let name = monitor.name.clone();
+            self.start_batch([name]);
+        }
+        Ok(())
+    }
+
+    /// Footprints of the enabled monitors other than `exclude_name`, at
+    /// their live positions.
+    fn other_monitor_rects(&self, exclude_name: &str) -> Vec<MonitorRect> {
+        self.monitors
+            .iter()
+            .filter(|m| m.enabled && m.name != exclude_name)
+            .map(|m| {
+                MonitorRect::new(
+                    (m.position.x, m.position.y),
+                    effective_dimensions(m),
+                )
+            })
+            .collect()
+    }
+
+    /// Enabled monitors as laid out on the map, pending moves included.
+    fn layout_rects(&self) -> Vec<(usize, MonitorRect)> {
+        self.monitors
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.enabled)
+            .map(|(idx, _)| {
+                let rect = MonitorRect::new(
+                    self.display_position(idx),
+                    self.display_dimensions(idx),
+                );
+                (idx, rect)
+            })
+            .collect()
+    }
+
+    /// The layout without the outputs the config mirrors onto another, as
+    /// those sit on top of their source on purpose.
+    fn unmirrored_layout_rects(&self) -> Vec<(usize, MonitorRect)> {
+        self.layout_rects()
+            .into_iter()
+            .filter(|&(idx, _)| {
+                !self.config_mirrors.contains_key(&self.monitors[idx].name)
+            })
+            .collect()
+    }
+
+    /// Two enabled monitors that overlap in the layout, if any. Mirrored
+    /// outputs don't count.
+    pub fn overlapping_pair(&self) -> Option<(usize, usize)> {
+        let layout = self.unmirrored_layout_rects();
+        let rects: Vec<MonitorRect> = layout.iter().map(|&(_, r)| r).collect();
+        geometry::first_overlap(&rects).map(|(a, b)| (layout[a].0, layout[b].0))
+    }
+
+    /// Refuses to go on with an overlapping layout, offering to move the
+    /// monitors apart instead.
+    fn refuse_overlaps(&mut self) -> bool {
+        let Some((a, b)) = self.overlapping_pair() else {
+            return false;
+        };
+        self.set_error(format!(
+            "{} overlaps {}; the layout wasn't applied",
+            self.monitors[a].name, self.monitors[b].name
+        ));
+        self.pending_confirm = Some(Confirm::ResolveOverlaps);
+        true
+    }
+
+    /// Moves overlapping monitors to the closest free spots. The moves stay
+    /// pending until applied.
+    pub fn resolve_overlaps(&mut self) {
+        let mut layout = self.unmirrored_layout_rects();
+        // Placed first, so only unlocked monitors get moved out of their way
+        layout.sort_by_key(|&(idx, _)| {
+            !self.monitor_memory.is_locked(&self.monitors[idx].name)
+        });
+        let rects: Vec<MonitorRect> = layout.iter().map(|&(_, r)| r).collect();
+        let resolved = geometry::resolve_overlaps(&rects);
+        if let Some(((idx, _), _)) =
+            layout.iter().zip(&resolved).find(|((idx, rect), pos)| {
+                (rect.x, rect.y) != **pos
+                    && self.monitor_memory.is_locked(&self.monitors[*idx].name)
+            })
+        {
+            let name = self.monitors[*idx].name.clone();
+            self.set_error(format!(
+                "{} is locked and overlaps another locked monitor",
+                name
+            ));
+            return;
+        }
+
+        let mut moved = 0;
+        for ((idx, rect), pos) in layout.into_iter().zip(resolved) {
+            if (rect.x, rect.y) != pos {
+                self.pending_positions.insert(idx, pos);
+                moved += 1;
+            }
+        }
+        self.panel = Panel::Monitor;
+        self.clear_error();
+        self.set_status(format!("Moved {} monitor(s) apart", moved));
+    }
+
+    fn position_overlaps(
+        &self,
+        exclude_name: &str,
+        pos: (i32, i32),
+        size: (i32, i32),
+    ) -> bool {
+        geometry::overlaps_any(
+            MonitorRect::new(pos, size),
+            &self.other_monitor_rects(exclude_name),
+        )
+    }
+
+    fn calculate_closest_non_overlapping_position(
+        &self,
+        exclude_name: &str,
+        preferred_pos: (i32, i32),
+        size: (i32, i32),
+    ) -> (i32, i32) {
+        geometry::closest_free_position(
+            &self.other_monitor_rects(exclude_name),
+            preferred_pos,
+            size,
+        )
+    }
+
+    fn calculate_non_overlapping_position(
+        &self,
+        exclude_name: &str,
+    ) -> (i32, i32) {
+        geometry::free_position(&self.other_monitor_rects(exclude_name))
+    }
+
+    pub fn move_monitor(&mut self, direction: PositionDirection) {
+        let Some(selected) = self.monitors.get(self.selected_monitor) else {
+            return;
+        };
+        if !selected.enabled
+            && self.pending_enable != Some(self.selected_monitor)
+        {
+            return;
+        }
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_move_time).as_millis();
+        let same_direction = self
+            .last_move_direction
+            .as_ref()
+            .map(|d| {
+                std::mem::discriminant(d) == std::mem::discriminant(&direction)
+            })
+            .unwrap_or(false);
+
+        if elapsed < REPEAT_WINDOW_MS && same_direction {
+            self.move_repeat_count += 1;
+        } else {
+            self.move_repeat_count = 0;
+        }
+        self.last_move_time = now;
+        self.last_move_direction = Some(direction.clone());
+
+        let step =
+            self.position_step * (1 + (self.move_repeat_count * 2) as i32);
+        self.shift_monitor(direction, step);
+    }
+
+    /// Moves the selected monitor by `count` steps without the key-repeat
+    /// acceleration, as used by a numeric count prefix.
+    pub fn move_monitor_by(
+        &mut self,
+        direction: PositionDirection,
+        count: u32,
+    ) {
+        let Some(selected) = self.monitors.get(self.selected_monitor) else {
+            return;
+        };
+        if !selected.enabled
+            && self.pending_enable != Some(self.selected_monitor)
+        {
+            return;
+        }
+        self.last_move_direction = None;
+        self.move_repeat_count = 0;
+        self.shift_monitor(direction, self.position_step * count as i32);
+    }
+
+    /// Moves the selected monitor flush past the nearest monitor in
+    /// `direction`, for putting it on the other side in one press.
+    pub fn hop_monitor(&mut self, direction: PositionDirection) {
+        let Some(selected) = self.monitors.get(self.selected_monitor) else {
+            return;
+        };
+        if !selected.enabled
+            && self.pending_enable != Some(self.selected_monitor)
+        {
+            return;
+        }
+        let name = selected.name.clone();
+        if self.monitor_memory.is_locked(&name) {
+            self.set_status(format!("{} is locked, K to unlock", name));
+            return;
+        }
+        if !self.group.is_empty() {
+            self.set_status("Hops move one monitor, clear the group first");
+            return;
+        }
+
+        let step = match direction {
+            PositionDirection::Left => (-1, 0),
+            PositionDirection::Right => (1, 0),
+            PositionDirection::Up => (0, -1),
+            PositionDirection::Down => (0, 1),
+        };
+        let moving = MonitorRect::new(
+            self.display_position(self.selected_monitor),
+            self.display_dimensions(self.selected_monitor),
+        );
+        let others: Vec<(usize, MonitorRect)> = self
+            .layout_rects()
+            .into_iter()
+            .filter(|&(idx, _)| idx != self.selected_monitor)
+            .collect();
+        let rects: Vec<MonitorRect> =
+            others.iter().map(|&(_, rect)| rect).collect();
+        let Some((x, y)) = geometry::hop_past(moving, &rects, step) else {
+            self.set_status(format!("No monitor to hop past from {}", name));
+            return;
+        };
+
+        // Hopping past the left or top edge shifts everything else over,
+        // keeping the layout at 0,0 like other moves do
+        let (dx, dy) = ((-x).max(0), (-y).max(0));
+        if (dx, dy) != (0, 0) {
+            if let Some(&(idx, _)) = others.iter().find(|&&(idx, _)| {
+                self.monitor_memory.is_locked(&self.monitors[idx].name)
+            }) {
+                self.set_status(format!(
+                    "{} is locked in place",
+                    self.monitors[idx].name
+                ));
+                return;
+            }
+            for &(idx, rect) in &others {
+                self.pending_positions
+                    .insert(idx, (rect.x + dx, rect.y + dy));
+            }
+        }
+        let (x, y) = (x + dx, y + dy);
+        self.last_move_direction = None;
+        self.move_repeat_count = 0;
+        self.pending_positions.insert(self.selected_monitor, (x, y));
+        self.set_status(format!("{} moved to {},{}", name, x, y));
+    }
+
+    fn shift_monitor(&mut self, direction: PositionDirection, step: i32) {
+        let Some(selected) = self.monitors.get(self.selected_monitor) else {
+            return;
+        };
+        if self.monitor_memory.is_locked(&selected.name) {
+            self.set_status(format!(
+                "{} is locked, K to unlock",
+                selected.name
+            ));
+            return;
+        }
+        if !self.group.is_empty() {
+            self.shift_group(direction, step);
+            return;
+        }
+
+        let (cur_x, cur_y) = self.display_position(self.selected_monitor);
+        let (sel_w, sel_h) = effective_dimensions(selected);
+
+        let (new_x, new_y) = match direction {
+            PositionDirection::Left => (cur_x - step, cur_y),
+            PositionDirection::Right => (cur_x + step, cur_y),
+            PositionDirection::Up => (cur_x, cur_y - step),
+            PositionDirection::Down => (cur_x, cur_y + step),
+        };
+
+        let new_x = new_x.max(0);
+        let new_y = new_y.max(0);
+
+        let moved = MonitorRect::new((new_x, new_y), (sel_w, sel_h));
+        let collided = self.monitors.iter().enumerate().find(|(i, m)| {
+            if *i == self.selected_monitor || !m.enabled {
+                return false;
+            }
+            moved.overlaps(MonitorRect::new(
+                self.display_position(*i),
+                effective_dimensions(m),
+            ))
+        });
+
+        // A locked monitor is never pushed aside; the move stops short
+        if let Some((_, other_mon)) = collided
+            && self.monitor_memory.is_locked(&other_mon.name)
+        {
+            self.set_status(format!("{} is locked in place", other_mon.name));
+            return;
+        }
+
+        if let Some((other_idx, other_mon)) = collided {
+            let (other_x, other_y) = self.display_position(other_idx);
+            let (other_w, other_h) = effective_dimensions(other_mon);
+
+            let (new_pos_selected, new_pos_other) = match direction {
+                PositionDirection::Left => {
+                    ((other_x, other_y), (other_x + sel_w, other_y))
+                }
+                PositionDirection::Right => {
+                    ((cur_x + other_w, cur_y), (cur_x, cur_y))
+                }
+                PositionDirection::Up => {
+                    ((other_x, other_y), (other_x, other_y + sel_h))
+                }
+                PositionDirection::Down => {
+                    ((cur_x, cur_y + other_h), (cur_x, cur_y))
+                }
+            };
+
+            let new_pos_selected =
+                (new_pos_selected.0.max(0), new_pos_selected.1.max(0));
+            let new_pos_other =
+                (new_pos_other.0.max(0), new_pos_other.1.max(0));
+
+            self.pending_positions
+                .insert(self.selected_monitor, new_pos_selected);
+            self.pending_positions.insert(other_idx, new_pos_other);
+        } else {
+            self.pending_positions
+                .insert(self.selected_monitor, (new_x, new_y));
+        }
+    }
+
+    /// Moves the group and the selected monitor by the same offset, keeping
+    /// their layout. Only monitors outside the group can block the move.
+    fn shift_group(&mut self, direction: PositionDirection, step: i32) {
+        let mut members = self.group.clone();
+        members.insert(self.selected_monitor);
+        if let Some(locked) = members
+            .iter()
+            .filter_map(|&i| self.monitors.get(i))
+            .find(|m| self.monitor_memory.is_locked(&m.name))
+        {
+            self.set_status(format!("{} is locked, K to unlock", locked.name));
+            return;
+        }
+
+        // Stop at the top-left edge as a whole rather than squashing
+        let min_x = members
+            .iter()
+            .map(|&i| self.display_position(i).0)
+            .min()
+            .unwrap_or(0);
+        let min_y = members
+            .iter()
+            .map(|&i| self.display_position(i).1)
+            .min()
+            .unwrap_or(0);
+        let (dx, dy) = match direction {
+            PositionDirection::Left => (-step.min(min_x), 0),
+            PositionDirection::Right => (step, 0),
+            PositionDirection::Up => (0, -step.min(min_y)),
+            PositionDirection::Down => (0, step),
+        };
+        if (dx, dy) == (0, 0) {
+            return;
+        }
+
+        let moved: Vec<(usize, (i32, i32))> = members
+            .iter()
+            .map(|&i| {
+                let (x, y) = self.display_position(i);
+                (i, (x + dx, y + dy))
+            })
+            .collect();
+        let blocker = moved.iter().find_map(|&(i, pos)| {
+            let rect = MonitorRect::new(
+                pos,
+                effective_dimensions(self.monitors.get(i)?),
+            );
+            self.monitors.iter().enumerate().find(|(j, m)| {
+                m.enabled
+                    && !members.contains(j)
+                    && rect.overlaps(MonitorRect::new(
+                        self.display_position(*j),
+                        effective_dimensions(m),
+                    ))
+            })
+        });
+        if let Some((_, other)) = blocker {
+            self.set_status(format!("{} is in the way", other.name));
+            return;
+        }
+        self.pending_positions.extend(moved);
+    }
+
+    /// Adds the selected monitor to the group moved together, or takes it
+    /// out again.
+    pub fn toggle_group_member(&mut self) {
+        if self.panel != Panel::Monitor {
+            return;
+        }
+        let idx = self.selected_monitor;
+        if !self.monitors.get(idx).is_some_and(|m| m.enabled) {
+            return;
+        }
+        if !self.group.remove(&idx) {
+            self.group.insert(idx);
+        }
+        if !self.group.is_empty() {
+            self.set_status(format!(
+                "{} monitor(s) grouped; arrows move them together, Esc clears",
+                self.group.len()
+            ));
+        }
+    }
+
+    pub fn clear_group(&mut self) {
+        self.group.clear();
+    }
+
+    /// Stores the arrangement as it would be after applying, pending moves
+    /// and the pending scale included, in `slot`.
+    pub fn store_slot(&mut self, slot: u8) {
+        let mut snapshot = Snapshot::default();
+        for (idx, m) in self.monitors.iter().enumerate() {
+            let pos = self
+                .pending_positions
+                .get(&idx)
+                .copied()
+                .unwrap_or((m.position.x, m.position.y));
+            let scale = if idx == self.selected_monitor {
+                self.pending_scale
+            } else {
+                self.pending_scales.get(&idx).copied().unwrap_or(m.scale)
+            };
+            snapshot.positions.insert(m.name.clone(), pos);
+            snapshot.scales.insert(m.name.clone(), scale);
+        }
+        self.slots.insert(slot, snapshot);
+        self.set_status(format!("Stored the arrangement in slot {slot}"));
+    }
+
+    /// Loads `slot` into the pending moves and scale. Nothing is applied
+    /// until Enter.
+    pub fn recall_slot(&mut self, slot: u8) {
+        let Some(snapshot) = self.slots.get(&slot) else {
+            self.set_status(format!(
+                "Slot {slot} is empty, Shift+{slot} stores one"
+            ));
+            return;
+        };
+        let current: Vec<(&str, (i32, i32))> = self
+            .monitors
+            .iter()
+            .map(|m| (m.name.as_str(), (m.position.x, m.position.y)))
+            .collect();
+        let positions = snapshot.pending_positions(&current);
+        let scales: Vec<(usize, f64)> = self
+            .monitors
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, m)| {
+                Some((idx, snapshot.scales.get(&m.name).copied()?))
+            })
+            .collect();
+
+        self.pending_positions = positions;
+        let capabilities = self.compositor.capabilities();
+        for (idx, scale) in scales {
+            let scale =
+                capabilities.snap_scale(scale, scale, self.scale_limits);
+            if idx == self.selected_monitor {
+                self.pending_scale = scale;
+            } else if (self.monitors[idx].scale - scale).abs() > 0.001 {
+                self.pending_scales.insert(idx, scale);
+            } else {
+                self.pending_scales.remove(&idx);
+            }
+        }
+        self.set_status(format!("Recalled slot {slot}"));
+    }
+
+    /// Stacks every enabled monitor in one left-aligned column, top to bottom
+    /// in their current vertical order. Positions stay pending until applied.
+    pub fn auto_arrange_vertical(&mut self) {
+        if self.panel != Panel::Monitor {
+            return;
+        }
+        let mut order: Vec<usize> = (0..self.monitors.len())
+            .filter(|&i| self.monitors[i].enabled)
+            .collect();
+        if order.len() < 2 {
+            return;
+        }
+        order.sort_by_key(|&i| {
+            let (x, y) = self.display_position(i);
+            (y, x)
+        });
+
+        let mut y = 0;
+        let mut stacked = Vec::with_capacity(order.len());
+        for idx in order {
+            stacked.push((idx, (0, y)));
+            y += effective_dimensions(&self.monitors[idx]).1;
+        }
+        if let Some(&(idx, _)) = stacked.iter().find(|&&(idx, pos)| {
+            pos != self.display_position(idx)
+                && self.monitor_memory.is_locked(&self.monitors[idx].name)
+        }) {
+            self.set_status(format!(
+                "{} is locked, K to unlock",
+                self.monitors[idx].name
+            ));
+            return;
+        }
+        self.pending_positions.extend(stacked);
+        self.set_status("Monitors stacked vertically");
+    }
+
+    pub fn push_count_digit(&mut self, digit: u32) {
+        let count = self.count_prefix.unwrap_or(0);
+        self.count_prefix = Some((count * 10 + digit).min(9999));
+    }
+
+    /// Runs a navigation key, honouring a pending count prefix. On the
+    /// Monitor panel the count scales the move step; elsewhere `step` is
+    /// repeated `count` times.
+    fn repeat_nav(
+        &mut self,
+        direction: PositionDirection,
+        step: fn(&mut Self),
+    ) {
+        let count = self.count_prefix.take();
+        if self.panel == Panel::Monitor {
+            match count {
+                Some(n) => self.move_monitor_by(direction, n),
+                None => self.move_monitor(direction),
+            }
+            return;
+        }
+        for _ in 0..count.unwrap_or(1) {
+            step(self);
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.repeat_nav(PositionDirection::Up, Self::select_previous_item);
+    }
+
+    pub fn next(&mut self) {
+        self.repeat_nav(PositionDirection::Down, Self::select_next_item);
+    }
+
+    fn select_previous_item(&mut self) {
+        match self.panel {
+            Panel::Mode => self.step_mode_selection(false),
+            Panel::Monitor => {}
+            Panel::Scale => {
+                self.scale_down();
+            }
+            Panel::Color => self.warmer(),
+            Panel::Transform => {
+                let len = TRANSFORMS.len();
+                let i = self
+                    .transform_state
+                    .selected()
+                    .map(|i| step_index(i, len, false, self.wrap_lists))
+                    .unwrap_or(0);
+                self.transform_state.select(Some(i));
+            }
+            Panel::Workspace => {
+                let len = self.workspace_assignments.len();
+                if len == 0 {
+                    return;
+                }
+                let i = self
+                    .workspace_state
+                    .selected()
+                    .map(|i| step_index(i, len, false, self.wrap_lists))
+                    .unwrap_or(0);
+                self.workspace_state.select(Some(i));
+            }
+        }
+    }
+
+    /// Jumps the focused list by a page or to either end.
+    pub fn jump_list(&mut self, jump: ListJump) {
+        let target = |selected: Option<usize>, len: usize, page: usize| {
+            let current = selected.unwrap_or(0);
+            let page = page.max(1);
+            match jump {
+                ListJump::PageUp => current.saturating_sub(page),
+                ListJump::PageDown => {
+                    (current + page).min(len.saturating_sub(1))
+                }
+                ListJump::First => 0,
+                ListJump::Last => len.saturating_sub(1),
+            }
+        };
+        match self.panel {
+            Panel::Mode => {
+                let rows = self.mode_rows();
+                if rows.is_empty() {
+                    return;
+                }
+                let i = target(
+                    self.mode_state.selected(),
+                    rows.len(),
+                    self.mode_list_height,
+                );
+                // Land on a mode, not a resolution header
+                let i = (i..rows.len())
+                    .find(|&i| rows[i].entry().is_some())
+                    .or_else(|| {
+                        (0..i).rev().find(|&i| rows[i].entry().is_some())
+                    });
+                if let Some(i) = i {
+                    self.mode_state.select(Some(i));
+                }
+            }
+            Panel::Transform => {
+                let i = target(
+                    self.transform_state.selected(),
+                    TRANSFORMS.len(),
+                    self.transform_list_height,
+                );
+                self.transform_state.select(Some(i));
+            }
+            Panel::Workspace => {
+                let len = self.workspace_assignments.len();
+                if len == 0 {
+                    return;
+                }
+                let i = target(
+                    self.workspace_state.selected(),
+                    len,
+                    self.workspace_list_height,
+                );
+                self.workspace_state.select(Some(i));
+            }
+            Panel::Monitor | Panel::Scale | Panel::Color => {}
+        }
+    }
+
+    /// Moves the Mode panel selection one mode up or down, skipping the
+    /// resolution headers of the grouped view.
+    fn step_mode_selection(&mut self, forward: bool) {
+        let rows = self.mode_rows();
+        let len = rows.len();
+        let mut i = self.mode_state.selected().unwrap_or(0);
+        for _ in 0..len {
+            let next = step_index(i, len, forward, self.wrap_lists);
+            if next == i {
+                return;
+            }
+            i = next;
+            if rows[i].entry().is_some() {
+                self.mode_state.select(Some(i));
+                return;
+            }
+        }
+    }
+
+    fn select_next_item(&mut self) {
+        match self.panel {
+            Panel::Mode => self.step_mode_selection(true),
+            Panel::Monitor => {}
+            Panel::Scale => {
+                self.scale_up();
+            }
+            Panel::Color => self.cooler(),
+            Panel::Transform => {
+                let len = TRANSFORMS.len();
+                let i = self
+                    .transform_state
+                    .selected()
+                    .map(|i| step_index(i, len, true, self.wrap_lists))
+                    .unwrap_or(0);
+                self.transform_state.select(Some(i));
+            }
+            Panel::Workspace => {
+                let len = self.workspace_assignments.len();
+                if len == 0 {
+                    return;
+                }
+                let i = self
+                    .workspace_state
+                    .selected()
+                    .map(|i| step_index(i, len, true, self.wrap_lists))
+                    .unwrap_or(0);
+                self.workspace_state.select(Some(i));
+            }
+        }
+    }
+
+    pub fn cycle_workspace_monitor(&mut self, forward: bool) {
+        let Some(ws_idx) = self.workspace_state.selected() else {
+            return;
+        };
+
+        let Some(effective) = self.get_effective_workspace(ws_idx) else {
+            return;
+        };
+
+        let monitors: Vec<usize> =
+            self.monitors.iter().enumerate().map(|(i, _)| i).collect();
+
+        if monitors.is_empty() {
+            return;
+        }
+
+        let new_monitor_idx = match effective.monitor_idx {
+            None => {
+                if forward {
+                    Some(monitors[0])
+                } else {
+                    Some(monitors[monitors.len() - 1])
+                }
+            }
+            Some(idx) => {
+                let pos = monitors.iter().position(|&i| i == idx);
+                match pos {
+                    Some(p) => {
+                        if forward {
+                            if p + 1 >= monitors.len() {
+                                None
+                            } else {
+                                Some(monitors[p + 1])
+                            }
+                        } else if p == 0 {
+                            None
+                        } else {
+                            Some(monitors[p - 1])
+                        }
+                    }
+                    None => {
+                        if forward {
+                            Some(monitors[0])
+                        } else {
+                            Some(monitors[monitors.len() - 1])
+                        }
+                    }
+                }
+            }
+        };
+
+        let mut new_ws = effective;
+        new_ws.monitor_idx = new_monitor_idx;
+        self.pending_workspaces.insert(ws_idx, new_ws);
+    }
+
+    pub fn get_effective_workspace(
+        &self,
+        idx: usize,
+    ) -> Option<WorkspaceAssignment> {
+        if let Some(ws) = self.pending_workspaces.get(&idx) {
+            return Some(ws.clone());
+        }
+        self.workspace_assignments.get(idx).cloned()
+    }
+
+    /// Ids of the workspaces assigned to monitor `idx`, counting unapplied
+    /// assignments.
+    pub fn workspaces_on(&self, idx: usize) -> Vec<usize> {
+        (0..self.workspace_assignments.len())
+            .filter_map(|i| self.get_effective_workspace(i))
+            .filter(|ws| ws.monitor_idx == Some(idx))
+            .map(|ws| ws.id)
+            .collect()
+    }
+
+    pub fn has_pending_workspaces(&self) -> bool {
+        !self.pending_workspaces.is_empty()
+    }
+
+    pub fn nav_left(&mut self) {
+        self.repeat_nav(PositionDirection::Left, |app| match app.panel {
+            Panel::Scale => app.scale_down(),
+            Panel::Color => app.warmer(),
+            Panel::Workspace => app.cycle_workspace_monitor(false),
+            _ => {}
+        });
+    }
+
+    pub fn nav_right(&mut self) {
+        self.repeat_nav(PositionDirection::Right, |app| match app.panel {
+            Panel::Scale => app.scale_up(),
+            Panel::Color => app.cooler(),
+            Panel::Workspace => app.cycle_workspace_monitor(true),
+            _ => {}
+        });
+    }
+
+    pub fn toggle_panel(&mut self) {
+        self.panel = match self.panel {
+            Panel::Monitor => Panel::Mode,
+            // Skipped where the compositor has no workspaces to assign
+            Panel::Mode if self.workspace_assignments.is_empty() => {
+                Panel::Scale
+            }
+            Panel::Mode => Panel::Workspace,
+            Panel::Workspace => Panel::Scale,
+            Panel::Scale => Panel::Color,
+            Panel::Color => Panel::Transform,
+            Panel::Transform => Panel::Monitor,
+        };
+    }
+
+    fn workspace_rules(&self) -> Vec<WorkspaceRule> {
+        self.workspace_assignments
+            .iter()
+            .map(|ws| {
+                let monitor_name = ws
+                    .monitor_idx
+                    .and_then(|idx| self.monitors.get(idx))
+                    .map(|m| m.name.clone())
+                    .unwrap_or_default();
+                WorkspaceRule {
+                    id: ws.id,
+                    monitor: monitor_name,
+                    is_default: ws.is_default,
+                    is_persistent: ws.is_persistent,
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the config if autosave is on. Otherwise the change stays
+    /// live-only until `save_now`.
+    pub fn save_config(&mut self) {
+        if self.autosave {
+            self.write_config(true);
+        }
+    }
+
+    /// Saves once the compositor has confirmed every change in flight and
+    /// no safe-apply countdown is running. `changed` is whether monitor
+    /// events arrived since the last call.
+    pub fn save_when_settled(&mut self, changed: bool) {
+        if (changed || self.expire_batch())
+            && !self.batch_in_flight()
+            && self.pending_revert.is_none()
+        {
+            self.save_config();
+        }
+    }
+
+    /// Writes the monitor config and reloads the compositor even when
+    /// nothing changed, to re-sync a file edited elsewhere.
+    pub fn save_now(&mut self) {
+        if self.batch_in_flight() || self.pending_revert.is_some() {
+            self.set_status(
+                "Waiting for the last change to settle before saving",
+            );
+            return;
+        }
+        self.clear_error();
+        self.needs_save = true;
+        self.write_config(true);
+        if self.error_message.is_none() {
+            match self.compositor {
+                compositor::Compositor::Hyprland
+                | compositor::Compositor::Sway => self.set_status(format!(
+                    "Saved the monitor config and reloaded {}",
+                    self.compositor.label()
+                )),
+                _ => self.set_status("Saved the monitor config"),
+            }
+        }
+    }
+
+    /// Applied changes that autosave is off for and that haven't been
+    /// written yet.
+    pub fn has_unsaved_changes(&self) -> bool {
+        !self.autosave && self.needs_save
+    }
+
+    /// Writes the compositor config, reloading the compositor afterwards
+    /// when `reload_after` is set.
+    fn write_config(&mut self, reload_after: bool) {
+        if !self.needs_save {
+            return;
+        }
+        // Left set when refused or failed, so the next save tries again
+        if self.refuse_overlaps() {
+            return;
+        }
+        if self.collapse_pending && !self.collapse_duplicate_entries() {
+            return;
+        }
+
+        let workspace_rules = self.workspace_rules();
+        let context = SaveContext {
+            descriptions: self
+                .match_by_description
+                .then_some(&self.monitor_descriptions),
+            sway_tuning: &self.sway_tuning,
+            header: &self.config_header,
+            startup_positions: &self.startup_positions,
+        };
+        if let Err(e) = save_monitor_config(
+            self.compositor,
+            &self.comp_monitor_config_path,
+            &self.monitors,
+            &workspace_rules,
+            &context,
+        ) {
+            let e = WriteAccessError::new(&self.comp_monitor_config_path, e);
+            self.set_error(format!("Failed to save config: {e}"));
+        } else {
+            self.needs_save = false;
+            if reload_after && let Err(e) = reload(self.compositor) {
+                self.set_error(format!(
+                    "Saved the config, but {} failed to reload it: {e}",
+                    self.compositor.label()
+                ));
+            }
+            self.run_wallpapers();
+        }
+        self.live_workspaces = compositor::live_workspaces(self.compositor);
+
+        self.monitor_memory.record(&self.monitors);
+        if self.persist_state
+            && let Err(e) = self.monitor_memory.save()
+        {
+            self.set_error(format!("Failed to save monitor positions: {e}"));
+        }
+    }
+
+    pub fn reset_positions(&mut self) {
+        self.pending_enable = None;
+        self.pending_positions.clear();
+        self.pending_workspaces.clear();
+    }
+
+    pub fn select_monitor(&mut self, idx: usize) {
+        if idx >= self.monitors.len() {
+            return;
+        }
+        if self.pending_enable.is_some_and(|pending| pending != idx) {
+            self.cancel_enable();
+        }
+        self.stage_panel_values();
+        self.selected_monitor = idx;
+        self.mode_state.select(Some(0));
+        self.sync_panel_state();
+    }
+
+    pub fn select_next_monitor(&mut self) {
+        if self.monitors.is_empty() {
+            return;
+        }
+        self.select_monitor((self.selected_monitor + 1) % self.monitors.len());
+    }
+
+    pub fn select_prev_monitor(&mut self) {
+        if self.monitors.is_empty() {
+            return;
+        }
+        let idx = if self.selected_monitor == 0 {
+            self.monitors.len() - 1
+        } else {
+            self.selected_monitor - 1
+        };
+        self.select_monitor(idx);
+    }
+
+    pub fn start_search(&mut self) {
+        self.search = Some(TextInput::default());
+    }
+
+    pub fn cancel_search(&mut self) {
+        self.search = None;
+    }
+
+    /// Opens the custom mode prompt, prefilled with the current mode.
+    pub fn start_custom_mode(&mut self) {
+        if self.panel != Panel::Mode {
+            return;
+        }
+        let Some(monitor) = self.selected_monitor() else {
+            return;
+        };
+        let current = monitor
+            .modes
+            .iter()
+            .find(|m| m.is_current)
+            .map(|m| {
+                format!(
+                    "{}x{}@{}",
+                    m.resolution.width, m.resolution.height, m.refresh_rate
+                )
+            })
+            .unwrap_or_default();
+        self.custom_mode = Some(TextInput::new(current));
+    }
+
+    pub fn cancel_custom_mode(&mut self) {
+        self.custom_mode = None;
+    }
+
+    /// Applies the typed mode. Invalid input keeps the prompt open.
+    pub fn submit_custom_mode(&mut self) {
+        let Some(input) = self.custom_mode.take() else {
+            return;
+        };
+        let mode = match CustomMode::parse(input.value()) {
+            Ok(mode) => mode,
+            Err(msg) => {
+                self.set_error(msg);
+                self.custom_mode = Some(input);
+                return;
+            }
+        };
+        let Some(monitor) = self.selected_monitor() else {
+            return;
+        };
+        let name = monitor.name.clone();
+        let previous =
+            monitor
+                .modes
+                .iter()
+                .find(|m| m.is_current)
+                .map(|m| Revert::Mode {
+                    width: m.resolution.width,
+                    height: m.resolution.height,
+                    refresh_rate: m.refresh_rate,
+                });
+
+        if let Err(e) = custom_mode::apply(self.compositor, monitor, mode) {
+            self.set_error(format!("Failed to set {mode} on {name}: {e}"));
+            return;
+        }
+        self.set_status(format!("{name} → {mode} (custom)"));
+        if let Some(previous) = previous {
+            self.arm_revert(name, previous);
+        }
+    }
+
+    /// Returns the first monitor whose name or description contains
+    /// `query`, ignoring case.
+    pub fn find_monitor(&self, query: &str) -> Option<usize> {
+        let query = query.to_lowercase();
+        self.monitors.iter().position(|m| {
+            [
+                Some(m.name.as_str()),
+                Some(m.description.as_str()),
+                self.monitor_description(&m.name),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|text| text.to_lowercase().contains(&query))
+        })
+    }
+
+    pub fn submit_search(&mut self) {
+        let Some(input) = self.search.take() else {
+            return;
+        };
+        let query = input.value().trim();
+        if query.is_empty() {
+            return;
+        }
+        match self.find_monitor(query) {
+            Some(idx) => self.select_monitor(idx),
+            None => self.set_error(format!("No monitor matches '{query}'")),
+        }
+    }
+
+    pub fn start_toggle_by_name(&mut self) {
+        self.toggle_prompt = Some(TextInput::default());
+    }
+
+    pub fn cancel_toggle_by_name(&mut self) {
+        self.toggle_prompt = None;
+    }
+
+    /// Toggles the monitor whose name matches the prompt exactly, ignoring
+    /// case. The monitor becomes the selected one, so turning off the last
+    /// enabled monitor still goes through the usual warning.
+    pub fn submit_toggle_by_name(
+        &mut self,
+    ) -> Result<(), SendError<WlMonitorAction>> {
+        let Some(input) = self.toggle_prompt.take() else {
+            return Ok(());
+        };
+        let name = input.value().trim();
+        if name.is_empty() {
+            return Ok(());
+        }
+        let idx = match self.monitor_named(name) {
+            Ok(idx) => idx,
+            Err(msg) => {
+                self.set_error(msg);
+                return Ok(());
+            }
+        };
+        self.select_monitor(idx);
+        self.toggle_monitor()?;
+        // Enabling previews the placement and sets its own status
+        if !self.pending_last_toggle_monitor && self.monitors[idx].enabled {
+            self.set_status(format!("Turning {} off", self.monitors[idx].name));
+        }
+        Ok(())
+    }
+
+    fn sync_panel_state(&mut self) {
+        let idx = self.selected_monitor;
+        let Some(monitor) = self.monitors.get(idx) else {
+            return;
+        };
+        self.pending_scale =
+            self.pending_scales.remove(&idx).unwrap_or(monitor.scale);
+        self.pending_color_temp = self.color_temp(&monitor.name);
+        let transform = self
+            .pending_transforms
+            .remove(&idx)
+            .unwrap_or(monitor.transform);
+        if let Some(tidx) = TRANSFORMS.iter().position(|&x| x == transform) {
+            self.transform_state.select(Some(tidx));
+        }
+        // A freshly plugged monitor may have no current mode yet; highlight
+        // the one the compositor would pick by default.
+        let mode_idx = monitor
+            .modes
+            .iter()
+            .position(|m| m.is_current)
+            .or_else(|| monitor.modes.iter().position(|m| m.preferred))
+            .unwrap_or(0);
+        self.select_mode_index(mode_idx);
+    }
+
+    pub fn toggle_persistent(&mut self) {
+        let Some(ws_idx) = self.workspace_state.selected() else {
+            return;
+        };
+
+        let Some(mut effective) = self.get_effective_workspace(ws_idx) else {
+            return;
+        };
+        effective.is_persistent = !effective.is_persistent;
+        self.pending_workspaces.insert(ws_idx, effective);
+    }
+
+    pub fn toggle_default(&mut self) {
+        let Some(ws_idx) = self.workspace_state.selected() else {
+            return;
+        };
+
+        let Some(effective) = self.get_effective_workspace(ws_idx) else {
+            return;
+        };
+
+        let new_default_monitor_idx = if effective.is_default {
+            None
+        } else {
+            effective.monitor_idx
+        };
+
+        let Some(mut effective) = self.get_effective_workspace(ws_idx) else {
+            return;
+        };
+        effective.is_default = new_default_monitor_idx.is_some();
+
+        if let Some(target_monitor) = new_default_monitor_idx {
+            for (_, w) in self.pending_workspaces.iter_mut() {
+                if w.is_default && w.monitor_idx == Some(target_monitor) {
+                    w.is_default = false;
+                }
+            }
+            for w in self.workspace_assignments.iter_mut() {
+                if w.is_default && w.monitor_idx == Some(target_monitor) {
+                    w.is_default = false;
+                }
+            }
+        }
+
+        self.pending_workspaces.insert(ws_idx, effective);
+    }
+
+    pub fn apply_action(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        if matches!(self.panel, Panel::Mode | Panel::Scale)
+            && self.mode_and_scale_pending()
+        {
+            return self.apply_mode_and_scale();
+        }
+        match self.panel {
+            Panel::Mode => self.apply_mode()?,
+            Panel::Scale => self.apply_scale()?,
+            Panel::Color => {
+                // Not part of the monitor config, so nothing to save
+                self.apply_color_temp();
+                return Ok(());
+            }
+            Panel::Transform => self.apply_transform()?,
+            Panel::Monitor => {
+                self.confirm_enable()?;
+                if self.pending_positions.is_empty() || self.refuse_overlaps() {
+                    return Ok(());
+                }
+                for (&idx, &(x, y)) in &self.pending_positions {
+                    if let Some(monitor) = self.monitors.get_mut(idx) {
+                        monitor.position.x = x;
+                        monitor.position.y = y;
+                    }
+                }
+                self.apply_positions()?;
+                let names: Vec<String> = self
+                    .pending_positions
+                    .keys()
+                    .filter_map(|&idx| self.monitors.get(idx))
+                    .map(|m| m.name.clone())
+                    .collect();
+                self.pending_positions.clear();
+                // Saved once the compositor confirms every monitor
+                self.start_batch(names);
+                return Ok(());
+            }
+            Panel::Workspace => {
+                if self.pending_workspaces.is_empty() {
+                    return Ok(());
+                }
+                let before = self.workspace_rules();
+                for (&idx, ws) in &self.pending_workspaces {
+                    if let Some(existing) =
+                        self.workspace_assignments.get_mut(idx)
+                    {
+                        existing.monitor_idx = ws.monitor_idx;
+                        existing.is_default = ws.is_default;
+                        existing.is_persistent = ws.is_persistent;
+                    }
+                }
+                self.pending_workspaces.clear();
+
+                // Sway takes workspace assignments live, so the config is
+                // written without the reload Hyprland needs.
+                if self.compositor == compositor::Compositor::Sway
+                    && self.pending_revert.is_none()
+                {
+                    let commands = compositor::sway_workspace_commands(
+                        &before,
+                        &self.workspace_rules(),
+                    );
+                    match compositor::apply_sway_workspaces(&commands) {
+                        Ok(()) => {
+                            self.needs_save = true;
+                            if self.autosave {
+                                self.write_config(false);
+                            }
+                            return Ok(());
+                        }
+                        Err(e) => self.set_error(format!(
+                            "swaymsg failed, reloading instead: {e}"
+                        )),
+                    }
+                }
+            }
+        }
+        self.needs_save = true;
+        // Saved once the change is kept
+        if self.pending_revert.is_none() {
+            self.save_config();
+        }
+
+        Ok(())
+    }
+
+    /// Applies every pending change, whichever panel it was made in, then
+    /// quits once the compositor has taken them and the config is written.
+    pub fn apply_all_and_quit(
+        &mut self,
+    ) -> Result<(), SendError<WlMonitorAction>> {
+        let focused = self.panel;
+        // The selected monitor's own go through its panels below
+        self.apply_staged_scales()?;
+        self.apply_staged_transforms()?;
+        let summary = self.pending_summary();
+        let mode_pending = focused == Panel::Mode
+            && self
+                .selected_monitor()
+                .zip(self.selected_mode_index())
+                .and_then(|(m, idx)| m.modes.get(idx))
+                .is_some_and(|mode| !mode.is_current);
+
+        let panels = [
+            (Panel::Monitor, summary.moved > 0),
+            (Panel::Workspace, summary.workspaces > 0),
+            (Panel::Mode, mode_pending),
+            // Applied together with the mode when both are pending
+            (Panel::Scale, summary.scale && !mode_pending),
+            (Panel::Transform, summary.transform),
+        ];
+        for (panel, pending) in panels {
+            if !pending {
+                continue;
+            }
+            self.panel = panel;
+            let applied = self.apply_action();
+            self.panel = focused;
+            applied?;
+            // Overlaps and risky modes ask first; stay so the user can answer
+            if self.pending_confirm.is_some()
+                || self.pending_risky_mode.is_some()
+            {
+                return Ok(());
+            }
+        }
+
+        self.quit_after_apply = true;
+        self.set_status("Applying changes, quitting once they are saved");
+        Ok(())
+    }
+
+    /// Whether apply-and-quit is done: everything it sent has been
+    /// confirmed or kept, and the config has been written.
+    pub fn ready_to_quit(&mut self) -> bool {
+        if !self.quit_after_apply
+            || self.batch_in_flight()
+            || self.pending_revert.is_some()
+        {
+            return false;
+        }
+        self.write_config(true);
+        true
+    }
+
+    /// Selects the monitor's preferred (native) mode and applies it.
+    pub fn apply_preferred_mode(
+        &mut self,
+    ) -> Result<(), SendError<WlMonitorAction>> {
+        if self.panel != Panel::Mode {
+            return Ok(());
+        }
+        let Some(monitor) = self.selected_monitor() else {
+            return Ok(());
+        };
+        let Some(idx) = monitor.modes.iter().position(|m| m.preferred) else {
+            self.set_status(format!("{} has no preferred mode", monitor.name));
+            return Ok(());
+        };
+        self.select_mode_index(idx);
+        self.apply_action()
+    }
+
+    /// Selects and applies the highest refresh rate available at the
+    /// monitor's current resolution.
+    pub fn apply_max_refresh(
+        &mut self,
+    ) -> Result<(), SendError<WlMonitorAction>> {
+        if self.panel != Panel::Mode {
+            return Ok(());
+        }
+        let Some(monitor) = self.selected_monitor() else {
+            return Ok(());
+        };
+        let Some(current) = monitor.modes.iter().find(|m| m.is_current) else {
+            self.set_status(format!("{} has no current mode", monitor.name));
+            return Ok(());
+        };
+        let (width, height) =
+            (current.resolution.width, current.resolution.height);
+        let Some((idx, best)) = monitor
+            .modes
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| {
+                m.resolution.width == width && m.resolution.height == height
+            })
+            .max_by_key(|(_, m)| m.refresh_rate)
+        else {
+            return Ok(());
+        };
+
+        if best.is_current {
+            self.set_status(format!(
+                "{} is already at its highest refresh rate ({}Hz)",
+                monitor.name, best.refresh_rate
+            ));
+            return Ok(());
+        }
+
+        let msg = format!(
+            "{} → {}x{}@{}Hz",
+            monitor.name, width, height, best.refresh_rate
+        );
+        self.select_mode_index(idx);
+        self.set_status(msg);
+        self.apply_action()
+    }
+
+    /// Rows of the Mode panel for the selected monitor.
+    pub fn mode_rows(&self) -> Vec<ModeRow> {
+        self.selected_monitor()
+            .map(|m| mode_rows(m, self.show_raw_modes, self.group_modes))
+            .unwrap_or_default()
+    }
+
+    /// Index into the selected monitor's modes of the highlighted row.
+    fn selected_mode_index(&self) -> Option<usize> {
+        self.mode_rows()
+            .get(self.mode_state.selected()?)?
+            .entry()
+            .map(|entry| entry.index)
+    }
+
+    /// Highlights the row standing for the selected monitor's `modes[index]`.
+    fn select_mode_index(&mut self, index: usize) {
+        let rows = self.mode_rows();
+        let row = self.selected_monitor().and_then(|monitor| {
+            let target = monitor.modes.get(index)?;
+            let entry_at = |row: &ModeRow| row.entry().map(|entry| entry.index);
+            rows.iter()
+                .position(|row| entry_at(row) == Some(index))
+                .or_else(|| {
+                    rows.iter().position(|row| {
+                        entry_at(row).is_some_and(|i| {
+                            let mode = &monitor.modes[i];
+                            mode.resolution.width == target.resolution.width
+                                && mode.resolution.height
+                                    == target.resolution.height
+                                && mode.refresh_rate == target.refresh_rate
+                        })
+                    })
+                })
+        });
+        let first = rows.iter().position(|row| row.entry().is_some());
+        self.mode_state.select(Some(row.or(first).unwrap_or(0)));
+    }
+
+    pub fn toggle_raw_modes(&mut self) {
+        if self.panel != Panel::Mode {
+            return;
+        }
+        let selected = self.selected_mode_index();
+        self.show_raw_modes = !self.show_raw_modes;
+        if let Some(idx) = selected {
+            self.select_mode_index(idx);
+        }
+        self.set_status(if self.show_raw_modes {
+            "Showing every reported mode"
+        } else {
+            "Hiding duplicate modes"
+        });
+    }
+
+    pub fn toggle_mode_grouping(&mut self) {
+        if self.panel != Panel::Mode {
+            return;
+        }
+        let selected = self.selected_mode_index();
+        self.group_modes = !self.group_modes;
+        self.select_mode_index(selected.unwrap_or(0));
+    }
+
+    /// Whether `modes[idx]` is a non-preferred mode well above the preferred
+    /// refresh rate, the kind that can push a monitor out of sync.
+    fn is_risky_mode(&self, idx: usize) -> bool {
+        let Some(monitor) = self.selected_monitor() else {
+            return false;
+        };
+        let Some(mode) = monitor.modes.get(idx) else {
+            return false;
+        };
+        let Some(preferred) = monitor.modes.iter().find(|m| m.preferred) else {
+            return false;
+        };
+        !mode.preferred
+            && mode.refresh_rate as f64
+                > preferred.refresh_rate as f64 * RISKY_REFRESH_RATIO
+    }
+
+    pub fn dismiss_risky_mode(&mut self) {
+        self.pending_risky_mode = None;
+    }
+
+    /// Holds back a risky mode until it is applied a second time.
+    /// Returns true while the mode is held back.
+    fn hold_risky_mode(&mut self, mode_idx: usize) -> bool {
+        let confirmed = self.pending_risky_mode.take() == Some(mode_idx);
+        if !confirmed && self.is_risky_mode(mode_idx) {
+            self.pending_risky_mode = Some(mode_idx);
+            self.set_status(
+                "⚠ This mode is far above the preferred refresh rate and may produce no signal",
+            );
+            return true;
+        }
+        false
+    }
+
+    fn apply_mode(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        let Some(mode_idx) = self.selected_mode_index() else {
+            return Ok(());
+        };
+        if self.hold_risky_mode(mode_idx) {
+            return Ok(());
+        }
+
+        let Some(monitor) = self.selected_monitor() else {
+            return Ok(());
+        };
+        let Some(mode) = monitor.modes.get(mode_idx) else {
+            return Ok(());
+        };
+        let name = monitor.name.clone();
+        let previous =
+            monitor
+                .modes
+                .iter()
+                .find(|m| m.is_current)
+                .map(|m| Revert::Mode {
+                    width: m.resolution.width,
+                    height: m.resolution.height,
+                    refresh_rate: m.refresh_rate,
+                });
+
+        self.wlx_action_handler.send(WlMonitorAction::SwitchMode {
+            name: name.clone(),
+            width: mode.resolution.width,
+            height: mode.resolution.height,
+            refresh_rate: mode.refresh_rate,
+        })?;
+
+        if let Some(previous) = previous {
+            self.arm_revert(name, previous);
+        }
+        Ok(())
+    }
+
+    /// Whether the selected monitor has both a new mode highlighted in the
+    /// Mode panel and a new scale set in the Scale panel.
+    fn mode_and_scale_pending(&self) -> bool {
+        let Some(monitor) = self.selected_monitor() else {
+            return false;
+        };
+        let mode_pending = self
+            .selected_mode_index()
+            .and_then(|idx| monitor.modes.get(idx))
+            .is_some_and(|mode| !mode.is_current);
+        mode_pending && (monitor.scale - self.pending_scale).abs() > 0.001
+    }
+
+    /// Sends the pending mode and scale back to back and saves once the
+    /// compositor has confirmed both, so the config is written and reloaded
+    /// a single time rather than once at the new mode with the old scale.
+    fn apply_mode_and_scale(
+        &mut self,
+    ) -> Result<(), SendError<WlMonitorAction>> {
+        let Some(mode_idx) = self.selected_mode_index() else {
+            return Ok(());
+        };
+        if self.refuse_scale() || self.hold_risky_mode(mode_idx) {
+            return Ok(());
+        }
+        let Some(monitor) = self.selected_monitor() else {
+            return Ok(());
+        };
+        let Some(mode) = monitor.modes.get(mode_idx) else {
+            return Ok(());
+        };
+        let name = monitor.name.clone();
+        let (width, height) = (mode.resolution.width, mode.resolution.height);
+        let refresh_rate = mode.refresh_rate;
+        let previous = monitor.modes.iter().find(|m| m.is_current).map(|m| {
+            Revert::ModeAndScale {
+                width: m.resolution.width,
+                height: m.resolution.height,
+                refresh_rate: m.refresh_rate,
+                scale: monitor.scale,
+            }
+        });
+
+        self.wlx_action_handler.send(WlMonitorAction::SwitchMode {
+            name: name.clone(),
+            width,
+            height,
+            refresh_rate,
+        })?;
+        self.wlx_action_handler.send(WlMonitorAction::SetScale {
+            name: name.clone(),
+            scale: self.pending_scale,
+        })?;
+        self.start_batch([name.clone(), name.clone()]);
+        self.set_status(format!(
+            "{} → {}x{}@{}Hz at scale {:.2}",
+            name, width, height, refresh_rate, self.pending_scale
+        ));
+
+        if let Some(previous) = previous {
+            self.arm_revert(name, previous);
+        }
+        Ok(())
+    }
+
+    /// Reports and returns true if the compositor can't take the pending
+    /// scale as it is.
+    fn refuse_scale(&mut self) -> bool {
+        let capabilities = self.compositor.capabilities();
+        match capabilities.check_scale(self.pending_scale, self.scale_limits) {
+            Ok(()) => false,
+            Err(msg) => {
+                self.set_error(format!(
+                    "{} would not apply it: {}",
+                    self.compositor.label(),
+                    msg
+                ));
+                true
+            }
         }
     }
 
-    pub fn reset_positions(&mut self) {
-        self.pending_positions.clear();
-        self.pending_workspaces.clear();
+    fn apply_scale(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        if self.refuse_scale() {
+            return Ok(());
+        }
+        let staged = self.apply_staged_scales()?;
+        let Some(monitor) = self.selected_monitor() else {
+            return Ok(());
+        };
+        if staged > 0 && (monitor.scale - self.pending_scale).abs() <= 0.001 {
+            return Ok(());
+        }
+        let name = monitor.name.clone();
+        let previous = Revert::Scale(monitor.scale);
+        self.wlx_action_handler.send(WlMonitorAction::SetScale {
+            name: name.clone(),
+            scale: self.pending_scale,
+        })?;
+        self.arm_revert(name, previous);
+        Ok(())
     }
 
-    pub fn select_next_monitor(&mut self) {
-        if self.monitors.is_empty() {
-            return;
+    fn apply_transform(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        let staged = self.apply_staged_transforms()?;
+        let Some(monitor) = self.selected_monitor() else {
+            return Ok(());
+        };
+        let Some(transform) = self.selected_transform() else {
+            return Ok(());
+        };
+        if staged > 0 && transform == monitor.transform {
+            return Ok(());
         }
-        self.selected_monitor = (self.selected_monitor + 1) % self.monitors.len();
-        self.mode_state.select(Some(0));
-        self.sync_panel_state();
+        let name = monitor.name.clone();
+        let previous = Revert::Transform(monitor.transform);
+        self.send_transform(&name, transform)?;
+        self.arm_revert(name, previous);
+        Ok(())
     }
 
-    pub fn select_prev_monitor(&mut self) {
-        if self.monitors.is_empty() {
+    /// Starts the countdown after a change when safe apply is on.
+    fn arm_revert(&mut self, monitor: String, revert: Revert) {
+        if !self.safe_apply {
             return;
         }
-        self.selected_monitor = if self.selected_monitor == 0 {
-            self.monitors.len() - 1
-        } else {
-            self.selected_monitor - 1
-        };
-        self.mode_state.select(Some(0));
-        self.sync_panel_state();
+        self.pending_revert = Some(PendingRevert {
+            monitor,
+            revert,
+            deadline: Instant::now() + Duration::from_secs(SAFE_APPLY_SECS),
+        });
     }
 
-    fn sync_panel_state(&mut self) {
-        let Some(monitor) = self.monitors.get(self.selected_monitor) else {
-            return;
+    /// Whole seconds left before the pending change is reverted.
+    pub fn revert_seconds_left(&self) -> Option<u64> {
+        let pending = self.pending_revert.as_ref()?;
+        let left = pending.deadline.saturating_duration_since(Instant::now());
+        Some(left.as_secs() + u64::from(left.subsec_nanos() > 0))
+    }
+
+    pub fn keep_change(&mut self) {
+        if let Some(pending) = self.pending_revert.take() {
+            self.set_status(format!("Kept the change to {}", pending.monitor));
+            self.save_config();
+        }
+    }
+
+    /// Restores the state from before the pending change.
+    pub fn revert_change(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        let Some(pending) = self.pending_revert.take() else {
+            return Ok(());
         };
-        self.pending_scale = monitor.scale;
-        if let Some(tidx) = TRANSFORMS.iter().position(|&x| x == monitor.transform) {
-            self.transform_state.select(Some(tidx));
+        let name = pending.monitor;
+        let actions = match pending.revert {
+            Revert::Mode {
+                width,
+                height,
+                refresh_rate,
+            } => vec![WlMonitorAction::SwitchMode {
+                name: name.clone(),
+                width,
+                height,
+                refresh_rate,
+            }],
+            Revert::Scale(scale) => vec![WlMonitorAction::SetScale {
+                name: name.clone(),
+                scale,
+            }],
+            Revert::Transform(transform) => {
+                vec![WlMonitorAction::SetTransform {
+                    name: name.clone(),
+                    transform,
+                }]
+            }
+            Revert::ModeAndScale {
+                width,
+                height,
+                refresh_rate,
+                scale,
+            } => vec![
+                WlMonitorAction::SwitchMode {
+                    name: name.clone(),
+                    width,
+                    height,
+                    refresh_rate,
+                },
+                WlMonitorAction::SetScale {
+                    name: name.clone(),
+                    scale,
+                },
+            ],
+        };
+        for action in actions {
+            self.wlx_action_handler.send(action)?;
         }
-        if let Some(mode_idx) = monitor.modes.iter().position(|m| m.is_current) {
-            self.mode_state.select(Some(mode_idx));
-        } else {
-            self.mode_state.select(Some(0));
+        // The config was never written for the reverted change
+        self.needs_save = false;
+        self.set_status(format!("Reverted the change to {}", name));
+        Ok(())
+    }
+
+    /// Reverts the pending change once its countdown runs out.
+    pub fn expire_revert(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        match &self.pending_revert {
+            Some(pending) if Instant::now() >= pending.deadline => {
+                self.revert_change()
+            }
+            _ => Ok(()),
         }
     }
 
-    pub fn toggle_persistent(&mut self) {
-        let Some(ws_idx) = self.workspace_state.selected() else {
-            return;
-        };
+    pub fn toggle_table_view(&mut self) {
+        self.table_view = !self.table_view;
+    }
 
-        let Some(mut effective) = self.get_effective_workspace(ws_idx) else {
+    /// Pins the selected monitor so moves can't shift it, or releases it.
+    /// The lock is remembered across runs.
+    pub fn toggle_lock(&mut self) {
+        let Some(name) = self.selected_monitor().map(|m| m.name.clone()) else {
             return;
         };
-        effective.is_persistent = !effective.is_persistent;
-        self.pending_workspaces.insert(ws_idx, effective);
+        let locked = self.monitor_memory.toggle_lock(&name);
+        if self.persist_state
+            && let Err(e) = self.monitor_memory.save()
+        {
+            self.set_error(format!("Failed to save monitor lock: {e}"));
+        } else if locked {
+            self.set_status(format!("{name} locked in place"));
+        } else {
+            self.set_status(format!("{name} unlocked"));
+        }
     }
 
-    pub fn toggle_default(&mut self) {
-        let Some(ws_idx) = self.workspace_state.selected() else {
+    /// Points out each enabled monitor in turn, left to right, so connector
+    /// names can be matched to the screens. Pressing it again stops early.
+    pub fn start_identify(&mut self) {
+        if self.identify.is_some() {
+            self.stop_identify();
             return;
-        };
-
-        let Some(effective) = self.get_effective_workspace(ws_idx) else {
+        }
+        let mut order: Vec<usize> = (0..self.monitors.len())
+            .filter(|&i| self.monitors[i].enabled)
+            .collect();
+        if order.is_empty() {
             return;
-        };
+        }
+        order.sort_by_key(|&i| {
+            let (x, y) = self.display_position(i);
+            (x, y)
+        });
+        let names: Vec<String> = order
+            .iter()
+            .map(|&i| self.monitors[i].name.clone())
+            .collect();
+        let bars = identify::show(&names);
+        if bars.is_empty() {
+            self.set_status("Identifying on the map only; install swaynag to label the screens");
+        }
+        self.identify = Some(Identify {
+            started: Instant::now(),
+            order,
+            bars,
+        });
+    }
 
-        let new_default_monitor_idx = if effective.is_default { None } else { effective.monitor_idx };
+    pub fn stop_identify(&mut self) {
+        if let Some(mut current) = self.identify.take() {
+            identify::hide(&mut current.bars);
+        }
+    }
 
-        let Some(mut effective) = self.get_effective_workspace(ws_idx) else {
-            return;
-        };
-        effective.is_default = new_default_monitor_idx.is_some();
+    /// The monitor currently pointed out on the map.
+    pub fn identified_monitor(&self) -> Option<usize> {
+        let current = self.identify.as_ref()?;
+        let step = current.started.elapsed().as_millis() / IDENTIFY_STEP_MS;
+        current.order.get(step as usize).copied()
+    }
 
-        if let Some(target_monitor) = new_default_monitor_idx {
-            for (_, w) in self.pending_workspaces.iter_mut() {
-                if w.is_default && w.monitor_idx == Some(target_monitor) {
-                    w.is_default = false;
-                }
-            }
-            for w in self.workspace_assignments.iter_mut() {
-                if w.is_default && w.monitor_idx == Some(target_monitor) {
-                    w.is_default = false;
-                }
-            }
+    /// Ends identify once every monitor had its turn.
+    pub fn expire_identify(&mut self) {
+        if self.identify.is_some() && self.identified_monitor().is_none() {
+            self.stop_identify();
         }
+    }
 
-        self.pending_workspaces.insert(ws_idx, effective);
+    pub fn toggle_workspace_badges(&mut self) {
+        self.show_workspace_badges = !self.show_workspace_badges;
     }
 
-    pub fn apply_action(&mut self) -> Result<(), SendError<WlMonitorAction>> {
-        match self.panel {
-            Panel::Mode => self.apply_mode()?,
-            Panel::Scale => self.apply_scale()?,
-            Panel::Transform => self.apply_transform()?,
-            Panel::Monitor => {
-                if self.pending_positions.is_empty() {
-                    return Ok(());
-                }
-                for (&idx, &(x, y)) in &self.pending_positions {
-                    if let Some(monitor) = self.monitors.get_mut(idx) {
-                        monitor.position.x = x;
-                        monitor.position.y = y;
-                    }
-                }
-                self.apply_positions()?;
-                self.pending_positions.clear();
-            }
-            Panel::Workspace => {
-                if self.pending_workspaces.is_empty() {
-                    return Ok(());
-                }
-                for (&idx, ws) in &self.pending_workspaces {
-                    if let Some(existing) = self.workspace_assignments.get_mut(idx) {
-                        existing.monitor_idx = ws.monitor_idx;
-                        existing.is_default = ws.is_default;
-                        existing.is_persistent = ws.is_persistent;
-                    }
-                }
-                self.pending_workspaces.clear();
-            }
-        }
-        self.needs_save = true;
-        self.save_config();
+    pub fn toggle_footer(&mut self) {
+        self.expanded_footer = !self.expanded_footer;
+    }
+
+    pub fn toggle_version(&mut self) {
+        self.show_version = !self.show_version;
+    }
+
+    pub fn toggle_legend(&mut self) {
+        self.show_legend = !self.show_legend;
+    }
+
+    /// The transform highlighted in the Transform panel, if it differs from
+    /// the selected monitor's current one.
+    pub fn pending_transform(&self) -> Option<WlTransform> {
+        let monitor = self.selected_monitor()?;
+        self.selected_transform()
+            .filter(|&t| t != monitor.transform)
+    }
+
+    fn selected_transform(&self) -> Option<WlTransform> {
+        let idx = self.transform_state.selected()?;
+        TRANSFORMS.get(idx).copied()
+    }
+
+    fn send_transform(
+        &self,
+        name: &str,
+        transform: WlTransform,
+    ) -> Result<(), SendError<WlMonitorAction>> {
+        self.wlx_action_handler
+            .send(WlMonitorAction::SetTransform {
+                name: name.to_string(),
+                transform,
+            })?;
 
         Ok(())
     }
 
-    fn apply_mode(&self) -> Result<(), SendError<WlMonitorAction>> {
+    /// Flips the selected monitor between landscape (Normal) and portrait,
+    /// reusing whichever of Rotate90/Rotate270 it was last rotated to.
+    pub fn toggle_portrait(
+        &mut self,
+    ) -> Result<(), SendError<WlMonitorAction>> {
         let Some(monitor) = self.selected_monitor() else {
             return Ok(());
         };
-        let Some(mode_idx) = self.mode_state.selected() else {
-            return Ok(());
-        };
-        let Some(mode) = monitor.modes.get(mode_idx) else {
-            return Ok(());
+        let name = monitor.name.clone();
+        let current = monitor.transform;
+
+        let target = match current {
+            WlTransform::Rotate90 | WlTransform::Rotate270 => {
+                self.portrait_transforms.insert(name.clone(), current);
+                WlTransform::Normal
+            }
+            WlTransform::Flipped90 | WlTransform::Flipped270 => {
+                WlTransform::Normal
+            }
+            _ => self
+                .portrait_transforms
+                .get(&name)
+                .copied()
+                .unwrap_or(WlTransform::Rotate90),
         };
 
-        self.wlx_action_handler.send(WlMonitorAction::SwitchMode {
-            name: monitor.name.clone(),
-            width: mode.resolution.width,
-            height: mode.resolution.height,
-            refresh_rate: mode.refresh_rate,
-        })?;
+        self.send_transform(&name, target)?;
+        self.arm_revert(name, Revert::Transform(current));
 
+        // Update locally so the map previews the new orientation right away;
+        // the compositor's Changed event confirms it.
+        if let Some(idx) = TRANSFORMS.iter().position(|&t| t == target) {
+            self.transform_state.select(Some(idx));
+        }
+        self.monitors[self.selected_monitor].transform = target;
+        self.needs_save = true;
         Ok(())
     }
 
-    fn apply_scale(&self) -> Result<(), SendError<WlMonitorAction>> {
-        let Some(monitor) = self.selected_monitor() else {
-            return Ok(());
-        };
-        self.wlx_action_handler.send(WlMonitorAction::SetScale {
-            name: monitor.name.clone(),
-            scale: self.pending_scale,
-        })?;
-        Ok(())
+    /// Asks for confirmation before applying the selected transform to every
+    /// enabled monitor.
+    pub fn request_rotate_all(&mut self) {
+        if self.panel != Panel::Transform || self.selected_transform().is_none()
+        {
+            return;
+        }
+        self.pending_confirm = Some(Confirm::RotateAll);
     }
 
-    fn apply_transform(&self) -> Result<(), SendError<WlMonitorAction>> {
-        let Some(monitor) = self.selected_monitor() else {
+    fn rotate_all(&mut self) -> Result<(), SendError<WlMonitorAction>> {
+        let Some(transform) = self.selected_transform() else {
             return Ok(());
         };
-        let Some(idx) = self.transform_state.selected() else {
-            return Ok(());
-        };
-        let Some(&transform) = TRANSFORMS.get(idx) else {
-            return Ok(());
-        };
-
-        self.wlx_action_handler
-            .send(WlMonitorAction::SetTransform {
-                name: monitor.name.clone(),
-                transform,
-            })?;
-
+        let names: Vec<String> = self
+            .monitors
+            .iter()
+            .filter(|m| m.enabled)
+            .map(|m| m.name.clone())
+            .collect();
+        for name in &names {
+            self.send_transform(name, transform)?;
+        }
+        self.start_batch(names);
         Ok(())
     }
 
@@ -868,7 +3764,8 @@ impl App {
             return;
         };
         for rule in &workspace_rules {
-            let monitor_idx = self.monitors.iter().position(|m| m.name == rule.monitor);
+            let monitor_idx =
+                self.monitors.iter().position(|m| m.name == rule.monitor);
             if let Some(ws) = self
                 .workspace_assignments
                 .iter_mut()
@@ -892,3 +3789,335 @@ impl App {
         }
     }
 }
+
+/// The index one step from `i` in a list of `len`, wrapping around at the
+/// ends or staying put there.
+fn step_index(i: usize, len: usize, forward: bool, wrap: bool) -> usize {
+    match (forward, wrap) {
+        (true, true) => (i + 1) % len,
+        (true, false) => (i + 1).min(len - 1),
+        (false, true) => i.checked_sub(1).unwrap_or(len - 1),
+        (false, false) => i.saturating_sub(1),
+    }
+}
+
+/// Where the monitor at `idx` in `previous` is in `current`.
+fn remap_index(
+    idx: usize,
+    previous: &[String],
+    current: &[&str],
+) -> Option<usize> {
+    let name = previous.get(idx)?;
+    current.iter().position(|c| c == name)
+}
+
+/// `map` with each key moved from its index in `previous` to the same
+/// monitor's index in `current`.
+fn reindex<T: Copy>(
+    map: &HashMap<usize, T>,
+    previous: &[String],
+    current: &[&str],
+) -> HashMap<usize, T> {
+    map.iter()
+        .filter_map(|(&idx, &value)| {
+            Some((remap_index(idx, previous, current)?, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyCode;
+    use std::{os::unix::net::UnixStream, path::PathBuf, sync::mpsc};
+    use wayland_client::{Connection, Proxy, backend::ObjectId};
+    use wayland_protocols_wlr::output_management::v1::client::{
+        zwlr_output_head_v1::ZwlrOutputHeadV1,
+        zwlr_output_mode_v1::ZwlrOutputModeV1,
+    };
+    use wlx_monitors::{WlMonitorMode, WlPosition, WlResolution};
+
+    fn test_app(config: &Config) -> App {
+        let (tx, _rx) = mpsc::sync_channel(16);
+        App::build(tx, config, Compositor::Unknown)
+    }
+
+    #[test]
+    fn scale_step_moves_pending_scale_by_configured_amount() {
+        let config = Config {
+            scale_step: 0.25,
+            ..Config::new(PathBuf::from("/tmp/test.conf"))
+        };
+        let mut app = test_app(&config);
+        app.pending_scale = 1.0;
+
+        app.scale_up();
+        assert_eq!(app.pending_scale, 1.25);
+        app.scale_up();
+        assert_eq!(app.pending_scale, 1.5);
+        app.scale_down();
+        assert_eq!(app.pending_scale, 1.25);
+    }
+
+    #[test]
+    fn apply_and_quit_waits_for_pending_revert() {
+        let mut app = test_app(&Config::new(PathBuf::from("/tmp/test.conf")));
+        assert!(!app.ready_to_quit());
+
+        app.apply_all_and_quit().unwrap();
+        app.pending_revert = Some(PendingRevert {
+            monitor: "DP-1".to_string(),
+            revert: Revert::Scale(1.0),
+            deadline: Instant::now() + Duration::from_secs(SAFE_APPLY_SECS),
+        });
+        assert!(!app.ready_to_quit());
+
+        app.pending_revert = None;
+        assert!(app.ready_to_quit());
+    }
+
+    #[test]
+    fn pending_move_follows_its_monitor_across_hotplug() {
+        let before = ["eDP-1".to_string(), "DP-1".to_string()];
+        let pending = HashMap::from([(1, (1920, 0))]);
+
+        // Docking reports the monitors again, the new one first
+        let docked = ["HDMI-A-1", "DP-1", "eDP-1"];
+        assert_eq!(
+            reindex(&pending, &before, &docked),
+            HashMap::from([(1, (1920, 0))])
+        );
+
+        let reordered = ["DP-1", "eDP-1"];
+        assert_eq!(
+            reindex(&pending, &before, &reordered),
+            HashMap::from([(0, (1920, 0))])
+        );
+
+        // A move for a monitor that was unplugged is dropped
+        let undocked = ["eDP-1"];
+        assert!(reindex(&pending, &before, &undocked).is_empty());
+    }
+
+    #[test]
+    fn snapshot_moves_only_monitors_out_of_place() {
+        let snapshot = Snapshot {
+            positions: HashMap::from([
+                ("DP-1".to_string(), (0, 0)),
+                ("HDMI-A-1".to_string(), (2560, 0)),
+            ]),
+            scales: HashMap::new(),
+        };
+        let monitors = [
+            ("eDP-1", (0, 1440)),
+            ("HDMI-A-1", (1920, 0)),
+            ("DP-1", (0, 0)),
+        ];
+
+        assert_eq!(
+            snapshot.pending_positions(&monitors),
+            HashMap::from([(1, (2560, 0))])
+        );
+    }
+
+    #[test]
+    fn recalling_an_empty_slot_leaves_pending_moves() {
+        let mut app = test_app(&Config::new(PathBuf::from("/tmp/test.conf")));
+        app.pending_positions.insert(0, (100, 0));
+
+        app.recall_slot(3);
+        assert_eq!(app.pending_positions.get(&0), Some(&(100, 0)));
+        assert!(
+            app.status_message
+                .as_deref()
+                .is_some_and(|m| m.contains("empty"))
+        );
+    }
+
+    /// An enabled monitor at `scale` offering `modes` (width, height,
+    /// refresh), the one at `current` active. Its Wayland objects are
+    /// inert, which is all the app needs.
+    fn test_monitor(
+        name: &str,
+        modes: &[(i32, i32, i32)],
+        current: usize,
+        scale: f64,
+    ) -> WlMonitor {
+        let (socket, _) = UnixStream::pair().unwrap();
+        let backend = Connection::from_socket(socket)
+            .unwrap()
+            .backend()
+            .downgrade();
+        let (width, height, _) = modes[current];
+        WlMonitor {
+            head_id: ObjectId::null(),
+            name: name.to_string(),
+            description: String::new(),
+            make: String::new(),
+            model: String::new(),
+            serial_number: String::new(),
+            modes: modes
+                .iter()
+                .enumerate()
+                .map(|(i, &(width, height, refresh_rate))| WlMonitorMode {
+                    mode_id: ObjectId::null(),
+                    head_id: ObjectId::null(),
+                    refresh_rate,
+                    resolution: WlResolution { width, height },
+                    preferred: i == 0,
+                    is_current: i == current,
+                    proxy: ZwlrOutputModeV1::inert(backend.clone()),
+                })
+                .collect(),
+            resolution: WlResolution { width, height },
+            position: WlPosition::default(),
+            scale,
+            enabled: true,
+            current_mode: None,
+            transform: WlTransform::Normal,
+            head: ZwlrOutputHeadV1::inert(backend),
+            changed: false,
+            last_mode: None,
+        }
+    }
+
+    #[test]
+    fn mode_and_scale_batch_saves_after_both_confirmations() {
+        let path = std::env::temp_dir()
+            .join(format!("xwlm-test-mode-scale-{}.sh", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let (tx, rx) = mpsc::sync_channel(16);
+        let mut app =
+            App::build(tx, &Config::new(path.clone()), Compositor::River);
+        let modes = [(1920, 1080, 60), (2560, 1440, 60)];
+        app.set_monitors(vec![test_monitor("DP-1", &modes, 0, 1.0)]);
+
+        app.panel = Panel::Mode;
+        app.select_mode_index(1);
+        app.pending_scale = 1.5;
+        app.apply_action().unwrap();
+
+        let sent: Vec<WlMonitorAction> = rx.try_iter().collect();
+        assert_eq!(sent.len(), 2);
+        assert!(matches!(
+            sent[0],
+            WlMonitorAction::SwitchMode {
+                width: 2560,
+                height: 1440,
+                refresh_rate: 60,
+                ..
+            }
+        ));
+        assert!(matches!(
+            sent[1],
+            WlMonitorAction::SetScale { scale: 1.5, .. }
+        ));
+
+        // The mode alone is confirmed: still at the old scale, so no save
+        app.update_monitor(test_monitor("DP-1", &modes, 1, 1.0));
+        app.save_when_settled(true);
+        assert!(!path.exists());
+
+        app.update_monitor(test_monitor("DP-1", &modes, 1, 1.5));
+        app.save_when_settled(true);
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            written.contains("--mode 2560x1440@60Hz --pos 0,0 --scale 1.50")
+        );
+
+        // Nothing left to save, so later events don't write it again
+        std::fs::remove_file(&path).unwrap();
+        app.save_when_settled(true);
+        assert!(!path.exists());
+        assert_eq!(app.monitors[0].scale, 1.5);
+        assert!(app.monitors[0].modes[1].is_current);
+    }
+
+    #[test]
+    fn map_pans_only_while_zoomed_in() {
+        let mut app = test_app(&Config::new(PathBuf::from("/tmp/test.conf")));
+
+        app.pan_map(PositionDirection::Right);
+        assert_eq!(app.map_pan_x, 0);
+
+        app.zoom_in();
+        app.pan_map(PositionDirection::Right);
+        app.pan_map(PositionDirection::Down);
+        assert_eq!(
+            (app.map_pan_x, app.map_pan_y),
+            (MAP_PAN_STEP_X, MAP_PAN_STEP_Y)
+        );
+
+        app.zoom_out();
+        assert_eq!((app.map_pan_x, app.map_pan_y), (0, 0));
+    }
+
+    #[test]
+    fn transform_list_wraps_by_default() {
+        let mut app = test_app(&Config::new(PathBuf::from("/tmp/test.conf")));
+        app.panel = Panel::Transform;
+
+        app.transform_state.select(Some(TRANSFORMS.len() - 1));
+        app.next();
+        assert_eq!(app.transform_state.selected(), Some(0));
+        app.previous();
+        assert_eq!(app.transform_state.selected(), Some(TRANSFORMS.len() - 1));
+    }
+
+    #[test]
+    fn transform_list_stops_at_ends_without_wrapping() {
+        let config = Config {
+            wrap_lists: false,
+            ..Config::new(PathBuf::from("/tmp/test.conf"))
+        };
+        let mut app = test_app(&config);
+        app.panel = Panel::Transform;
+
+        app.transform_state.select(Some(TRANSFORMS.len() - 1));
+        app.next();
+        assert_eq!(app.transform_state.selected(), Some(TRANSFORMS.len() - 1));
+
+        app.transform_state.select(Some(0));
+        app.previous();
+        assert_eq!(app.transform_state.selected(), Some(0));
+        app.next();
+        assert_eq!(app.transform_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn toggle_by_name_reports_unknown_monitor() {
+        let mut app = test_app(&Config::new(PathBuf::from("/tmp/test.conf")));
+        app.start_toggle_by_name();
+        for c in "DP-9".chars() {
+            app.toggle_prompt
+                .as_mut()
+                .unwrap()
+                .handle_key(KeyCode::Char(c));
+        }
+
+        app.submit_toggle_by_name().unwrap();
+        assert!(app.toggle_prompt.is_none());
+        assert!(app.error_message.as_deref().unwrap().contains("'DP-9'"));
+    }
+
+    #[test]
+    fn workspaces_on_counts_pending_assignments() {
+        let config = Config {
+            workspace_count: Some(3),
+            ..Config::new(PathBuf::from("/tmp/test.conf"))
+        };
+        let mut app = test_app(&config);
+        app.workspace_assignments[0].monitor_idx = Some(0);
+        app.workspace_assignments[2].monitor_idx = Some(1);
+        app.pending_workspaces.insert(
+            1,
+            WorkspaceAssignment {
+                monitor_idx: Some(0),
+                ..app.workspace_assignments[1].clone()
+            },
+        );
+
+        assert_eq!(app.workspaces_on(0), [1, 2]);
+        assert_eq!(app.workspaces_on(1), [3]);
+    }
+}
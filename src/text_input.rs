@@ -0,0 +1,113 @@
+use crossterm::event::KeyCode;
+
+/// A single-line text buffer with a byte-indexed cursor, shared by the setup
+/// wizard and the prompts in the main TUI.
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new(value: impl Into<String>) -> Self {
+        let value = value.into();
+        let cursor = value.len();
+        Self { value, cursor }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Applies an editing key. Returns `false` if the key isn't an editing
+    /// key so the caller can handle it (e.g. Enter or Esc).
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char(c) => {
+                self.value.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            KeyCode::Backspace => {
+                if self.cursor > 0 {
+                    let prev = self.prev_cursor();
+                    self.value.remove(prev);
+                    self.cursor = prev;
+                }
+            }
+            KeyCode::Delete => {
+                if self.cursor < self.value.len() {
+                    self.value.remove(self.cursor);
+                }
+            }
+            KeyCode::Left => self.cursor = self.prev_cursor(),
+            KeyCode::Right => self.cursor = self.next_cursor(),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Splits the value into the text before the cursor, the character
+    /// under it (a space at the end) and the rest, for rendering.
+    pub fn split_at_cursor(&self) -> (&str, &str, &str) {
+        let (before, after) = self.value.split_at(self.cursor);
+        let cursor_len = after.chars().next().map_or(0, char::len_utf8);
+        if cursor_len == 0 {
+            return (before, " ", "");
+        }
+        (before, &after[..cursor_len], &after[cursor_len..])
+    }
+
+    fn prev_cursor(&self) -> usize {
+        self.value[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    fn next_cursor(&self) -> usize {
+        self.value[self.cursor..]
+            .char_indices()
+            .nth(1)
+            .map(|(i, _)| self.cursor + i)
+            .unwrap_or(self.value.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_in_middle() {
+        let mut input = TextInput::new("DP1");
+        input.handle_key(KeyCode::Left);
+        input.handle_key(KeyCode::Char('-'));
+        assert_eq!(input.value(), "DP-1");
+
+        input.handle_key(KeyCode::Backspace);
+        input.handle_key(KeyCode::Home);
+        input.handle_key(KeyCode::Delete);
+        assert_eq!(input.value(), "P1");
+    }
+
+    #[test]
+    fn test_split_at_cursor_multibyte() {
+        let mut input = TextInput::new("a×b");
+        input.handle_key(KeyCode::Left);
+        input.handle_key(KeyCode::Left);
+        assert_eq!(input.split_at_cursor(), ("a", "×", "b"));
+
+        input.handle_key(KeyCode::End);
+        assert_eq!(input.split_at_cursor(), ("a×b", " ", ""));
+    }
+
+    #[test]
+    fn test_non_editing_keys_are_not_consumed() {
+        let mut input = TextInput::default();
+        assert!(!input.handle_key(KeyCode::Enter));
+        assert!(!input.handle_key(KeyCode::Esc));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     compositor::Compositor,
     state::{App, Panel},
+    tui::keymap::{Action, Keymap},
 };
 
 use ratatui::{
@@ -11,139 +12,491 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-pub fn config(frame: &mut Frame, area: Rect, app: &App) {
-    let panel = &app.panel;
-    let mut keys = vec![
+pub fn config(frame: &mut Frame, area: Rect, app: &App, keymap: &Keymap) {
+    if app.expanded_footer {
+        let lines = expanded_lines(app, keymap, area.width as usize);
+        frame.render_widget(Paragraph::new(lines), area);
+        return;
+    }
+
+    let mut keys = status_spans(app, keymap);
+    keys.push(Span::styled(" | ", Style::default().fg(Color::Cyan)));
+    keys.extend(global_keybinds(keymap, false));
+    keys.push(Span::styled(" | ", Style::default().fg(Color::DarkGray)));
+    let (title, panel_keys) = panel_keybinds(app, keymap);
+    keys.push(Span::styled(
+        format!("[ {} | ", title),
+        Style::default().fg(Color::Cyan),
+    ));
+    keys.extend(panel_keys);
+    keys.push(Span::styled("]", Style::default().fg(Color::Cyan)));
+    if let Some(count) = app.count_prefix {
+        keys.push(count_span(count));
+    }
+    let line = Line::from(keys);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+/// Rows the expanded footer needs at `width` columns.
+pub fn expanded_height(app: &App, keymap: &Keymap, width: u16) -> u16 {
+    expanded_lines(app, keymap, width as usize).len() as u16
+}
+
+/// The status on the first row, then the global keys and the current
+/// panel's keys wrapped to `width`, never splitting a key from its label.
+fn expanded_lines(
+    app: &App,
+    keymap: &Keymap,
+    width: usize,
+) -> Vec<Line<'static>> {
+    let mut status = status_spans(app, keymap);
+    if let Some(count) = app.count_prefix {
+        status.push(count_span(count));
+    }
+    let (title, panel_keys) = panel_keybinds(app, keymap);
+    let mut lines = vec![Line::from(status)];
+    lines.extend(wrap_keybinds(
+        Span::styled("Global  ", Style::default().fg(Color::Cyan)),
+        global_keybinds(keymap, true),
+        width,
+    ));
+    lines.extend(wrap_keybinds(
+        Span::styled(format!("{}  ", title), Style::default().fg(Color::Cyan)),
+        panel_keys,
+        width,
+    ));
+    lines
+}
+
+/// Lays `keys` (key, label pairs) out behind `title`, starting a new row
+/// whenever the next pair would overflow `width`.
+fn wrap_keybinds(
+    title: Span<'static>,
+    keys: Vec<Span<'static>>,
+    width: usize,
+) -> Vec<Line<'static>> {
+    let indent = " ".repeat(title.width());
+    let mut lines = Vec::new();
+    let mut row = vec![title];
+    let mut used = row[0].width();
+    for pair in keys.chunks(2) {
+        let pair_width: usize = pair.iter().map(Span::width).sum();
+        if used + pair_width > width && row.len() > 1 {
+            lines.push(Line::from(std::mem::take(&mut row)));
+            row.push(Span::raw(indent.clone()));
+            used = indent.len();
+        }
+        row.extend_from_slice(pair);
+        used += pair_width;
+    }
+    lines.push(Line::from(row));
+    lines
+}
+
+fn status_spans(app: &App, keymap: &Keymap) -> Vec<Span<'static>> {
+    let mut keys = vec![Span::styled(
+        match app.compositor_version {
+            Some(version) => {
+                format!("[xwlm]-[{} {}]", app.compositor.label(), version)
+            }
+            None => format!("[xwlm]-[{}]", app.compositor.label()),
+        },
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if !app.compositor.capabilities().saves_config() {
+        keys.push(Span::styled(
+            " live only",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    let pending = app.pending_summary();
+    if pending.count() == 0 {
+        keys.push(Span::styled(
+            " no pending changes",
+            Style::default().fg(Color::DarkGray),
+        ));
+    } else {
+        let mut parts = Vec::new();
+        if pending.moved > 0 {
+            parts.push(format!("{} moved", pending.moved));
+        }
+        if pending.workspaces > 0 {
+            parts.push(format!("{} workspace", pending.workspaces));
+        }
+        if pending.scale {
+            parts.push("scale".to_string());
+        }
+        if pending.transform {
+            parts.push("transform".to_string());
+        }
+        if pending.staged > 0 {
+            parts.push(format!("{} other monitor", pending.staged));
+        }
+        keys.push(Span::styled(
+            format!(" {} pending: {}", pending.count(), parts.join(", ")),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if app.has_unsaved_changes() {
+        keys.push(Span::styled(
+            format!(
+                " live changes not saved ({} to save)",
+                keymap.key_for(Action::SaveConfig)
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    keys
+}
+
+/// Keys that work in every panel. The compact status line only has room
+/// for switching panels and quitting.
+fn global_keybinds(keymap: &Keymap, all: bool) -> Vec<Span<'static>> {
+    let mut keys = hint(keymap, &[Action::SwitchPanel], "", "switch panel  ");
+    if !all {
+        keys.extend(hint(keymap, &[Action::Quit], "", "quit"));
+        return keys;
+    }
+    keys.extend(hint(keymap, &[Action::Quit], "", "quit  "));
+    keys.extend(hint(
+        keymap,
+        &[Action::PrevMonitor, Action::NextMonitor],
+        "",
+        "switch monitor  ",
+    ));
+    keys.extend(hint(keymap, &[Action::ToggleMonitor], "", "toggle  "));
+    keys.extend(hint(keymap, &[Action::Reset], "", "reset  "));
+    keys.extend(hint(keymap, &[Action::SaveConfig], "", "save  "));
+    keys.extend(hint(
+        keymap,
+        &[Action::ToggleFooter],
+        "",
+        "compact footer  ",
+    ));
+    keys.extend(hint(keymap, &[Action::ShowLegend], "", "map legend  "));
+    keys
+}
+
+/// A key hint: the first key of each of `actions` joined by `sep`, then
+/// `label`. Nothing when none of them has a key.
+fn hint(
+    keymap: &Keymap,
+    actions: &[Action],
+    sep: &str,
+    label: &str,
+) -> Vec<Span<'static>> {
+    let keys: Vec<String> = actions
+        .iter()
+        .filter_map(|&action| keymap.keys_for(action).into_iter().next())
+        .collect();
+    if keys.is_empty() {
+        return Vec::new();
+    }
+    vec![
         Span::styled(
-            format!("[xwlm]-[{}]", app.compositor.label()),
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+            format!("{} ", keys.join(sep)),
+            Style::default().fg(Color::Cyan),
         ),
-        Span::styled(" | ", Style::default().fg(Color::Cyan)),
-        Span::styled("Tab ", Style::default().fg(Color::Cyan)),
-        Span::styled("switch panel  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("q ", Style::default().fg(Color::Cyan)),
-        Span::styled("quit", Style::default().fg(Color::DarkGray)),
-        Span::styled(" | ", Style::default().fg(Color::DarkGray)),
-    ];
+        Span::styled(label.to_string(), Style::default().fg(Color::DarkGray)),
+    ]
+}
 
-    match panel {
+/// The current panel's title and keys.
+fn panel_keybinds(
+    app: &App,
+    keymap: &Keymap,
+) -> (&'static str, Vec<Span<'static>>) {
+    let mut keys = Vec::new();
+    let title = match app.panel {
         Panel::Monitor => {
-            keys.push(Span::styled(
-                "[ Monitor Layout | ",
-                Style::default().fg(Color::Cyan),
-            ));
-            get_monitor_keybinds(&mut keys);
-            keys.push(Span::styled("]", Style::default().fg(Color::Cyan)));
+            get_monitor_keybinds(&mut keys, keymap);
+            "Monitor Layout"
         }
         Panel::Mode => {
-            keys.push(Span::styled(
-                "[ Modes | ",
-                Style::default().fg(Color::Cyan),
-            ));
-            get_modes_keybinds(&mut keys);
-            keys.push(Span::styled("]", Style::default().fg(Color::Cyan)));
+            get_modes_keybinds(&mut keys, keymap);
+            "Modes"
         }
         Panel::Scale => {
-            keys.push(Span::styled(
-                "[ Scale | ",
-                Style::default().fg(Color::Cyan),
-            ));
-            get_scale_keybinds(&mut keys);
-            keys.push(Span::styled("]", Style::default().fg(Color::Cyan)));
+            get_scale_keybinds(&mut keys, keymap);
+            "Scale"
+        }
+        Panel::Color => {
+            get_color_keybinds(&mut keys, keymap);
+            "Color"
         }
         Panel::Transform => {
-            keys.push(Span::styled(
-                "[ Transform | ",
-                Style::default().fg(Color::Cyan),
-            ));
-            get_transform_keybinds(&mut keys);
-            keys.push(Span::styled("]", Style::default().fg(Color::Cyan)));
+            get_transform_keybinds(&mut keys, keymap);
+            "Transform"
         }
         Panel::Workspace => {
-            keys.push(Span::styled(
-                "[ Workspaces | ",
-                Style::default().fg(Color::Cyan),
-            ));
-            get_workspaces_keybinds(&mut keys, app.compositor);
-            keys.push(Span::styled("]", Style::default().fg(Color::Cyan)));
+            get_workspaces_keybinds(&mut keys, keymap, app.compositor);
+            "Workspaces"
         }
     };
-    let line = Line::from(keys);
+    (title, keys)
+}
+
+fn count_span(count: u32) -> Span<'static> {
+    Span::styled(
+        format!(" {}×", count),
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+pub fn search(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(ref input) = app.search else {
+        return;
+    };
+    let (before, cursor_char, rest) = input.split_at_cursor();
+    let line = Line::from(vec![
+        Span::styled("/", Style::default().fg(Color::Cyan)),
+        Span::styled(before, Style::default().fg(Color::White)),
+        Span::styled(
+            cursor_char,
+            Style::default().fg(Color::Black).bg(Color::White),
+        ),
+        Span::styled(rest, Style::default().fg(Color::White)),
+        Span::styled(
+            "  Enter jump  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
     frame.render_widget(Paragraph::new(line), area);
 }
 
-pub fn get_monitor_keybinds(keys: &mut Vec<Span<'static>>) {
-    keys.push(Span::styled("↑↓ ←→ ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled("move  ", Style::default().fg(Color::DarkGray)));
-    keys.push(Span::styled("+/- ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled("zoom  ", Style::default().fg(Color::DarkGray)));
-    keys.push(Span::styled("[] ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled(
-        "switch monitor ",
-        Style::default().fg(Color::DarkGray),
+pub fn custom_mode(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(ref input) = app.custom_mode else {
+        return;
+    };
+    let (before, cursor_char, rest) = input.split_at_cursor();
+    let line = Line::from(vec![
+        Span::styled("mode ", Style::default().fg(Color::Cyan)),
+        Span::styled(before, Style::default().fg(Color::White)),
+        Span::styled(
+            cursor_char,
+            Style::default().fg(Color::Black).bg(Color::White),
+        ),
+        Span::styled(rest, Style::default().fg(Color::White)),
+        Span::styled(
+            "  WIDTHxHEIGHT@HZ  Enter apply  Esc cancel  ",
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            "⚠ a mode the monitor can't drive may leave it without signal",
+            Style::default().fg(Color::Yellow),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+pub fn get_monitor_keybinds(keys: &mut Vec<Span<'static>>, keymap: &Keymap) {
+    keys.extend(hint(
+        keymap,
+        &[
+            Action::MoveUp,
+            Action::MoveDown,
+            Action::MoveLeft,
+            Action::MoveRight,
+        ],
+        "",
+        "move  ",
+    ));
+    keys.extend(hint(
+        keymap,
+        &[Action::ZoomIn, Action::ZoomOut],
+        "/",
+        "zoom  ",
     ));
+    keys.push(Span::styled("⇧←→ ", Style::default().fg(Color::Cyan)));
+    keys.push(Span::styled("pan  ", Style::default().fg(Color::DarkGray)));
+    keys.extend(hint(
+        keymap,
+        &[Action::PrevMonitor, Action::NextMonitor],
+        "",
+        "switch monitor  ",
+    ));
+    keys.extend(hint(keymap, &[Action::SoloMonitor], "", "only  "));
+    keys.extend(hint(keymap, &[Action::EnableAll], "", "enable all  "));
+    keys.extend(hint(keymap, &[Action::StackVertical], "", "stack  "));
+    keys.extend(hint(
+        keymap,
+        &[Action::RevertToFile],
+        "",
+        "revert to file  ",
+    ));
+    keys.extend(hint(keymap, &[Action::ToggleTableView], "", "table  "));
+    keys.extend(hint(
+        keymap,
+        &[Action::ToggleRelativePositions],
+        "",
+        "relative  ",
+    ));
+    keys.extend(hint(keymap, &[Action::TogglePortrait], "", "portrait  "));
+    keys.extend(hint(keymap, &[Action::Search], "", "find "));
 }
 
-pub fn get_modes_keybinds(keys: &mut Vec<Span<'static>>) {
-    keys.push(Span::styled("↑↓ ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled(
+/// Banner shown when the live monitors differ from the saved config.
+pub fn drift(frame: &mut Frame, area: Rect, app: &App, keymap: &Keymap) {
+    let Some(first) = app.drift.first() else {
+        return;
+    };
+    let mut spans = vec![
+        Span::styled(
+            "⚠ Live state differs from config: ",
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::styled(
+            format!(
+                "{} {} {} → {}",
+                first.monitor, first.field, first.saved, first.live
+            ),
+            Style::default().fg(Color::White),
+        ),
+    ];
+    if app.drift.len() > 1 {
+        spans.push(Span::styled(
+            format!(" (+{} more)", app.drift.len() - 1),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    spans.push(Span::raw("  "));
+    spans.extend(hint(keymap, &[Action::ReapplyConfig], "", "reapply file  "));
+    spans.extend(hint(keymap, &[Action::WriteLiveConfig], "", "save live  "));
+    spans.extend(hint(keymap, &[Action::DismissDrift], "", "dismiss"));
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Countdown shown while a safe-applied change waits to be kept.
+pub fn revert(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(secs) = app.revert_seconds_left() else {
+        return;
+    };
+    let line = Line::from(vec![
+        Span::styled(
+            format!("Keep this change? Reverting in {}s  ", secs),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::styled("y ", Style::default().fg(Color::Cyan)),
+        Span::styled("keep  ", Style::default().fg(Color::DarkGray)),
+        Span::styled("any other key ", Style::default().fg(Color::Cyan)),
+        Span::styled("revert", Style::default().fg(Color::DarkGray)),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+pub fn get_modes_keybinds(keys: &mut Vec<Span<'static>>, keymap: &Keymap) {
+    keys.extend(hint(
+        keymap,
+        &[Action::MoveUp, Action::MoveDown],
+        "",
         "select  ",
-        Style::default().fg(Color::DarkGray),
-    ));
-    keys.push(Span::styled("Enter ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled(
-        "apply  ",
-        Style::default().fg(Color::DarkGray),
     ));
+    keys.extend(hint(keymap, &[Action::Apply], "", "apply  "));
+    keys.extend(hint(keymap, &[Action::PreferredMode], "", "preferred  "));
+    keys.extend(hint(keymap, &[Action::MaxRefresh], "", "max Hz  "));
+    keys.extend(hint(keymap, &[Action::ToggleRawModes], "", "raw  "));
+    keys.extend(hint(keymap, &[Action::ToggleModeGrouping], "", "group  "));
+    keys.extend(hint(keymap, &[Action::CustomMode], "", "custom  "));
 }
 
 pub fn get_workspaces_keybinds(
     keys: &mut Vec<Span<'static>>,
+    keymap: &Keymap,
     compositor: Compositor,
 ) {
-    keys.push(Span::styled("←→ ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled(
+    keys.extend(hint(
+        keymap,
+        &[Action::MoveLeft, Action::MoveRight],
+        "",
         "assign  ",
-        Style::default().fg(Color::DarkGray),
     ));
-    if compositor.supports_workspace_defaults() {
-        keys.push(Span::styled("d ", Style::default().fg(Color::Cyan)));
-        keys.push(Span::styled(
-            "default  ",
-            Style::default().fg(Color::DarkGray),
-        ));
-        keys.push(Span::styled("p ", Style::default().fg(Color::Cyan)));
-        keys.push(Span::styled(
+    let capabilities = compositor.capabilities();
+    if capabilities.workspace_defaults() {
+        keys.extend(hint(keymap, &[Action::ToggleDefault], "", "default  "));
+    }
+    if capabilities.persistent_workspaces() {
+        keys.extend(hint(
+            keymap,
+            &[Action::TogglePersistent],
+            "",
             "persistent  ",
-            Style::default().fg(Color::DarkGray),
         ));
     }
 }
 
-pub fn get_scale_keybinds(keys: &mut Vec<Span<'static>>) {
-    keys.push(Span::styled("←→ ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled(
+pub fn get_scale_keybinds(keys: &mut Vec<Span<'static>>, keymap: &Keymap) {
+    keys.extend(hint(
+        keymap,
+        &[Action::MoveLeft, Action::MoveRight],
+        "",
         "adjust ",
-        Style::default().fg(Color::DarkGray),
     ));
-    keys.push(Span::styled("Enter ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled(
-        "apply  ",
-        Style::default().fg(Color::DarkGray),
+    keys.extend(hint(keymap, &[Action::Apply], "", "apply  "));
+}
+
+pub fn get_color_keybinds(keys: &mut Vec<Span<'static>>, keymap: &Keymap) {
+    keys.extend(hint(
+        keymap,
+        &[Action::MoveLeft, Action::MoveRight],
+        "",
+        "warmer/cooler ",
     ));
+    keys.extend(hint(keymap, &[Action::Apply], "", "apply  "));
 }
 
-pub fn get_transform_keybinds(keys: &mut Vec<Span<'static>>) {
-    keys.push(Span::styled("↑↓ ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled(
+pub fn get_transform_keybinds(keys: &mut Vec<Span<'static>>, keymap: &Keymap) {
+    keys.extend(hint(
+        keymap,
+        &[Action::MoveUp, Action::MoveDown],
+        "",
         "rotate  ",
-        Style::default().fg(Color::DarkGray),
-    ));
-    keys.push(Span::styled("Enter ", Style::default().fg(Color::Cyan)));
-    keys.push(Span::styled(
-        "apply  ",
-        Style::default().fg(Color::DarkGray),
     ));
+    keys.extend(hint(keymap, &[Action::Apply], "", "apply  "));
+    keys.extend(hint(keymap, &[Action::RotateAll], "", "apply to all  "));
+}
+
+pub fn wallpaper_prompt(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(ref input) = app.wallpaper_prompt else {
+        return;
+    };
+    let (before, cursor_char, rest) = input.split_at_cursor();
+    let line = Line::from(vec![
+        Span::styled("wallpaper ", Style::default().fg(Color::Cyan)),
+        Span::styled(before, Style::default().fg(Color::White)),
+        Span::styled(
+            cursor_char,
+            Style::default().fg(Color::Black).bg(Color::White),
+        ),
+        Span::styled(rest, Style::default().fg(Color::White)),
+        Span::styled(
+            "  image path, empty to clear  Enter set  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+pub fn toggle_prompt(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(ref input) = app.toggle_prompt else {
+        return;
+    };
+    let (before, cursor_char, rest) = input.split_at_cursor();
+    let line = Line::from(vec![
+        Span::styled("toggle ", Style::default().fg(Color::Cyan)),
+        Span::styled(before, Style::default().fg(Color::White)),
+        Span::styled(
+            cursor_char,
+            Style::default().fg(Color::Black).bg(Color::White),
+        ),
+        Span::styled(rest, Style::default().fg(Color::White)),
+        Span::styled(
+            "  monitor name  Enter toggle  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
 }
@@ -0,0 +1,486 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crossterm::event::KeyCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum KeymapError {
+    #[error("unknown keybind action: {0}")]
+    UnknownAction(String),
+
+    #[error("invalid key '{key}' for action {action}")]
+    InvalidKey { action: String, key: String },
+
+    #[error("key '{key}' for action {action} stores an arrangement slot")]
+    SlotKey { action: String, key: String },
+
+    #[error("key '{key}' is bound to both {first} and {second}")]
+    Conflict {
+        key: String,
+        first: &'static str,
+        second: &'static str,
+    },
+}
+
+/// What Shift+1–9 sends without the kitty keyboard protocol, on a US
+/// layout. They store arrangement slots, so no action can take them.
+pub const SLOT_STORE_KEYS: &str = "!@#$%^&*(";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    SwitchPanel,
+    ToggleMonitor,
+    Reset,
+    NextMonitor,
+    PrevMonitor,
+    ZoomIn,
+    ZoomOut,
+    ToggleDefault,
+    TogglePersistent,
+    Apply,
+    Search,
+    SoloMonitor,
+    EnableAll,
+    RotateAll,
+    PreferredMode,
+    MaxRefresh,
+    ReapplyConfig,
+    WriteLiveConfig,
+    DismissDrift,
+    TogglePortrait,
+    ToggleRawModes,
+    ToggleModeGrouping,
+    StackVertical,
+    RevertToFile,
+    PageUp,
+    PageDown,
+    FirstItem,
+    LastItem,
+    CycleBitDepth,
+    CycleRenderTime,
+    ToggleRelativePositions,
+    ToggleTableView,
+    SaveConfig,
+    CustomMode,
+    ShowVersion,
+    ApplyAndQuit,
+    ToggleByName,
+    ToggleWorkspaceBadges,
+    ToggleLock,
+    ToggleGroup,
+    Identify,
+    ToggleBasicColors,
+    ToggleFooter,
+    ShowLegend,
+    SetWallpaper,
+    HopLeft,
+    HopRight,
+    HopUp,
+    HopDown,
+}
+
+impl Action {
+    pub const ALL: [Action; 54] = [
+        Action::Quit,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::SwitchPanel,
+        Action::ToggleMonitor,
+        Action::Reset,
+        Action::NextMonitor,
+        Action::PrevMonitor,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ToggleDefault,
+        Action::TogglePersistent,
+        Action::Apply,
+        Action::Search,
+        Action::SoloMonitor,
+        Action::EnableAll,
+        Action::RotateAll,
+        Action::PreferredMode,
+        Action::MaxRefresh,
+        Action::ReapplyConfig,
+        Action::WriteLiveConfig,
+        Action::DismissDrift,
+        Action::TogglePortrait,
+        Action::ToggleRawModes,
+        Action::ToggleModeGrouping,
+        Action::StackVertical,
+        Action::RevertToFile,
+        Action::PageUp,
+        Action::PageDown,
+        Action::FirstItem,
+        Action::LastItem,
+        Action::CycleBitDepth,
+        Action::CycleRenderTime,
+        Action::ToggleRelativePositions,
+        Action::ToggleTableView,
+        Action::SaveConfig,
+        Action::CustomMode,
+        Action::ShowVersion,
+        Action::ApplyAndQuit,
+        Action::ToggleByName,
+        Action::ToggleWorkspaceBadges,
+        Action::ToggleLock,
+        Action::ToggleGroup,
+        Action::Identify,
+        Action::ToggleBasicColors,
+        Action::ToggleFooter,
+        Action::ShowLegend,
+        Action::SetWallpaper,
+        Action::HopLeft,
+        Action::HopRight,
+        Action::HopUp,
+        Action::HopDown,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::SwitchPanel => "switch_panel",
+            Action::ToggleMonitor => "toggle_monitor",
+            Action::Reset => "reset",
+            Action::NextMonitor => "next_monitor",
+            Action::PrevMonitor => "prev_monitor",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::ToggleDefault => "toggle_default",
+            Action::TogglePersistent => "toggle_persistent",
+            Action::Apply => "apply",
+            Action::Search => "search",
+            Action::SoloMonitor => "solo_monitor",
+            Action::EnableAll => "enable_all",
+            Action::RotateAll => "rotate_all",
+            Action::PreferredMode => "preferred_mode",
+            Action::MaxRefresh => "max_refresh",
+            Action::ReapplyConfig => "reapply_config",
+            Action::WriteLiveConfig => "write_live_config",
+            Action::DismissDrift => "dismiss_drift",
+            Action::TogglePortrait => "toggle_portrait",
+            Action::ToggleRawModes => "toggle_raw_modes",
+            Action::ToggleModeGrouping => "toggle_mode_grouping",
+            Action::StackVertical => "stack_vertical",
+            Action::RevertToFile => "revert_to_file",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::FirstItem => "first_item",
+            Action::LastItem => "last_item",
+            Action::CycleBitDepth => "cycle_bit_depth",
+            Action::CycleRenderTime => "cycle_render_time",
+            Action::ToggleRelativePositions => "toggle_relative_positions",
+            Action::ToggleTableView => "toggle_table_view",
+            Action::SaveConfig => "save_config",
+            Action::CustomMode => "custom_mode",
+            Action::ShowVersion => "show_version",
+            Action::ApplyAndQuit => "apply_and_quit",
+            Action::ToggleByName => "toggle_by_name",
+            Action::ToggleWorkspaceBadges => "toggle_workspace_badges",
+            Action::ToggleLock => "toggle_lock",
+            Action::ToggleGroup => "toggle_group",
+            Action::Identify => "identify",
+            Action::ToggleBasicColors => "toggle_basic_colors",
+            Action::ToggleFooter => "toggle_footer",
+            Action::ShowLegend => "show_legend",
+            Action::SetWallpaper => "set_wallpaper",
+            Action::HopLeft => "hop_left",
+            Action::HopRight => "hop_right",
+            Action::HopUp => "hop_up",
+            Action::HopDown => "hop_down",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "Esc"],
+            Action::MoveUp => &["Up", "k"],
+            Action::MoveDown => &["Down", "j"],
+            Action::MoveLeft => &["Left", "h"],
+            Action::MoveRight => &["Right", "l"],
+            Action::SwitchPanel => &["Tab"],
+            Action::ToggleMonitor => &["t"],
+            Action::Reset => &["r"],
+            Action::NextMonitor => &["]"],
+            Action::PrevMonitor => &["["],
+            Action::ZoomIn => &["+"],
+            Action::ZoomOut => &["-"],
+            Action::ToggleDefault => &["d"],
+            Action::TogglePersistent => &["p"],
+            Action::Apply => &["Enter"],
+            Action::Search => &["/"],
+            Action::SoloMonitor => &["o"],
+            Action::EnableAll => &["E"],
+            Action::RotateAll => &["A"],
+            Action::PreferredMode => &["P"],
+            Action::MaxRefresh => &["M"],
+            Action::ReapplyConfig => &["F"],
+            Action::WriteLiveConfig => &["W"],
+            Action::DismissDrift => &["X"],
+            Action::TogglePortrait => &["v"],
+            Action::ToggleRawModes => &["R"],
+            Action::ToggleModeGrouping => &["G"],
+            Action::StackVertical => &["S"],
+            Action::RevertToFile => &["L"],
+            Action::PageUp => &["PageUp"],
+            Action::PageDown => &["PageDown"],
+            Action::FirstItem => &["Home"],
+            Action::LastItem => &["End"],
+            Action::CycleBitDepth => &["D"],
+            Action::CycleRenderTime => &["T"],
+            Action::ToggleRelativePositions => &["a"],
+            Action::ToggleTableView => &["m"],
+            Action::SaveConfig => &["s"],
+            Action::CustomMode => &["c"],
+            Action::ShowVersion => &["V"],
+            Action::ApplyAndQuit => &["Q"],
+            Action::ToggleByName => &["n"],
+            Action::ToggleWorkspaceBadges => &["w"],
+            Action::ToggleLock => &["K"],
+            Action::ToggleGroup => &["space"],
+            Action::Identify => &["i"],
+            Action::ToggleBasicColors => &["b"],
+            Action::ToggleFooter => &["f"],
+            Action::ShowLegend => &["?"],
+            Action::SetWallpaper => &["I"],
+            Action::HopLeft => &["<"],
+            Action::HopRight => &[">"],
+            Action::HopUp => &["{"],
+            Action::HopDown => &["}"],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeyCode, Action>,
+    keys: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Keymap {
+    /// Builds the keymap from the `[keybinds]` config section. Actions that
+    /// are not listed keep their default keys.
+    pub fn from_config(
+        overrides: &BTreeMap<String, Vec<String>>,
+    ) -> Result<Self, KeymapError> {
+        for name in overrides.keys() {
+            if Action::from_name(name).is_none() {
+                return Err(KeymapError::UnknownAction(name.clone()));
+            }
+        }
+
+        let mut bindings = HashMap::new();
+        let mut keys: HashMap<Action, Vec<KeyCode>> = HashMap::new();
+        for action in Action::ALL {
+            let specs: Vec<&str> = match overrides.get(action.name()) {
+                Some(keys) => keys.iter().map(String::as_str).collect(),
+                None => action.default_keys().to_vec(),
+            };
+
+            for spec in specs {
+                let Some(code) = parse_key(spec) else {
+                    return Err(KeymapError::InvalidKey {
+                        action: action.name().to_string(),
+                        key: spec.to_string(),
+                    });
+                };
+                if let KeyCode::Char(c) = code
+                    && SLOT_STORE_KEYS.contains(c)
+                {
+                    return Err(KeymapError::SlotKey {
+                        action: action.name().to_string(),
+                        key: spec.to_string(),
+                    });
+                }
+                if let Some(existing) = bindings.insert(code, action)
+                    && existing != action
+                {
+                    return Err(KeymapError::Conflict {
+                        key: spec.to_string(),
+                        first: existing.name(),
+                        second: action.name(),
+                    });
+                }
+                keys.entry(action).or_default().push(code);
+            }
+        }
+
+        Ok(Self { bindings, keys })
+    }
+
+    pub fn action(&self, code: KeyCode) -> Option<Action> {
+        self.bindings.get(&code).copied()
+    }
+
+    /// Labels for the keys bound to `action`, in the order the config lists
+    /// them. Empty when the action has no key.
+    pub fn keys_for(&self, action: Action) -> Vec<String> {
+        self.keys
+            .get(&action)
+            .into_iter()
+            .flatten()
+            .map(|&code| key_label(code))
+            .collect()
+    }
+
+    /// The first key bound to `action`, for hints that name a single key.
+    /// Falls back to the action's name when it has none.
+    pub fn key_for(&self, action: Action) -> String {
+        self.keys_for(action)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| action.name().to_string())
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new())
+            .expect("default keybinds must not conflict")
+    }
+}
+
+fn parse_key(spec: &str) -> Option<KeyCode> {
+    let mut chars = spec.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+
+    let code = match spec.to_ascii_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "space" => KeyCode::Char(' '),
+        other => {
+            let n = other.strip_prefix('f')?.parse::<u8>().ok()?;
+            if !(1..=12).contains(&n) {
+                return None;
+            }
+            KeyCode::F(n)
+        }
+    };
+    Some(code)
+}
+
+fn key_label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(pairs: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(action, keys)| {
+                (
+                    action.to_string(),
+                    keys.iter().map(|k| k.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action(KeyCode::Char('q')), Some(Action::Quit));
+        assert_eq!(keymap.action(KeyCode::Up), Some(Action::MoveUp));
+        assert_eq!(keymap.action(KeyCode::Char('k')), Some(Action::MoveUp));
+        assert_eq!(keymap.action(KeyCode::Enter), Some(Action::Apply));
+        assert_eq!(keymap.action(KeyCode::Char('x')), None);
+    }
+
+    #[test]
+    fn test_override_replaces_defaults() {
+        let keymap =
+            Keymap::from_config(&overrides(&[("quit", &["x"])])).unwrap();
+        assert_eq!(keymap.action(KeyCode::Char('x')), Some(Action::Quit));
+        assert_eq!(keymap.action(KeyCode::Char('q')), None);
+        assert_eq!(keymap.action(KeyCode::Esc), None);
+    }
+
+    #[test]
+    fn test_keys_for_follows_overrides() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.keys_for(Action::MoveUp), ["↑", "k"]);
+        assert_eq!(keymap.key_for(Action::ToggleMonitor), "t");
+
+        let keymap =
+            Keymap::from_config(&overrides(&[("toggle_monitor", &["x"])]))
+                .unwrap();
+        assert_eq!(keymap.key_for(Action::ToggleMonitor), "x");
+
+        let keymap =
+            Keymap::from_config(&overrides(&[("search", &[])])).unwrap();
+        assert!(keymap.keys_for(Action::Search).is_empty());
+        assert_eq!(keymap.key_for(Action::Search), "search");
+    }
+
+    #[test]
+    fn test_conflict_is_reported() {
+        let result = Keymap::from_config(&overrides(&[("apply", &["t"])]));
+        assert!(matches!(result, Err(KeymapError::Conflict { .. })));
+    }
+
+    #[test]
+    fn test_slot_key_is_reported() {
+        let result = Keymap::from_config(&overrides(&[("identify", &["@"])]));
+        assert!(matches!(result, Err(KeymapError::SlotKey { .. })));
+    }
+
+    #[test]
+    fn test_unknown_action_is_reported() {
+        let result = Keymap::from_config(&overrides(&[("fly", &["f"])]));
+        assert!(matches!(result, Err(KeymapError::UnknownAction(_))));
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("k"), Some(KeyCode::Char('k')));
+        assert_eq!(parse_key("K"), Some(KeyCode::Char('K')));
+        assert_eq!(parse_key("PageUp"), Some(KeyCode::PageUp));
+        assert_eq!(parse_key("space"), Some(KeyCode::Char(' ')));
+        assert_eq!(parse_key("F5"), Some(KeyCode::F(5)));
+        assert_eq!(parse_key("F13"), None);
+        assert_eq!(parse_key("nope"), None);
+    }
+}
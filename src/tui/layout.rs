@@ -2,44 +2,60 @@ use crate::{
     state::App,
     tui::{
         key_binds,
+        keymap::{Action, Keymap},
         panels::{
             left::{self},
-            mode, workspace,
+            mode, strip, workspace,
         },
     },
 };
 
 use ratatui::{
+    Frame,
     layout::{Constraint, Direction, Layout},
     style::{Color, Style},
     widgets::Paragraph,
-    Frame,
 };
 
-pub fn draw(frame: &mut Frame, app: &mut App) {
+pub fn draw(frame: &mut Frame, app: &mut App, keymap: &Keymap) {
     let area = frame.area();
 
-    let error_exists =
-        app.error_message.is_some() || app.pending_last_toggle_monitor;
+    let error_exists = app.error_message.is_some()
+        || app.status_message.is_some()
+        || app.pending_revert.is_some()
+        || !app.drift.is_empty()
+        || app.pending_last_toggle_monitor;
 
+    let footer_height = if app.expanded_footer {
+        key_binds::expanded_height(app, keymap, area.width)
+    } else {
+        1
+    };
     let constraints: [Constraint; 3] = if error_exists {
         [
             Constraint::Min(1),
-            Constraint::Length(1),
+            Constraint::Length(footer_height),
             Constraint::Length(1),
         ]
     } else {
         [
             Constraint::Min(1),
-            Constraint::Length(1),
+            Constraint::Length(footer_height),
             Constraint::Length(0),
         ]
     };
 
+    let [strip_area, body] = Layout::vertical([
+        Constraint::Length(u16::from(!app.monitors.is_empty())),
+        Constraint::Min(1),
+    ])
+    .areas(area);
+    strip::render(frame, app, strip_area);
+
     let main_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints(constraints)
-        .split(area);
+        .split(body);
 
     let content = Layout::default()
         .direction(Direction::Horizontal)
@@ -50,15 +66,53 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         ])
         .split(main_layout[0]);
 
-    left::panel(frame, app, content[0]);
-    mode::panel(frame, app, content[1]);
-    workspace::panel(frame, app, content[2]);
-    key_binds::config(frame, main_layout[1], app);
+    left::panel(frame, app, keymap, content[0]);
+    mode::panel(frame, app, keymap, content[1]);
+    workspace::panel(frame, app, keymap, content[2]);
+    if app.custom_mode.is_some() {
+        key_binds::custom_mode(frame, main_layout[1], app);
+    } else if app.wallpaper_prompt.is_some() {
+        key_binds::wallpaper_prompt(frame, main_layout[1], app);
+    } else if app.toggle_prompt.is_some() {
+        key_binds::toggle_prompt(frame, main_layout[1], app);
+    } else if app.search.is_some() {
+        key_binds::search(frame, main_layout[1], app);
+    } else {
+        key_binds::config(frame, main_layout[1], app, keymap);
+    }
 
     if let Some(ref err) = app.error_message {
         let error_bar =
             Paragraph::new(err.as_str()).style(Style::default().fg(Color::Red));
         frame.render_widget(error_bar, main_layout[2]);
+    } else if app.pending_revert.is_some() {
+        key_binds::revert(frame, main_layout[2], app);
+    } else if let Some(ref status) = app.status_message {
+        let (text, color) = if app.pending_risky_mode.is_some() {
+            let again = keymap.key_for(Action::Apply);
+            (
+                format!("{}. Press {} again to apply", status, again),
+                Color::Yellow,
+            )
+        } else {
+            (status.clone(), Color::Green)
+        };
+        let status_bar = Paragraph::new(text).style(Style::default().fg(color));
+        frame.render_widget(status_bar, main_layout[2]);
+    } else if !app.drift.is_empty() {
+        key_binds::drift(frame, main_layout[2], app, keymap);
+    }
+
+    if let Some(confirm) = app.pending_confirm {
+        left::render_confirm_modal(frame, area, confirm, app);
+    }
+
+    if app.show_version {
+        left::render_version_modal(frame, area, app);
+    }
+
+    if app.show_legend {
+        left::render_legend_modal(frame, area, keymap);
     }
 
     if app.pending_last_toggle_monitor {
@@ -1,30 +1,54 @@
 mod key_binds;
+pub mod keymap;
 mod layout;
 mod panels;
 mod ui;
 
 use crossterm::{
+    event::{DisableFocusChange, EnableFocusChange},
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode,
+        enable_raw_mode,
+    },
 };
 use ratatui::{Terminal, prelude::CrosstermBackend};
-use std::{io, sync::mpsc::Receiver};
+use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+use std::{
+    io,
+    sync::{Arc, atomic::AtomicBool, mpsc::Receiver},
+};
 use wlx_monitors::WlMonitorEvent;
 
-use crate::state::App;
+use crate::{state::App, tui::keymap::Keymap};
+
+pub fn run(
+    app: &mut App,
+    wlx_events: Receiver<WlMonitorEvent>,
+    keymap: &Keymap,
+) -> Result<(), ui::TuiLoopError> {
+    // Logout or `kill` should restore the terminal like a normal quit.
+    let shutdown = Arc::new(AtomicBool::new(false));
+    for signal in [SIGTERM, SIGINT, SIGHUP] {
+        signal_hook::flag::register(signal, Arc::clone(&shutdown))?;
+    }
 
-pub fn run(app: &mut App, wlx_events: Receiver<WlMonitorEvent>) -> Result<(), ui::TuiLoopError> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableFocusChange)?;
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    ui::tui_loop(app, wlx_events, &mut terminal)?;
+    let result =
+        ui::tui_loop(app, wlx_events, &mut terminal, keymap, &shutdown);
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        DisableFocusChange,
+        LeaveAlternateScreen
+    )?;
 
-    Ok(())
+    result
 }
@@ -1,10 +1,20 @@
 use crate::{
-    constants::TRANSFORMS,
-    state::{App, Panel},
-    tui::key_binds::{
-        get_monitor_keybinds, get_scale_keybinds, get_transform_keybinds,
+    cli,
+    compositor::{Compositor, color_temp, hyprland_scale_is_clean},
+    constants::{MIN_COLOR_TEMP, NEUTRAL_COLOR_TEMP, SEAM_MAX_GAP, TRANSFORMS},
+    geometry::{self, Contact, MonitorRect, Seam},
+    state::{App, Confirm, Panel},
+    tui::{
+        key_binds::{
+            get_color_keybinds, get_monitor_keybinds, get_scale_keybinds,
+            get_transform_keybinds,
+        },
+        keymap::{Action, Keymap},
+    },
+    utils::{
+        effective_dimensions, logical_size, monitor_color, monitor_resolution,
+        transform_label,
     },
-    utils::{self, effective_dimensions, monitor_resolution, transform_label},
 };
 
 use ratatui::{
@@ -12,28 +22,36 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, List, ListItem, Paragraph,
+        Row, Table,
+    },
 };
 use wlx_monitors::WlTransform;
 
-pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
+pub fn panel(frame: &mut Frame, app: &mut App, keymap: &Keymap, area: Rect) {
     let left = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(8), Constraint::Length(10)])
         .split(area);
 
-    render_map(frame, app, left[0]);
+    render_map(frame, app, keymap, left[0]);
 
     let bottom = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .constraints([
+            Constraint::Percentage(36),
+            Constraint::Percentage(28),
+            Constraint::Percentage(36),
+        ])
         .split(left[1]);
 
-    render_scale(frame, app, bottom[0]);
-    render_transform(frame, app, bottom[1]);
+    render_scale(frame, app, keymap, bottom[0]);
+    render_color(frame, app, keymap, bottom[1]);
+    render_transform(frame, app, keymap, bottom[2]);
 }
 
-fn render_map(frame: &mut Frame, app: &App, area: Rect) {
+fn render_map(frame: &mut Frame, app: &App, keymap: &Keymap, area: Rect) {
     let focused = app.panel == Panel::Monitor;
     let border_color = if focused {
         Color::Blue
@@ -47,7 +65,7 @@ fn render_map(frame: &mut Frame, app: &App, area: Rect) {
             " Monitor Layout | ",
             Style::default().fg(Color::Blue),
         ));
-        get_monitor_keybinds(&mut keys);
+        get_monitor_keybinds(&mut keys, keymap);
         Line::from(keys)
     } else {
         Line::from(Span::styled(
@@ -56,52 +74,86 @@ fn render_map(frame: &mut Frame, app: &App, area: Rect) {
         ))
     };
 
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(border_color))
         .title(title);
 
+    if let Some((w, h)) = app.virtual_desktop_size() {
+        block = block.title_bottom(
+            Line::from(Span::styled(
+                format!(" desktop {}×{} ", w, h),
+                Style::default().fg(Color::DarkGray),
+            ))
+            .right_aligned(),
+        );
+    }
+
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     if inner.height < 4 || inner.width < 10 {
         return;
     }
+    if app.table_view {
+        render_table(frame, app, inner);
+        return;
+    }
 
-    let grid_height = inner.height.saturating_sub(1) as usize;
+    let map_height = inner.height.saturating_sub(1) as usize;
+    let mut grid_height = map_height;
     let grid_width = inner.width as usize;
 
-    let mut lines = build_layout_map(app, grid_width, grid_height);
+    let mut lines = Vec::new();
+    if !app.monitors.is_empty() && !app.has_active_outputs() {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  No active outputs — select one and press {} to enable it",
+                keymap.key_for(Action::ToggleMonitor)
+            ),
+            Style::default().fg(Color::Yellow),
+        )));
+        grid_height = grid_height.saturating_sub(1);
+    }
+    let strip = build_disabled_strip(app, grid_width);
+    grid_height = grid_height.saturating_sub(strip.len());
+    lines.extend(build_layout_map(app, keymap, grid_width, grid_height));
 
-    while lines.len() < grid_height {
+    while lines.len() + strip.len() < map_height {
         lines.push(Line::from(""));
     }
+    lines.extend(strip);
 
     if let Some(monitor) = app.selected_monitor() {
-        let (ew, eh) = utils::effective_dimensions(monitor);
+        let (ew, eh) = app.display_dimensions(app.selected_monitor);
         if monitor.enabled {
-            let (dx, dy) = app.display_position(app.selected_monitor);
-            let has_pending = app.has_pending_positions();
-            let pos_color = if has_pending {
+            let pending_mode = app.pending_mode_resolution().is_some();
+            let has_pending = app.has_pending_positions() || pending_mode;
+            let pos_color = if app.has_pending_positions() {
                 Color::Yellow
             } else {
                 Color::DarkGray
             };
+            let size_color = if pending_mode {
+                Color::Yellow
+            } else {
+                Color::White
+            };
             let mut spans = vec![
-                Span::styled("  ○ ", Style::default().fg(Color::Green)),
+                Span::styled(STATE_DOT, Style::default().fg(ON_COLOR)),
                 Span::styled(
                     format!("{}  ", monitor.name),
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(monitor_color(app.selected_monitor))
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(
                     format!("{}×{}  ", ew, eh),
-                    Style::default().fg(Color::White),
+                    Style::default().fg(size_color),
                 ),
                 Span::styled(
-                    format!("({},{})  ", dx, dy),
+                    format!("{}  ", app.position_label(app.selected_monitor)),
                     Style::default().fg(pos_color),
                 ),
                 Span::styled(
@@ -115,16 +167,28 @@ fn render_map(frame: &mut Frame, app: &App, area: Rect) {
                         .add_modifier(Modifier::BOLD),
                 ),
             ];
+            if let Some(source) = app.config_mirrors.get(&monitor.name) {
+                spans.push(Span::styled(
+                    format!("  mirrors {}", source),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            if app.pending_mode_overlaps() {
+                spans.push(Span::styled(
+                    "  overlaps",
+                    Style::default().fg(Color::Red),
+                ));
+            }
             if has_pending {
                 spans.push(Span::styled(
-                    "  Enter to apply",
+                    format!("  {} to apply", keymap.key_for(Action::Apply)),
                     Style::default().fg(Color::Yellow),
                 ));
             }
             lines.push(Line::from(spans));
         } else {
             lines.push(Line::from(vec![
-                Span::styled("  ○ ", Style::default().fg(Color::Red)),
+                Span::styled(STATE_DOT, Style::default().fg(OFF_COLOR)),
                 Span::styled(
                     format!("{}  ", monitor.name),
                     Style::default()
@@ -142,20 +206,241 @@ fn render_map(frame: &mut Frame, app: &App, area: Rect) {
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(
-                    "— t to enable",
+                    format!(
+                        "— {} to enable",
+                        keymap.key_for(Action::ToggleMonitor)
+                    ),
                     Style::default().fg(Color::DarkGray),
                 ),
             ]));
         }
-    } else {
+    } else if app.monitors_received {
         lines.push(Line::from("  No monitor selected"));
     }
 
     frame.render_widget(Paragraph::new(lines), inner);
 }
 
+/// One row per monitor, for terminals too small or screen readers that
+/// can't make sense of the map. Pending edits show in yellow.
+fn render_table(frame: &mut Frame, app: &App, area: Rect) {
+    if app.monitors.is_empty() {
+        let msg = if app.monitors_received {
+            "  No monitors connected"
+        } else {
+            "  Waiting for monitors…"
+        };
+        frame.render_widget(
+            Paragraph::new(msg).style(Style::default().fg(Color::DarkGray)),
+            area,
+        );
+        return;
+    }
+
+    let pending = Style::default().fg(Color::Yellow);
+    let plain = Style::default().fg(Color::White);
+    let rows = app.monitors.iter().enumerate().map(|(idx, m)| {
+        let selected = idx == app.selected_monitor;
+        let refresh = m
+            .modes
+            .iter()
+            .find(|mode| mode.is_current)
+            .map(|mode| mode.refresh_rate)
+            .unwrap_or(0);
+        let (mode, mode_style) =
+            match selected.then(|| app.pending_mode_resolution()).flatten() {
+                Some((w, h)) => (format!("{}x{}", w, h), pending),
+                None => {
+                    let (w, h) = monitor_resolution(m);
+                    (format!("{}x{}@{}", w, h, refresh), plain)
+                }
+            };
+        let (scale, scale_style) = if selected
+            && app.panel == Panel::Scale
+            && (app.pending_scale - m.scale).abs() > 0.001
+        {
+            (format!("{:.2}", app.pending_scale), pending)
+        } else {
+            (format!("{}", m.scale), plain)
+        };
+        let (transform, transform_style) =
+            match selected.then(|| app.pending_transform()).flatten() {
+                Some(t) => (transform_label(t), pending),
+                None => (transform_label(m.transform), plain),
+            };
+        let position_style = if app.pending_positions.contains_key(&idx) {
+            pending
+        } else {
+            plain
+        };
+        let (state, state_style) = if m.enabled {
+            ("ON", Style::default().fg(Color::Green))
+        } else {
+            ("OFF", Style::default().fg(Color::Red))
+        };
+
+        let name_style = Style::default().fg(monitor_color(idx));
+        let row = Row::new([
+            Cell::from(m.name.clone()).style(name_style),
+            Cell::from(mode).style(mode_style),
+            Cell::from(scale).style(scale_style),
+            Cell::from(transform).style(transform_style),
+            Cell::from(app.position_label(idx)).style(position_style),
+            Cell::from(state).style(state_style),
+        ]);
+        if selected {
+            row.style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            )
+        } else {
+            row
+        }
+    });
+
+    let header =
+        Row::new(["Name", "Mode", "Scale", "Transform", "Position", ""])
+            .style(Style::default().fg(Color::DarkGray));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(8),
+            Constraint::Length(16),
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Length(14),
+            Constraint::Length(3),
+        ],
+    )
+    .header(header)
+    .column_spacing(1);
+    frame.render_widget(table, area);
+}
+
+/// Box-drawing characters for a monitor on the map: the top-left,
+/// top-right, bottom-left and bottom-right corners, then the horizontal and
+/// vertical edges.
+type BoxChars = [char; 6];
+
+const PLAIN_BOX: BoxChars = ['┌', '┐', '└', '┘', '─', '│'];
+const SELECTED_BOX: BoxChars = ['╔', '╗', '╚', '╝', '═', '║'];
+const GROUP_BOX: BoxChars = ['┏', '┓', '┗', '┛', '━', '┃'];
+const GHOST_BOX: BoxChars = ['┌', '┐', '└', '┘', '┄', '┆'];
+/// A turned-off monitor in the row under the map.
+const OFF_BOX: BoxChars = ['┌', '┐', '└', '┘', '╌', '╎'];
+
+const PENDING_COLOR: Color = Color::Yellow;
+const GROUP_COLOR: Color = Color::Cyan;
+const IDENTIFY_COLOR: Color = Color::White;
+const IDENTIFY_FILL: char = '░';
+const LOCKED_COLOR: Color = Color::Magenta;
+const LOCKED_LABEL: &str = "▣ locked";
+const BADGE_COLOR: Color = Color::Cyan;
+const STATE_DOT: &str = "  ○ ";
+const ON_COLOR: Color = Color::Green;
+const OFF_COLOR: Color = Color::Red;
+const OFF_BOX_COLOR: Color = Color::Rgb(60, 60, 60);
+const OFF_NAME_COLOR: Color = Color::Rgb(80, 80, 80);
+
+/// Seam markers for monitors side by side, then stacked.
+const FLUSH_MARKS: [char; 2] = ['↔', '↕'];
+const GAP_MARKS: [char; 2] = ['⋯', '⋮'];
+const OVERLAP_MARK: char = '✕';
+const FLUSH_COLOR: Color = Color::Green;
+const GAP_COLOR: Color = Color::DarkGray;
+const OVERLAP_COLOR: Color = Color::Red;
+
+/// The marker drawn where two monitors meet.
+fn seam_mark(seam: Seam) -> (char, Color) {
+    let axis = usize::from(!seam.side_by_side);
+    match seam.contact {
+        Contact::Flush => (FLUSH_MARKS[axis], FLUSH_COLOR),
+        Contact::Gap(_) => (GAP_MARKS[axis], GAP_COLOR),
+        Contact::Overlap => (OVERLAP_MARK, OVERLAP_COLOR),
+    }
+}
+
+/// What each marking on the map means, drawn with the characters and
+/// colors the map itself uses.
+fn legend_lines(keymap: &Keymap) -> Vec<Line<'static>> {
+    let edge = |b: BoxChars| format!("{}{}{}", b[0], b[4], b[1]);
+    let marks = |m: [char; 2]| format!("{} {}", m[0], m[1]);
+    let pending = format!(
+        "unapplied change, {} applies",
+        keymap.key_for(Action::Apply)
+    );
+    let entries = [
+        (
+            edge(PLAIN_BOX),
+            monitor_color(0),
+            "monitor, in its own color",
+        ),
+        (edge(SELECTED_BOX), Color::Gray, "selected"),
+        (
+            edge(GROUP_BOX),
+            GROUP_COLOR,
+            "grouped, moves with the selection",
+        ),
+        (
+            edge(GHOST_BOX),
+            PENDING_COLOR,
+            "disabled, shown where it will go",
+        ),
+        (edge(PLAIN_BOX), PENDING_COLOR, pending.as_str()),
+        (
+            edge(OFF_BOX),
+            OFF_BOX_COLOR,
+            "off, in the row under the map",
+        ),
+        (
+            edge(SELECTED_BOX),
+            IDENTIFY_COLOR,
+            "being identified, filled with its color",
+        ),
+        (
+            LOCKED_LABEL.chars().take(1).collect(),
+            LOCKED_COLOR,
+            "position locked",
+        ),
+        ("ws".to_string(), BADGE_COLOR, "workspaces on the monitor"),
+        (
+            marks(FLUSH_MARKS),
+            FLUSH_COLOR,
+            "edges touch, the cursor crosses",
+        ),
+        (marks(GAP_MARKS), GAP_COLOR, "gap between edges"),
+        (OVERLAP_MARK.to_string(), OVERLAP_COLOR, "monitors overlap"),
+        (
+            STATE_DOT.trim().to_string(),
+            ON_COLOR,
+            "selected monitor is on",
+        ),
+        (
+            STATE_DOT.trim().to_string(),
+            OFF_COLOR,
+            "selected monitor is off",
+        ),
+    ];
+    entries
+        .into_iter()
+        .map(|(sample, color, meaning)| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:<4}", sample),
+                    Style::default().fg(color),
+                ),
+                Span::styled(
+                    meaning.to_string(),
+                    Style::default().fg(Color::White),
+                ),
+            ])
+        })
+        .collect()
+}
+
 fn build_layout_map<'a>(
     app: &App,
+    keymap: &Keymap,
     width: usize,
     height: usize,
 ) -> Vec<Line<'a>> {
@@ -164,7 +449,15 @@ fn build_layout_map<'a>(
     let zoom = app.map_zoom;
 
     if monitors.is_empty() {
-        return vec![Line::from("  No monitors")];
+        let msg = if app.monitors_received {
+            "  No monitors connected"
+        } else {
+            "  Waiting for monitors…"
+        };
+        return vec![Line::from(Span::styled(
+            msg,
+            Style::default().fg(Color::DarkGray),
+        ))];
     }
     if width < 5 || height < 3 {
         return vec![Line::from("  Panel too small")];
@@ -172,65 +465,81 @@ fn build_layout_map<'a>(
 
     struct MonRect {
         name: String,
+        color: Color,
         px: i32,
         py: i32,
         pw: i32,
         ph: i32,
         is_selected: bool,
-        is_enabled: bool,
+        is_pending: bool,
+        is_moved: bool,
         res_label: String,
         pos_label: String,
+        /// Scale or transform picked in their panels but not applied yet.
+        pending_note: Option<String>,
+        /// Assigned workspace ids, e.g. `ws 1 2 5`, when badges are on.
+        workspaces: Option<String>,
+        /// A disabled monitor previewed where enabling will put it.
+        is_ghost: bool,
+        is_locked: bool,
+        /// Picked with Space to move along with the selected monitor.
+        in_group: bool,
+        /// Currently pointed out by identify.
+        is_identified: bool,
     }
 
     let mut monitor_rects: Vec<MonRect> = Vec::new();
     for (idx, m) in monitors.iter().enumerate() {
-        if !m.enabled {
+        let is_ghost = !m.enabled && app.pending_enable == Some(idx);
+        if !m.enabled && !is_ghost {
             continue;
         }
-        let (w, h) = effective_dimensions(m);
-        let (rw, rh) = monitor_resolution(m);
+        let (w, h) = app.display_dimensions(idx);
+        let pending_res = (idx == selected_idx)
+            .then(|| app.pending_mode_resolution())
+            .flatten();
+        let (rw, rh) = pending_res.unwrap_or_else(|| monitor_resolution(m));
         let (px, py) = app.display_position(idx);
+        let is_moved = app.pending_positions.contains_key(&idx);
+        let pending_note = if is_ghost {
+            Some(format!("{} to enable", keymap.key_for(Action::Apply)))
+        } else {
+            pending_note(app, idx)
+        };
+        let workspaces = app
+            .show_workspace_badges
+            .then(|| app.workspaces_on(idx))
+            .filter(|ids| !ids.is_empty())
+            .map(|ids| {
+                let ids: Vec<String> =
+                    ids.iter().map(ToString::to_string).collect();
+                format!("ws {}", ids.join(" "))
+            });
         monitor_rects.push(MonRect {
             name: m.name.clone(),
+            color: monitor_color(idx),
             px,
             py,
             pw: w.max(1),
             ph: h.max(1),
             is_selected: idx == selected_idx,
-            is_enabled: true,
+            is_pending: pending_res.is_some()
+                || is_moved
+                || pending_note.is_some(),
+            is_moved,
             res_label: format!("{}×{}", rw, rh),
-            pos_label: format!("({},{})", px, py),
+            pos_label: app.position_label(idx),
+            pending_note,
+            workspaces,
+            is_ghost,
+            is_locked: app.monitor_memory.is_locked(&m.name),
+            in_group: app.group.contains(&idx),
+            is_identified: app.identified_monitor() == Some(idx),
         });
     }
-
-    let bottom_y = if monitor_rects.is_empty() {
-        0
-    } else {
-        monitor_rects.iter().map(|r| r.py + r.ph).max().unwrap_or(0)
-    };
-    let disabled_y = bottom_y + 200;
-    let mut disabled_x = monitor_rects.iter().map(|r| r.px).min().unwrap_or(0);
-
-    for (idx, m) in monitors.iter().enumerate() {
-        if m.enabled {
-            continue;
-        }
-        let (w, h) = effective_dimensions(m);
-        let (rw, rh) = monitor_resolution(m);
-        let pw = w.max(1);
-        let ph = h.max(1);
-        monitor_rects.push(MonRect {
-            name: m.name.clone(),
-            px: disabled_x,
-            py: disabled_y,
-            pw,
-            ph,
-            is_selected: idx == selected_idx,
-            is_enabled: false,
-            res_label: format!("{}×{}", rw, rh),
-            pos_label: "OFF".to_string(),
-        });
-        disabled_x += pw + 100;
+    // Disabled monitors live in their own strip below the map
+    if monitor_rects.is_empty() {
+        return vec![];
     }
 
     let min_x = monitor_rects.iter().map(|r| r.px).min().unwrap_or(0);
@@ -242,7 +551,7 @@ fn build_layout_map<'a>(
     let total_h = (max_y - min_y) as f64;
 
     if total_w <= 0.0 || total_h <= 0.0 {
-        return vec![];
+        return vec![Line::from("  Monitors report no size yet")];
     }
 
     const CHAR_ASPECT: f64 = 2.0;
@@ -262,60 +571,64 @@ fn build_layout_map<'a>(
     let mut grid: Vec<Vec<(char, Color, bool)>> =
         vec![vec![(' ', Color::Reset, false); width]; height];
 
+    // Panning shifts the corner of the desktop shown at the grid origin
+    let origin_x = min_x as f64 + app.map_pan_x as f64 * ppc;
+    let origin_y = min_y as f64 + app.map_pan_y as f64 * ppc * CHAR_ASPECT;
+
     for rect in &monitor_rects {
-        let cx = pad + ((rect.px - min_x) as f64 / ppc) as usize;
-        let cy = ((rect.py - min_y) as f64 / (ppc * CHAR_ASPECT)) as usize;
-        let cw = (rect.pw as f64 / ppc).round().max(1.0) as usize;
-        let ch =
-            (rect.ph as f64 / (ppc * CHAR_ASPECT)).round().max(1.0) as usize;
-
-        let x1 = cx.min(width.saturating_sub(1));
-        let y1 = cy.min(height.saturating_sub(1));
-        let x2 = (cx + cw).min(width);
-        let y2 = (cy + ch).min(height);
+        let cx =
+            pad as i64 + ((rect.px as f64 - origin_x) / ppc).floor() as i64;
+        let cy =
+            ((rect.py as f64 - origin_y) / (ppc * CHAR_ASPECT)).floor() as i64;
+        let cw = (rect.pw as f64 / ppc).round().max(1.0) as i64;
+        let ch = (rect.ph as f64 / (ppc * CHAR_ASPECT)).round().max(1.0) as i64;
+
+        // Scrolled or zoomed entirely out of view
+        if cx + cw <= 0
+            || cy + ch <= 0
+            || cx >= width as i64
+            || cy >= height as i64
+        {
+            continue;
+        }
+        let x1 = cx.max(0) as usize;
+        let y1 = cy.max(0) as usize;
+        let x2 = (cx + cw).min(width as i64) as usize;
+        let y2 = (cy + ch).min(height as i64) as usize;
         let w = x2.saturating_sub(x1);
         let h = y2.saturating_sub(y1);
 
         if w < 2 || h < 2 {
             if y1 < height && x1 < width {
                 let ch = rect.name.chars().next().unwrap_or('?');
-                let fg = if rect.is_selected {
-                    Color::Cyan
-                } else if rect.is_enabled {
-                    Color::White
-                } else {
-                    Color::DarkGray
-                };
-                grid[y1][x1] = (ch, fg, rect.is_selected);
+                grid[y1][x1] = (ch, rect.color, rect.is_selected);
             }
             continue;
         }
 
-        let border_fg = if rect.is_selected && rect.is_enabled {
-            Color::Cyan
-        } else if rect.is_selected {
-            Color::Yellow
-        } else if rect.is_enabled {
-            Color::DarkGray
+        let border_fg = if rect.is_identified {
+            IDENTIFY_COLOR
+        } else if rect.is_pending {
+            PENDING_COLOR
+        } else if rect.in_group {
+            GROUP_COLOR
         } else {
-            Color::Rgb(60, 60, 60)
+            rect.color
         };
-        let text_fg = if rect.is_selected && rect.is_enabled {
+        let text_fg = if rect.is_selected {
             Color::White
-        } else if rect.is_selected {
-            Color::Yellow
-        } else if rect.is_enabled {
-            Color::Gray
         } else {
-            Color::Rgb(80, 80, 80)
+            Color::Gray
         };
 
-        let (tl, tr, bl, br, hc, vc) = if rect.is_selected {
-            ('╔', '╗', '╚', '╝', '═', '║')
-        } else if rect.is_enabled {
-            ('┌', '┐', '└', '┘', '─', '│')
+        let [tl, tr, bl, br, hc, vc] = if rect.is_ghost {
+            GHOST_BOX
+        } else if rect.is_selected || rect.is_identified {
+            SELECTED_BOX
+        } else if rect.in_group {
+            GROUP_BOX
         } else {
-            ('┌', '┐', '└', '┘', '╌', '╎')
+            PLAIN_BOX
         };
 
         grid[y1][x1] = (tl, border_fg, false);
@@ -335,9 +648,15 @@ fn build_layout_map<'a>(
             row[x2 - 1] = (vc, border_fg, false);
         }
 
+        // Identify shades the monitor in its own color
+        let fill = if rect.is_identified {
+            (IDENTIFY_FILL, rect.color)
+        } else {
+            (' ', text_fg)
+        };
         for row in grid[(y1 + 1)..(y2 - 1)].iter_mut() {
             for cell in row[(x1 + 1)..(x2 - 1)].iter_mut() {
-                *cell = (' ', text_fg, false);
+                *cell = (fill.0, fill.1, false);
             }
         }
 
@@ -345,15 +664,28 @@ fn build_layout_map<'a>(
         let inner_h = h.saturating_sub(2);
 
         if inner_w >= 1 && inner_h >= 1 {
-            let text_lines: Vec<(&str, bool)> = vec![
-                (&rect.name, true),
-                (&rect.res_label, false),
-                (&rect.pos_label, false),
+            let pos_fg = if rect.is_moved {
+                PENDING_COLOR
+            } else {
+                text_fg
+            };
+            let mut text_lines: Vec<(&str, bool, Color)> = vec![
+                (&rect.name, true, text_fg),
+                (&rect.res_label, false, text_fg),
+                (&rect.pos_label, false, pos_fg),
             ];
+            if let Some(ref note) = rect.pending_note {
+                text_lines.push((note, false, PENDING_COLOR));
+            }
+            if rect.is_locked {
+                text_lines.push((LOCKED_LABEL, false, LOCKED_COLOR));
+            }
             let count = text_lines.len().min(inner_h);
             let start_y = y1 + 1 + inner_h.saturating_sub(count) / 2;
 
-            for (i, (text, bold)) in text_lines.iter().take(count).enumerate() {
+            for (i, (text, bold, fg)) in
+                text_lines.iter().take(count).enumerate()
+            {
                 let row = start_y + i;
                 if row >= y2 - 1 {
                     break;
@@ -364,11 +696,49 @@ fn build_layout_map<'a>(
                 for (j, ch) in truncated.chars().enumerate() {
                     let col = text_start + j;
                     if col < x2 - 1 {
-                        grid[row][col] =
-                            (ch, text_fg, *bold || rect.is_selected);
+                        grid[row][col] = (ch, *fg, *bold || rect.is_selected);
                     }
                 }
             }
+
+            // Badges take the bottom row, only when it is free and wide
+            // enough to show every id
+            if let Some(ref badges) = rect.workspaces
+                && inner_h > text_lines.len()
+                && badges.chars().count() <= inner_w
+            {
+                let row = y2 - 2;
+                let badge_start =
+                    x1 + 1 + (inner_w - badges.chars().count()) / 2;
+                for (j, ch) in badges.chars().enumerate() {
+                    grid[row][badge_start + j] = (ch, BADGE_COLOR, false);
+                }
+            }
+        }
+    }
+
+    // Mark where monitors meet, so touching can be told apart from nearly
+    // touching
+    let placed: Vec<MonitorRect> = monitor_rects
+        .iter()
+        .filter(|r| !r.is_ghost)
+        .map(|r| MonitorRect::new((r.px, r.py), (r.pw, r.ph)))
+        .collect();
+    for (i, &a) in placed.iter().enumerate() {
+        for &b in &placed[i + 1..] {
+            let Some(seam) = geometry::seam(a, b, SEAM_MAX_GAP) else {
+                continue;
+            };
+            let col = pad as i64
+                + ((seam.at.0 as f64 - origin_x) / ppc).floor() as i64;
+            let row = ((seam.at.1 as f64 - origin_y) / (ppc * CHAR_ASPECT))
+                .floor() as i64;
+            if (0..width as i64).contains(&col)
+                && (0..height as i64).contains(&row)
+            {
+                let (mark, color) = seam_mark(seam);
+                grid[row as usize][col as usize] = (mark, color, true);
+            }
         }
     }
 
@@ -398,7 +768,103 @@ fn build_layout_map<'a>(
     lines
 }
 
-fn render_scale(frame: &mut Frame, app: &App, area: Rect) {
+/// A monitor's unapplied scale and transform, e.g. `→ 1.50x 90°`.
+fn pending_note(app: &App, idx: usize) -> Option<String> {
+    let (scale, transform) = if idx == app.selected_monitor {
+        let summary = app.pending_summary();
+        (
+            summary.scale.then_some(app.pending_scale),
+            app.pending_transform().filter(|_| summary.transform),
+        )
+    } else {
+        (
+            app.pending_scales.get(&idx).copied(),
+            app.pending_transforms.get(&idx).copied(),
+        )
+    };
+    let mut parts = Vec::new();
+    if let Some(scale) = scale {
+        parts.push(format!("{:.2}x", scale));
+    }
+    if let Some(transform) = transform {
+        parts.push(transform_label(transform).to_string());
+    }
+    (!parts.is_empty()).then(|| format!("→ {}", parts.join(" ")))
+}
+
+/// Disabled monitors as small fixed-size boxes in a row, so they stay
+/// visible and selectable whatever the map's zoom. Empty when every monitor
+/// is enabled.
+///
+/// wlr-output-management (and so wlx_monitors) only reports enabled or not,
+/// so an output that is connected but asleep or without signal can't be told
+/// apart from one the user turned off; both end up here.
+fn build_disabled_strip<'a>(app: &App, width: usize) -> Vec<Line<'a>> {
+    const NAME_MAX: usize = 12;
+
+    let disabled: Vec<(usize, &str)> = app
+        .monitors
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| !m.enabled)
+        .map(|(idx, m)| (idx, m.name.as_str()))
+        .collect();
+    if disabled.is_empty() || width < 10 {
+        return vec![];
+    }
+
+    let label = "  off ";
+    let mut rows: [Vec<Span>; 3] = [
+        vec![Span::raw(" ".repeat(label.len()))],
+        vec![Span::styled(label, Style::default().fg(Color::DarkGray))],
+        vec![Span::raw(" ".repeat(label.len()))],
+    ];
+    let mut used = label.len();
+
+    for (shown, &(idx, name)) in disabled.iter().enumerate() {
+        let name: String = name.chars().take(NAME_MAX).collect();
+        let inner = name.chars().count() + 2;
+        let hidden = disabled.len() - shown - 1;
+        // Leave room for the "+N" marker when more boxes follow
+        let reserve = if hidden > 0 { 4 } else { 0 };
+        if used + inner + 3 + reserve > width {
+            rows[1].push(Span::styled(
+                format!("+{}", disabled.len() - shown),
+                Style::default().fg(Color::DarkGray),
+            ));
+            break;
+        }
+
+        let selected = idx == app.selected_monitor;
+        let (border, text) = if selected {
+            (
+                Style::default().fg(Color::Yellow),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            (
+                Style::default().fg(app.color(OFF_BOX_COLOR)),
+                Style::default().fg(app.color(OFF_NAME_COLOR)),
+            )
+        };
+        let [tl, tr, bl, br, hc, vc] =
+            if selected { SELECTED_BOX } else { OFF_BOX };
+        let edge = hc.to_string().repeat(inner);
+
+        rows[0].push(Span::styled(format!("{}{}{} ", tl, edge, tr), border));
+        rows[1].push(Span::styled(vc.to_string(), border));
+        rows[1].push(Span::styled(format!(" {} ", name), text));
+        rows[1].push(Span::styled(format!("{} ", vc), border));
+        rows[2].push(Span::styled(format!("{}{}{} ", bl, edge, br), border));
+        used += inner + 3;
+    }
+
+    rows.into_iter().map(Line::from).collect()
+}
+
+fn render_scale(frame: &mut Frame, app: &App, keymap: &Keymap, area: Rect) {
     let focused = app.panel == Panel::Scale;
     let border_color = if focused {
         Color::Blue
@@ -409,7 +875,7 @@ fn render_scale(frame: &mut Frame, app: &App, area: Rect) {
     let title = if focused {
         let mut keys = Vec::new();
         keys.push(Span::styled(" Scale | ", Style::default().fg(Color::Blue)));
-        get_scale_keybinds(&mut keys);
+        get_scale_keybinds(&mut keys, keymap);
         Line::from(keys)
     } else {
         Line::from(Span::styled(
@@ -424,8 +890,9 @@ fn render_scale(frame: &mut Frame, app: &App, area: Rect) {
     let changed = (current - pending).abs() > 0.001;
 
     let bar_width = (area.width as usize).saturating_sub(6);
-    let max_scale = 10.0_f64;
-    let fill = ((pending / max_scale) * bar_width as f64)
+    let limits = app.scale_limits;
+    let fill = (((pending - limits.min) / (limits.max - limits.min))
+        * bar_width as f64)
         .round()
         .min(bar_width as f64) as usize;
     let empty = bar_width.saturating_sub(fill);
@@ -434,6 +901,21 @@ fn render_scale(frame: &mut Frame, app: &App, area: Rect) {
 
     let pending_color = if changed { Color::Yellow } else { Color::White };
 
+    let scale_warning = match (app.compositor, monitor) {
+        (Compositor::Hyprland, Some(m)) => {
+            let (w, h) = monitor_resolution(m);
+            (!hyprland_scale_is_clean(w, h, pending))
+                .then_some("not a whole-pixel scale here")
+        }
+        (Compositor::Sway, Some(_)) => app
+            .compositor
+            .capabilities()
+            .check_scale(pending, app.scale_limits)
+            .is_err()
+            .then_some("not a 1/120 step; Sway won't take it"),
+        _ => None,
+    };
+
     let lines = vec![
         Line::from(""),
         Line::from(vec![
@@ -459,15 +941,55 @@ fn render_scale(frame: &mut Frame, app: &App, area: Rect) {
             Span::styled("●", Style::default().fg(Color::White)),
             Span::styled(empty_part, Style::default().fg(Color::DarkGray)),
         ]),
-        Line::from(""),
-        if changed {
+        if let Some(warning) = scale_warning {
+            Line::from(vec![Span::styled(
+                format!("  ⚠ {}", warning),
+                Style::default().fg(Color::Red),
+            )])
+        } else {
+            Line::from("")
+        },
+        if let Some(m) = monitor
+            && (changed || (pending - 1.0).abs() > 0.001)
+        {
+            // Physical pixels next to the desktop size they act as
+            let (w, h) = effective_dimensions(m);
+            let (lw, lh) = logical_size(w, h, pending);
+            let mut spans = vec![
+                Span::styled(
+                    format!("  {}×{} px → ", w, h),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{}×{}", lw, lh),
+                    Style::default().fg(pending_color),
+                ),
+                Span::styled(
+                    " logical  ",
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ];
+            if changed {
+                spans.push(Span::styled(
+                    format!("{} to apply", keymap.key_for(Action::Apply)),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            Line::from(spans)
+        } else if changed {
             Line::from(vec![Span::styled(
-                "  Enter to apply",
+                format!("  {} to apply", keymap.key_for(Action::Apply)),
                 Style::default().fg(Color::Yellow),
             )])
         } else {
             Line::from(vec![Span::styled(
-                "  ↑↓ or +/- adjust",
+                format!(
+                    "  {}{} or {}/{} adjust",
+                    keymap.key_for(Action::MoveUp),
+                    keymap.key_for(Action::MoveDown),
+                    keymap.key_for(Action::ZoomIn),
+                    keymap.key_for(Action::ZoomOut)
+                ),
                 Style::default().fg(Color::DarkGray),
             )])
         },
@@ -482,7 +1004,114 @@ fn render_scale(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(Paragraph::new(lines).block(block), area);
 }
 
-fn render_transform(frame: &mut Frame, app: &mut App, area: Rect) {
+fn render_color(frame: &mut Frame, app: &App, keymap: &Keymap, area: Rect) {
+    let focused = app.panel == Panel::Color;
+    let border_color = if focused {
+        Color::Blue
+    } else {
+        Color::DarkGray
+    };
+
+    let title = if focused {
+        let mut keys = Vec::new();
+        keys.push(Span::styled(" Color | ", Style::default().fg(Color::Blue)));
+        get_color_keybinds(&mut keys, keymap);
+        Line::from(keys)
+    } else {
+        Line::from(Span::styled(
+            " Color ",
+            Style::default().fg(Color::DarkGray),
+        ))
+    };
+
+    let current = app
+        .selected_monitor()
+        .map(|m| app.color_temp(&m.name))
+        .unwrap_or(NEUTRAL_COLOR_TEMP);
+    let pending = app.pending_color_temp;
+    let changed = current != pending;
+
+    // Full bar is neutral; it empties as the output gets warmer
+    let bar_width = (area.width as usize).saturating_sub(6);
+    let range = (NEUTRAL_COLOR_TEMP - MIN_COLOR_TEMP) as f64;
+    let fill = ((pending.saturating_sub(MIN_COLOR_TEMP)) as f64 / range
+        * bar_width as f64)
+        .round()
+        .min(bar_width as f64) as usize;
+    let empty = bar_width.saturating_sub(fill);
+    let filled_part = "━".repeat(fill.saturating_sub(1));
+    let empty_part = "─".repeat(empty);
+
+    let pending_color = if changed { Color::Yellow } else { Color::White };
+    let kelvin = |k: u32| {
+        if k >= NEUTRAL_COLOR_TEMP {
+            format!("{}K neutral", k)
+        } else {
+            format!("{}K", k)
+        }
+    };
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  current ", Style::default().fg(Color::DarkGray)),
+            Span::styled(kelvin(current), Style::default().fg(Color::White)),
+        ]),
+        Line::from(vec![
+            Span::styled("  pending ", Style::default().fg(Color::DarkGray)),
+            Span::styled(kelvin(pending), Style::default().fg(pending_color)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("  {}", filled_part),
+                Style::default().fg(app.color(Color::Rgb(255, 170, 80))),
+            ),
+            Span::styled("●", Style::default().fg(Color::White)),
+            Span::styled(empty_part, Style::default().fg(Color::DarkGray)),
+        ]),
+        if color_temp::is_global(app.compositor) {
+            Line::from(Span::styled(
+                "  applies to every output",
+                Style::default().fg(Color::DarkGray),
+            ))
+        } else {
+            Line::from("")
+        },
+        if changed {
+            Line::from(Span::styled(
+                format!("  {} to apply", keymap.key_for(Action::Apply)),
+                Style::default().fg(Color::Yellow),
+            ))
+        } else {
+            Line::from(Span::styled(
+                format!(
+                    "  {}{} or {}/{} adjust",
+                    keymap.key_for(Action::MoveLeft),
+                    keymap.key_for(Action::MoveRight),
+                    keymap.key_for(Action::ZoomIn),
+                    keymap.key_for(Action::ZoomOut)
+                ),
+                Style::default().fg(Color::DarkGray),
+            ))
+        },
+    ];
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(border_color))
+        .title(title);
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_transform(
+    frame: &mut Frame,
+    app: &mut App,
+    keymap: &Keymap,
+    area: Rect,
+) {
     let focused = app.panel == Panel::Transform;
     let border_color = if focused {
         Color::Blue
@@ -496,7 +1125,7 @@ fn render_transform(frame: &mut Frame, app: &mut App, area: Rect) {
             " Transform | ",
             Style::default().fg(Color::Blue),
         ));
-        get_transform_keybinds(&mut keys);
+        get_transform_keybinds(&mut keys, keymap);
         Line::from(keys)
     } else {
         Line::from(Span::styled(
@@ -529,12 +1158,19 @@ fn render_transform(frame: &mut Frame, app: &mut App, area: Rect) {
         })
         .collect();
 
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(border_color))
         .title(title);
 
+    if let Some(note) = app.compositor.capabilities().transform_note() {
+        block = block.title_bottom(Line::from(Span::styled(
+            format!(" {} ", note),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
     let list = List::new(items)
         .block(block)
         .highlight_symbol(" › ")
@@ -544,6 +1180,7 @@ fn render_transform(frame: &mut Frame, app: &mut App, area: Rect) {
                 .add_modifier(Modifier::BOLD),
         );
 
+    app.transform_list_height = area.height.saturating_sub(2) as usize;
     frame.render_stateful_widget(list, area, &mut app.transform_state);
 }
 
@@ -607,6 +1244,297 @@ pub fn render_warning_modal(frame: &mut Frame, area: Rect, config_path: &str) {
         )]),
     ];
 
+    let text_widget =
+        Paragraph::new(text).style(Style::default().fg(Color::White));
+    frame.render_widget(text_widget, layout[0]);
+
+    render_confirm_buttons(frame, layout[1]);
+}
+
+pub fn render_confirm_modal(
+    frame: &mut Frame,
+    area: Rect,
+    confirm: Confirm,
+    app: &App,
+) {
+    let selected = app
+        .selected_monitor()
+        .map(|m| m.name.as_str())
+        .unwrap_or("?");
+
+    let (title, text) = match confirm {
+        Confirm::SoloMonitor => {
+            let others: Vec<&str> = app
+                .monitors
+                .iter()
+                .filter(|m| m.enabled && m.name != selected)
+                .map(|m| m.name.as_str())
+                .collect();
+            (
+                " Only this monitor ",
+                vec![
+                    Line::from(vec![
+                        Span::styled(
+                            " Keep only ",
+                            Style::default().fg(Color::White),
+                        ),
+                        Span::styled(
+                            selected.to_string(),
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(
+                            " enabled?",
+                            Style::default().fg(Color::White),
+                        ),
+                    ]),
+                    Line::from(""),
+                    Line::from(vec![
+                        Span::styled(
+                            " Turns off: ",
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::styled(
+                            others.join(", "),
+                            Style::default().fg(Color::Yellow),
+                        ),
+                    ]),
+                    Line::from(vec![Span::styled(
+                        " E re-enables all monitors afterwards.",
+                        Style::default().fg(Color::DarkGray),
+                    )]),
+                ],
+            )
+        }
+        Confirm::RotateAll => {
+            let transform = app
+                .transform_state
+                .selected()
+                .and_then(|i| TRANSFORMS.get(i))
+                .map(|&t| transform_label(t))
+                .unwrap_or("?");
+            let count = app.monitors.iter().filter(|m| m.enabled).count();
+            (
+                " Rotate all ",
+                vec![
+                    Line::from(vec![
+                        Span::styled(
+                            " Apply ",
+                            Style::default().fg(Color::White),
+                        ),
+                        Span::styled(
+                            transform,
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(
+                            format!(" to all {} enabled monitors?", count),
+                            Style::default().fg(Color::White),
+                        ),
+                    ]),
+                    Line::from(""),
+                    Line::from(vec![Span::styled(
+                        " Each monitor keeps its current position.",
+                        Style::default().fg(Color::DarkGray),
+                    )]),
+                ],
+            )
+        }
+        Confirm::RevertToFile => (
+            " Revert to file ",
+            vec![
+                Line::from(vec![Span::styled(
+                    " Restore every monitor to the saved config?",
+                    Style::default().fg(Color::White),
+                )]),
+                Line::from(""),
+                Line::from(vec![Span::styled(
+                    " Live changes and pending moves are lost.",
+                    Style::default().fg(Color::DarkGray),
+                )]),
+            ],
+        ),
+        Confirm::ResolveOverlaps => {
+            let pair = app
+                .overlapping_pair()
+                .map(|(a, b)| {
+                    format!(
+                        " {} and {} overlap.",
+                        app.monitors[a].name, app.monitors[b].name
+                    )
+                })
+                .unwrap_or_else(|| " Monitors overlap.".to_string());
+            (
+                " Overlapping monitors ",
+                vec![
+                    Line::from(vec![Span::styled(
+                        pair,
+                        Style::default().fg(Color::White),
+                    )]),
+                    Line::from(""),
+                    Line::from(vec![Span::styled(
+                        " Move them to the closest free spots?",
+                        Style::default().fg(Color::DarkGray),
+                    )]),
+                ],
+            )
+        }
+        Confirm::CollapseDuplicates => {
+            let mut lines = vec![
+                Line::from(vec![Span::styled(
+                    " Monitors listed more than once in the config:",
+                    Style::default().fg(Color::White),
+                )]),
+                Line::from(""),
+            ];
+            lines.extend(app.duplicate_entries.iter().map(|(name, count)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {}", name),
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!(" {} entries", count),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                ])
+            }));
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![Span::styled(
+                " The last entry wins. Remove the ones it overrides?",
+                Style::default().fg(Color::DarkGray),
+            )]));
+            (" Duplicate entries ", lines)
+        }
+        Confirm::RestoreDraft => {
+            let count = app
+                .restorable_draft
+                .as_ref()
+                .map(|d| d.change_count())
+                .unwrap_or(0);
+            (
+                " Restore draft ",
+                vec![
+                    Line::from(vec![
+                        Span::styled(
+                            " Restore ",
+                            Style::default().fg(Color::White),
+                        ),
+                        Span::styled(
+                            count.to_string(),
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(
+                            " unapplied change(s) from last time?",
+                            Style::default().fg(Color::White),
+                        ),
+                    ]),
+                    Line::from(""),
+                    Line::from(vec![Span::styled(
+                        " They stay pending until you apply them.",
+                        Style::default().fg(Color::DarkGray),
+                    )]),
+                ],
+            )
+        }
+    };
+
+    let modal_w = 52u16.min(area.width.saturating_sub(4));
+    let modal_h = (text.len() as u16 + 6).min(area.height.saturating_sub(2));
+    let x = (area.width.saturating_sub(modal_w)) / 2;
+    let y = (area.height.saturating_sub(modal_h)) / 2;
+    let modal_area = Rect::new(x, y, modal_w, modal_h);
+
+    frame.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Yellow))
+        .title(title);
+
+    let inner = block.inner(modal_area);
+    frame.render_widget(block, modal_area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(3)])
+        .split(inner);
+
+    frame.render_widget(Paragraph::new(text), layout[0]);
+    render_confirm_buttons(frame, layout[1]);
+}
+
+pub fn render_version_modal(frame: &mut Frame, area: Rect, app: &App) {
+    let mut text: Vec<Line> = cli::version_lines(app.compositor)
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let style = if i == 0 {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            Line::from(Span::styled(format!(" {}", line), style))
+        })
+        .collect();
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        " Press any key to close",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let modal_w = 40u16.min(area.width.saturating_sub(4));
+    let modal_h = (text.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let x = (area.width.saturating_sub(modal_w)) / 2;
+    let y = (area.height.saturating_sub(modal_h)) / 2;
+    let modal_area = Rect::new(x, y, modal_w, modal_h);
+
+    frame.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Version ");
+
+    frame.render_widget(Paragraph::new(text).block(block), modal_area);
+}
+
+pub fn render_legend_modal(frame: &mut Frame, area: Rect, keymap: &Keymap) {
+    let mut text = legend_lines(keymap);
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        " Press any key to close",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let modal_w = 48u16.min(area.width.saturating_sub(4));
+    let modal_h = (text.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let x = (area.width.saturating_sub(modal_w)) / 2;
+    let y = (area.height.saturating_sub(modal_h)) / 2;
+    let modal_area = Rect::new(x, y, modal_w, modal_h);
+
+    frame.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Map legend ");
+
+    frame.render_widget(Paragraph::new(text).block(block), modal_area);
+}
+
+fn render_confirm_buttons(frame: &mut Frame, area: Rect) {
     let buttons = vec![
         Line::from(vec![
             Span::styled(" ┌───────┐ ", Style::default().fg(Color::Red)),
@@ -636,11 +1564,7 @@ pub fn render_warning_modal(frame: &mut Frame, area: Rect, config_path: &str) {
         ]),
     ];
 
-    let text_widget =
-        Paragraph::new(text).style(Style::default().fg(Color::White));
-    frame.render_widget(text_widget, layout[0]);
-
     let buttons_widget =
         Paragraph::new(buttons).style(Style::default().fg(Color::White));
-    frame.render_widget(buttons_widget, layout[1]);
+    frame.render_widget(buttons_widget, area);
 }
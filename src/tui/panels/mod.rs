@@ -1,3 +1,4 @@
 pub mod left;
 pub mod mode;
+pub mod strip;
 pub mod workspace;
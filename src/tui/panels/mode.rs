@@ -1,17 +1,18 @@
 use crate::{
     state::{App, Panel},
-    tui::key_binds::get_modes_keybinds,
+    tui::{key_binds::get_modes_keybinds, keymap::Keymap},
+    utils::{ModeRow, monitor_resolution, refresh_range},
 };
 
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, List, ListItem},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph},
 };
 
-pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
+pub fn panel(frame: &mut Frame, app: &mut App, keymap: &Keymap, area: Rect) {
     let focused = app.panel == Panel::Mode;
     let border_color = if focused {
         Color::Blue
@@ -22,7 +23,7 @@ pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
     let title = if focused {
         let mut keys = Vec::new();
         keys.push(Span::styled(" Modes ", Style::default().fg(Color::Blue)));
-        get_modes_keybinds(&mut keys);
+        get_modes_keybinds(&mut keys, keymap);
         Line::from(keys)
     } else {
         Line::from(Span::styled(
@@ -32,30 +33,53 @@ pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
     };
 
     let monitor = app.selected_monitor().cloned();
+    let rows = app.mode_rows();
     let items: Vec<ListItem> = monitor
         .as_ref()
         .map(|m| {
-            m.modes
-                .iter()
-                .map(|mode| {
-                    let marker = if mode.is_current { "▸ " } else { "  " };
-                    let preferred = if mode.preferred { " ★" } else { "" };
-                    let style = if mode.is_current {
+            rows.iter()
+                .map(|row| {
+                    let entry = match *row {
+                        ModeRow::Header(w, h) => {
+                            return Line::from(Span::styled(
+                                format!("{}×{}", w, h),
+                                Style::default()
+                                    .fg(Color::DarkGray)
+                                    .add_modifier(Modifier::BOLD),
+                            ))
+                            .into();
+                        }
+                        ModeRow::Mode(entry) => entry,
+                    };
+                    let mode = &m.modes[entry.index];
+                    let marker = if entry.is_current { "▸ " } else { "  " };
+                    let preferred = if entry.preferred { " ★" } else { "" };
+                    let mut style = if entry.is_current {
                         Style::default().fg(Color::Cyan)
                     } else {
                         Style::default().fg(Color::White)
                     };
+                    if entry.preferred {
+                        style = style.add_modifier(Modifier::BOLD);
+                    }
+                    let label = if app.group_modes {
+                        format!("  {} Hz", mode.refresh_rate)
+                    } else {
+                        format!(
+                            "{}x{}@{}",
+                            mode.resolution.width,
+                            mode.resolution.height,
+                            mode.refresh_rate,
+                        )
+                    };
 
                     Line::from(vec![
                         Span::styled(marker, style),
+                        Span::styled(label, style),
                         Span::styled(
-                            format!(
-                                "{}x{}@{}",
-                                mode.resolution.width, mode.resolution.height, mode.refresh_rate,
-                            ),
-                            style,
+                            preferred,
+                            Style::default().fg(Color::Yellow),
                         ),
-                        Span::styled(preferred, Style::default().fg(Color::Yellow)),
                     ])
                     .into()
                 })
@@ -68,15 +92,60 @@ pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(border_color))
         .title(title);
+    let block = if app.show_raw_modes {
+        block.title_bottom(Line::from(Span::styled(
+            " raw modes ",
+            Style::default().fg(Color::Yellow),
+        )))
+    } else {
+        block
+    };
+
+    let header = monitor.as_ref().and_then(|m| {
+        let (w, h) = monitor_resolution(m);
+        let (min, max) = refresh_range(m)?;
+        let range = if min == max {
+            format!("{} Hz", max)
+        } else {
+            format!("{}–{} Hz", min, max)
+        };
+        Some(Line::from(vec![
+            Span::styled(
+                format!("  {}×{} ", w, h),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(range, Style::default().fg(Color::White)),
+        ]))
+    });
 
-    let list = List::new(items)
-        .block(block)
-        .highlight_symbol(" › ")
-        .highlight_style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let [header_area, list_area] = Layout::vertical([
+        Constraint::Length(header.is_some() as u16),
+        Constraint::Min(0),
+    ])
+    .areas(inner);
+
+    if let Some(header) = header {
+        frame.render_widget(Paragraph::new(header), header_area);
+    }
+    app.mode_list_height = list_area.height as usize;
+    if monitor.as_ref().is_some_and(|m| m.modes.is_empty()) {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "  No modes reported",
+                Style::default().fg(Color::DarkGray),
+            )),
+            list_area,
         );
+        return;
+    }
+
+    let list = List::new(items).highlight_symbol(" › ").highlight_style(
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
 
-    frame.render_stateful_widget(list, area, &mut app.mode_state);
+    frame.render_stateful_widget(list, list_area, &mut app.mode_state);
 }
@@ -0,0 +1,42 @@
+use crate::{state::App, utils::monitor_color};
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+/// One-line list of every monitor with its on/off state, shown above all
+/// panels so toggling and switching monitors have a visible target
+/// whatever has focus.
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let mut spans = vec![Span::styled(
+        " Monitors ",
+        Style::default().fg(Color::DarkGray),
+    )];
+
+    for (idx, monitor) in app.monitors.iter().enumerate() {
+        let selected = idx == app.selected_monitor;
+        let mut name_style = Style::default().fg(monitor_color(idx));
+        if selected {
+            name_style = name_style
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::REVERSED);
+        }
+        let (badge, badge_color) = if monitor.enabled {
+            ("ON", Color::Green)
+        } else {
+            ("OFF", Color::Red)
+        };
+
+        spans.push(Span::styled(format!(" {} ", monitor.name), name_style));
+        spans.push(Span::styled(
+            format!("{} ", badge),
+            Style::default().fg(badge_color),
+        ));
+    }
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
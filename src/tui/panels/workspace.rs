@@ -1,17 +1,18 @@
 use crate::{
     state::{App, Panel},
-    tui::key_binds::get_workspaces_keybinds,
+    tui::{key_binds::get_workspaces_keybinds, keymap::Keymap},
+    utils::monitor_color,
 };
 
 use ratatui::{
+    Frame,
     layout::Rect,
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, List, ListItem},
-    Frame,
 };
 
-pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
+pub fn panel(frame: &mut Frame, app: &mut App, keymap: &Keymap, area: Rect) {
     let focused = app.panel == Panel::Workspace;
     let border_color = if focused {
         Color::Blue
@@ -22,7 +23,7 @@ pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
     let title = if focused {
         let mut keys = Vec::new();
         keys.push(Span::styled(" Wkspc ", Style::default().fg(Color::Blue)));
-        get_workspaces_keybinds(&mut keys, app.compositor);
+        get_workspaces_keybinds(&mut keys, keymap, app.compositor);
         Line::from(keys)
     } else {
         Line::from(Span::styled(
@@ -37,9 +38,10 @@ pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
     } else {
         Color::DarkGray
     };
-    let supports_defaults = app.compositor.supports_workspace_defaults();
+    let capabilities = app.compositor.capabilities();
     let monitors = app.monitors.clone();
-    let pending_keys: Vec<usize> = app.pending_workspaces.keys().copied().collect();
+    let pending_keys: Vec<usize> =
+        app.pending_workspaces.keys().copied().collect();
 
     let items: Vec<ListItem> = app
         .workspace_assignments
@@ -55,12 +57,11 @@ pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
                 .map(|m| m.name.as_str())
                 .unwrap_or("unassigned");
 
-            let is_assigned = effective.monitor_idx.is_some();
             let is_pending = pending_keys.contains(&idx);
             let name_style = if is_pending {
                 Style::default().fg(Color::Yellow)
-            } else if is_assigned {
-                Style::default().fg(Color::Cyan)
+            } else if let Some(i) = effective.monitor_idx {
+                Style::default().fg(monitor_color(i))
             } else {
                 Style::default().fg(Color::DarkGray)
             };
@@ -74,27 +75,55 @@ pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
                 Span::styled(monitor_name, name_style),
             ];
 
-            if effective.is_default && supports_defaults {
-                spans.push(Span::styled(" [D]", Style::default().fg(Color::Green)));
+            if effective.is_default && capabilities.workspace_defaults() {
+                spans.push(Span::styled(
+                    " [D]",
+                    Style::default().fg(Color::Green),
+                ));
             }
-            if effective.is_persistent && supports_defaults {
-                spans.push(Span::styled(" [P]", Style::default().fg(Color::Yellow)));
+            if effective.is_persistent && capabilities.persistent_workspaces() {
+                spans.push(Span::styled(
+                    " [P]",
+                    Style::default().fg(Color::Yellow),
+                ));
             }
 
             if is_pending {
-                spans.push(Span::styled(" *", Style::default().fg(Color::Yellow)));
+                spans.push(Span::styled(
+                    " *",
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+
+            if let Some(live) = app.live_workspaces.get(&effective.id) {
+                spans.push(Span::styled(
+                    format!("  now on {}", live),
+                    Style::default().fg(Color::DarkGray),
+                ));
             }
 
             Line::from(spans).into()
         })
         .collect();
 
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(border_color))
         .title(title);
 
+    if !capabilities.workspace_defaults()
+        || !capabilities.persistent_workspaces()
+    {
+        block = block.title_bottom(Line::from(Span::styled(
+            format!(
+                " default/persistent not supported on {} ",
+                app.compositor.label()
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
     let list = List::new(items)
         .block(block)
         .highlight_symbol(" \u{203a} ")
@@ -104,5 +133,6 @@ pub fn panel(frame: &mut Frame, app: &mut App, area: Rect) {
                 .add_modifier(Modifier::BOLD),
         );
 
+    app.workspace_list_height = area.height.saturating_sub(2) as usize;
     frame.render_stateful_widget(list, area, &mut app.workspace_state);
 }
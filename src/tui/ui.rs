@@ -1,13 +1,16 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::SendError;
 use std::{io, sync::mpsc::Receiver, time::Duration};
 
-use crossterm::event::{self, Event, KeyCode};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{DefaultTerminal, Terminal, backend::CrosstermBackend};
 use thiserror::Error;
 use wlx_monitors::WlMonitorEvent;
 
-use crate::state::{App, Panel};
+use crate::state::{App, ListJump, Panel, PositionDirection};
+use crate::tui::keymap::{Action, Keymap, SLOT_STORE_KEYS};
 use crate::tui::layout;
+use crate::utils;
 
 #[derive(Error, Debug)]
 pub enum TuiLoopError {
@@ -22,8 +25,16 @@ pub fn tui_loop(
     app: &mut App,
     wlx_events: Receiver<WlMonitorEvent>,
     terminal: &mut DefaultTerminal,
+    keymap: &Keymap,
+    shutdown: &AtomicBool,
 ) -> Result<(), TuiLoopError> {
     loop {
+        if shutdown.load(Ordering::Relaxed) {
+            app.reset_positions();
+            app.revert_change()?;
+            break;
+        }
+
         let mut had_events = false;
         while let Ok(event) = wlx_events.try_recv() {
             had_events = true;
@@ -37,98 +48,343 @@ pub fn tui_loop(
                 WlMonitorEvent::Removed { name, .. } => {
                     app.remove_monitor(&name);
                 }
-                WlMonitorEvent::ActionFailed { action: _, reason } => {
-                    app.needs_save = false;
-                    app.set_error(format!("Action failed: {}", reason));
+                WlMonitorEvent::ActionFailed { action, reason } => {
+                    app.quit_after_apply = false;
+                    app.abort_batch();
+                    app.set_error(format!(
+                        "Failed to {}: {}",
+                        utils::action_label(&action),
+                        reason
+                    ));
                 }
             }
         }
 
-        if had_events {
-            app.save_config();
+        app.expire_identify();
+        app.check_wallpapers();
+        if let Err(e) = app.expire_revert() {
+            app.set_error(format!("Failed to revert: {}", e));
+        }
+
+        app.save_when_settled(had_events);
+
+        if app.ready_to_quit() {
+            app.sync_draft();
+            break;
         }
 
-        render(terminal, app)?;
+        render(terminal, app, keymap)?;
+
+        let event = if event::poll(Duration::from_millis(50))? {
+            Some(event::read()?)
+        } else {
+            None
+        };
+
+        match event {
+            // Back from another window or from sleep: the monitors stream in
+            // live, but the config and descriptions may have changed meanwhile
+            Some(Event::FocusGained) => {
+                app.refresh_external_state();
+                terminal.clear()?;
+            }
+            Some(Event::Resize(..)) => terminal.clear()?,
+            _ => {}
+        }
 
-        if event::poll(Duration::from_millis(50))?
-            && let Event::Key(k) = event::read()?
-        {
+        if let Some(Event::Key(k)) = event {
             app.clear_error();
+            app.clear_status();
 
-            if app.pending_last_toggle_monitor {
+            if let Some(input) = app.custom_mode.as_mut() {
+                match k.code {
+                    KeyCode::Enter => app.submit_custom_mode(),
+                    KeyCode::Esc => app.cancel_custom_mode(),
+                    code => {
+                        input.handle_key(code);
+                    }
+                }
+            } else if let Some(input) = app.wallpaper_prompt.as_mut() {
+                match k.code {
+                    KeyCode::Enter => app.submit_wallpaper_prompt(),
+                    KeyCode::Esc => app.cancel_wallpaper_prompt(),
+                    code => {
+                        input.handle_key(code);
+                    }
+                }
+            } else if let Some(input) = app.toggle_prompt.as_mut() {
+                match k.code {
+                    KeyCode::Enter => {
+                        if let Err(e) = app.submit_toggle_by_name() {
+                            app.set_error(format!("Failed to toggle: {}", e));
+                        }
+                    }
+                    KeyCode::Esc => app.cancel_toggle_by_name(),
+                    code => {
+                        input.handle_key(code);
+                    }
+                }
+            } else if let Some(input) = app.search.as_mut() {
+                match k.code {
+                    KeyCode::Enter => app.submit_search(),
+                    KeyCode::Esc => app.cancel_search(),
+                    code => {
+                        input.handle_key(code);
+                    }
+                }
+            } else if app.show_version {
+                app.toggle_version();
+            } else if app.show_legend {
+                app.toggle_legend();
+            } else if app.pending_revert.is_some() {
+                match k.code {
+                    KeyCode::Char('y') => app.keep_change(),
+                    _ => {
+                        if let Err(e) = app.revert_change() {
+                            app.set_error(format!("Failed to revert: {}", e));
+                        }
+                    }
+                }
+            } else if app.pending_confirm.is_some() {
+                match k.code {
+                    KeyCode::Char('y') => {
+                        if let Err(e) = app.run_confirmed() {
+                            app.set_error(format!("Failed to apply: {}", e));
+                        }
+                    }
+                    _ => app.dismiss_confirm(),
+                }
+            } else if app.pending_last_toggle_monitor {
                 match k.code {
                     KeyCode::Char('y') => {
                         if let Err(e) = app.toggle_monitor() {
-                            app.set_error(format!("Failed to toggle monitor: {}", e));
+                            app.set_error(format!(
+                                "Failed to toggle monitor: {}",
+                                e
+                            ));
                         }
                     }
                     _ => app.dismiss_warning(),
                 }
+            } else if app.pending_enable.is_some() && k.code == KeyCode::Esc {
+                app.cancel_enable();
+            } else if !app.group.is_empty() && k.code == KeyCode::Esc {
+                app.clear_group();
+            } else if app.panel == Panel::Monitor
+                && k.modifiers.contains(KeyModifiers::SHIFT)
+                && let Some(direction) = pan_direction(k.code)
+            {
+                app.pan_map(direction);
             } else {
-                match k.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
+                let action = keymap.action(k.code);
+                if action.is_none()
+                    && let Some(slot) = slot_key(k)
+                {
+                    match slot {
+                        SlotKey::Store(n) => app.store_slot(n),
+                        SlotKey::Recall(n) => app.recall_slot(n),
+                    }
+                    app.count_prefix = None;
+                    continue;
+                }
+                if action.is_none()
+                    && let KeyCode::Char(c) = k.code
+                    && let Some(digit) = c.to_digit(10)
+                    && (digit != 0 || app.count_prefix.is_some())
+                {
+                    app.push_count_digit(digit);
+                    continue;
+                }
+                let Some(action) = action else {
+                    app.count_prefix = None;
+                    app.dismiss_risky_mode();
+                    continue;
+                };
+                if action != Action::Apply {
+                    app.dismiss_risky_mode();
+                }
+                match action {
+                    Action::Quit => {
                         app.reset_positions();
+                        app.sync_draft();
                         break;
                     }
-                    KeyCode::Up | KeyCode::Char('k') => app.previous(),
-                    KeyCode::Down | KeyCode::Char('j') => app.next(),
-                    KeyCode::Left | KeyCode::Char('h') => app.nav_left(),
-                    KeyCode::Right | KeyCode::Char('l') => app.nav_right(),
-                    KeyCode::Tab => app.toggle_panel(),
-                    KeyCode::Char('t') => {
+                    Action::MoveUp => app.previous(),
+                    Action::MoveDown => app.next(),
+                    Action::MoveLeft => app.nav_left(),
+                    Action::MoveRight => app.nav_right(),
+                    Action::SwitchPanel => app.toggle_panel(),
+                    Action::ToggleMonitor => {
                         if let Err(e) = app.toggle_monitor() {
-                            app.set_error(format!("Failed to toggle monitor: {}", e));
+                            app.set_error(format!(
+                                "Failed to toggle monitor: {}",
+                                e
+                            ));
                         }
                     }
-                    KeyCode::Char('r') => app.reset_positions(),
-                    KeyCode::Char(']') => app.select_next_monitor(),
-                    KeyCode::Char('[') => app.select_prev_monitor(),
-                    KeyCode::Char('+') => {
-                        if app.panel == Panel::Monitor {
-                            app.zoom_in();
-                        } else {
-                            app.scale_up();
+                    Action::SoloMonitor => app.request_solo_monitor(),
+                    Action::EnableAll => {
+                        if let Err(e) = app.enable_all_monitors() {
+                            app.set_error(format!(
+                                "Failed to toggle monitor: {}",
+                                e
+                            ));
                         }
                     }
-                    KeyCode::Char('-') => {
-                        if app.panel == Panel::Monitor {
-                            app.zoom_out();
-                        } else {
-                            app.scale_down();
-                        }
-                    }
-                    KeyCode::Char('d') => {
+                    Action::RotateAll => app.request_rotate_all(),
+                    Action::Reset => app.reset_positions(),
+                    Action::NextMonitor => app.select_next_monitor(),
+                    Action::PrevMonitor => app.select_prev_monitor(),
+                    Action::ZoomIn => match app.panel {
+                        Panel::Monitor => app.zoom_in(),
+                        Panel::Color => app.cooler(),
+                        _ => app.scale_up(),
+                    },
+                    Action::ZoomOut => match app.panel {
+                        Panel::Monitor => app.zoom_out(),
+                        Panel::Color => app.warmer(),
+                        _ => app.scale_down(),
+                    },
+                    Action::ToggleDefault => {
                         if app.panel == Panel::Workspace
-                            && app.compositor.supports_workspace_defaults()
+                            && app
+                                .compositor
+                                .capabilities()
+                                .workspace_defaults()
                         {
                             app.toggle_default();
                         }
                     }
-                    KeyCode::Char('p') => {
+                    Action::TogglePersistent => {
                         if app.panel == Panel::Workspace
-                            && app.compositor.supports_workspace_defaults()
+                            && app
+                                .compositor
+                                .capabilities()
+                                .persistent_workspaces()
                         {
                             app.toggle_persistent();
                         }
                     }
-                    KeyCode::Enter => {
+                    Action::Search => {
+                        if app.panel == Panel::Monitor {
+                            app.start_search();
+                        }
+                    }
+                    Action::PreferredMode => {
+                        if let Err(e) = app.apply_preferred_mode() {
+                            app.set_error(format!("Failed to apply: {}", e));
+                        }
+                    }
+                    Action::MaxRefresh => {
+                        if let Err(e) = app.apply_max_refresh() {
+                            app.set_error(format!("Failed to apply: {}", e));
+                        }
+                    }
+                    Action::ReapplyConfig => app.reapply_config(),
+                    Action::WriteLiveConfig => app.write_live_config(),
+                    Action::DismissDrift => app.dismiss_drift(),
+                    Action::TogglePortrait => {
+                        if let Err(e) = app.toggle_portrait() {
+                            app.set_error(format!("Failed to apply: {}", e));
+                        }
+                    }
+                    Action::PageUp => app.jump_list(ListJump::PageUp),
+                    Action::PageDown => app.jump_list(ListJump::PageDown),
+                    Action::FirstItem => app.jump_list(ListJump::First),
+                    Action::LastItem => app.jump_list(ListJump::Last),
+                    Action::CycleBitDepth => app.cycle_render_bit_depth(),
+                    Action::CycleRenderTime => app.cycle_max_render_time(),
+                    Action::RevertToFile => app.request_revert_to_file(),
+                    Action::StackVertical => app.auto_arrange_vertical(),
+                    Action::ToggleRawModes => app.toggle_raw_modes(),
+                    Action::ToggleModeGrouping => app.toggle_mode_grouping(),
+                    Action::ToggleRelativePositions => {
+                        app.toggle_relative_positions()
+                    }
+                    Action::ToggleTableView => app.toggle_table_view(),
+                    Action::SaveConfig => app.save_now(),
+                    Action::CustomMode => app.start_custom_mode(),
+                    Action::ShowVersion => app.toggle_version(),
+                    Action::ToggleByName => app.start_toggle_by_name(),
+                    Action::ToggleWorkspaceBadges => {
+                        app.toggle_workspace_badges()
+                    }
+                    Action::ToggleLock => app.toggle_lock(),
+                    Action::ToggleGroup => app.toggle_group_member(),
+                    Action::Identify => app.start_identify(),
+                    Action::ToggleBasicColors => app.toggle_basic_colors(),
+                    Action::ToggleFooter => app.toggle_footer(),
+                    Action::ShowLegend => app.toggle_legend(),
+                    Action::SetWallpaper => app.start_wallpaper_prompt(),
+                    Action::HopLeft => app.hop_monitor(PositionDirection::Left),
+                    Action::HopRight => {
+                        app.hop_monitor(PositionDirection::Right)
+                    }
+                    Action::HopUp => app.hop_monitor(PositionDirection::Up),
+                    Action::HopDown => app.hop_monitor(PositionDirection::Down),
+                    Action::ApplyAndQuit => {
+                        if let Err(e) = app.apply_all_and_quit() {
+                            app.set_error(format!("Failed to apply: {}", e));
+                        }
+                    }
+                    Action::Apply => {
                         if let Err(e) = app.apply_action() {
                             app.set_error(format!("Failed to apply: {}", e));
                         }
                     }
-                    _ => {}
                 }
+                app.count_prefix = None;
             }
+            app.sync_draft();
         }
     }
 
+    app.stop_identify();
     Ok(())
 }
 
+enum SlotKey {
+    Store(u8),
+    Recall(u8),
+}
+
+/// Shift+1–9 stores an arrangement slot and Alt+1–9 recalls it; plain
+/// digits stay count prefixes. Without the kitty keyboard protocol,
+/// terminals send Shift+digit as the shifted symbol of a US layout, which
+/// the keymap keeps free. Keys the keymap binds come first.
+fn slot_key(k: KeyEvent) -> Option<SlotKey> {
+    let KeyCode::Char(c) = k.code else {
+        return None;
+    };
+    let slot = |d: u32| u8::try_from(d).ok().filter(|&d| (1..=9).contains(&d));
+    if let Some(pos) = SLOT_STORE_KEYS.find(c) {
+        return slot(pos as u32 + 1).map(SlotKey::Store);
+    }
+    let digit = slot(c.to_digit(10)?)?;
+    if k.modifiers.contains(KeyModifiers::SHIFT) {
+        Some(SlotKey::Store(digit))
+    } else if k.modifiers.contains(KeyModifiers::ALT) {
+        Some(SlotKey::Recall(digit))
+    } else {
+        None
+    }
+}
+
+fn pan_direction(code: KeyCode) -> Option<PositionDirection> {
+    match code {
+        KeyCode::Left => Some(PositionDirection::Left),
+        KeyCode::Right => Some(PositionDirection::Right),
+        KeyCode::Up => Some(PositionDirection::Up),
+        KeyCode::Down => Some(PositionDirection::Down),
+        _ => None,
+    }
+}
+
 pub fn render(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
+    keymap: &Keymap,
 ) -> io::Result<()> {
-    terminal.draw(|f| layout::draw(f, app))?;
+    terminal.draw(|f| layout::draw(f, app, keymap))?;
     Ok(())
 }
@@ -1,7 +1,15 @@
-use std::{env, io, path::PathBuf};
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+};
 
+use ratatui::style::Color;
 use thiserror::Error;
-use wlx_monitors::{WlMonitor, WlTransform};
+use wlx_monitors::{ActionKind, WlMonitor, WlTransform};
+
+use crate::constants::MONITOR_PALETTE;
 
 #[derive(Error, Debug)]
 pub enum UtilsError {
@@ -37,6 +45,66 @@ pub fn monitor_config_exists(path: &str) -> bool {
     path_buf.exists()
 }
 
+#[derive(Error, Debug)]
+pub enum WriteAccessError {
+    #[error("no permission to write {0}")]
+    PermissionDenied(PathBuf),
+
+    #[error("{0} is on a read-only file system")]
+    ReadOnly(PathBuf),
+
+    #[error("cannot write {path}: {source}")]
+    Other {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl WriteAccessError {
+    pub fn new(path: &Path, source: io::Error) -> Self {
+        let path = path.to_path_buf();
+        match source.kind() {
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied(path),
+            io::ErrorKind::ReadOnlyFilesystem => Self::ReadOnly(path),
+            _ => Self::Other { path, source },
+        }
+    }
+}
+
+/// Whether a config file that xwlm is about to write already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteTarget {
+    Existing,
+    /// Missing, but the nearest existing directory above it is writable.
+    Creatable,
+}
+
+/// Checks that `path` can be written without changing it. A missing file is
+/// checked by creating and removing a probe file in the nearest existing
+/// directory, where `create_dir_all` would start.
+pub fn check_writable(path: &Path) -> Result<WriteTarget, WriteAccessError> {
+    if path.exists() {
+        OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map_err(|e| WriteAccessError::new(path, e))?;
+        return Ok(WriteTarget::Existing);
+    }
+
+    let Some(dir) = path.ancestors().skip(1).find(|p| p.is_dir()) else {
+        return Ok(WriteTarget::Creatable);
+    };
+    let probe = dir.join(format!(".xwlm-write-check-{}", std::process::id()));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| WriteAccessError::new(dir, e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(WriteTarget::Creatable)
+}
+
 pub fn monitor_resolution(monitor: &WlMonitor) -> (i32, i32) {
     if let Some(mode) = monitor.modes.iter().find(|m| m.is_current) {
         return (mode.resolution.width, mode.resolution.height);
@@ -52,7 +120,136 @@ pub fn monitor_resolution(monitor: &WlMonitor) -> (i32, i32) {
 
 pub fn effective_dimensions(monitor: &WlMonitor) -> (i32, i32) {
     let (w, h) = monitor_resolution(monitor);
-    match monitor.transform {
+    transformed_dimensions(w, h, monitor.transform)
+}
+
+/// A mode listed in the Mode panel. `index` points into `WlMonitor::modes`;
+/// the flags cover every duplicate the entry stands for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModeEntry {
+    pub index: usize,
+    pub is_current: bool,
+    pub preferred: bool,
+}
+
+/// A visible row of the Mode panel. Headers only appear in the grouped view
+/// and can't be selected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModeRow {
+    Header(i32, i32),
+    Mode(ModeEntry),
+}
+
+impl ModeRow {
+    pub fn entry(self) -> Option<ModeEntry> {
+        match self {
+            ModeRow::Mode(entry) => Some(entry),
+            ModeRow::Header(..) => None,
+        }
+    }
+}
+
+/// The monitor's modes as listed in the Mode panel. Unless `raw` is set,
+/// modes reported more than once with the same resolution and refresh rate
+/// (drivers do this for different pixel clocks) collapse into one entry.
+pub fn mode_entries(monitor: &WlMonitor, raw: bool) -> Vec<ModeEntry> {
+    let mut entries: Vec<ModeEntry> = Vec::new();
+    for (index, mode) in monitor.modes.iter().enumerate() {
+        let duplicate = (!raw)
+            .then(|| {
+                entries.iter_mut().find(|entry| {
+                    let other = &monitor.modes[entry.index];
+                    other.resolution.width == mode.resolution.width
+                        && other.resolution.height == mode.resolution.height
+                        && other.refresh_rate == mode.refresh_rate
+                })
+            })
+            .flatten();
+        match duplicate {
+            Some(entry) => {
+                if mode.is_current {
+                    entry.index = index;
+                }
+                entry.is_current |= mode.is_current;
+                entry.preferred |= mode.preferred;
+            }
+            None => entries.push(ModeEntry {
+                index,
+                is_current: mode.is_current,
+                preferred: mode.preferred,
+            }),
+        }
+    }
+    entries
+}
+
+/// Rows of the Mode panel. `grouped` puts each resolution under a header,
+/// in the order the resolutions are first reported.
+pub fn mode_rows(
+    monitor: &WlMonitor,
+    raw: bool,
+    grouped: bool,
+) -> Vec<ModeRow> {
+    let entries = mode_entries(monitor, raw);
+    if !grouped {
+        return entries.into_iter().map(ModeRow::Mode).collect();
+    }
+
+    let resolution = |entry: &ModeEntry| {
+        let mode = &monitor.modes[entry.index];
+        (mode.resolution.width, mode.resolution.height)
+    };
+    let mut resolutions: Vec<(i32, i32)> = Vec::new();
+    for entry in &entries {
+        let res = resolution(entry);
+        if !resolutions.contains(&res) {
+            resolutions.push(res);
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (w, h) in resolutions {
+        rows.push(ModeRow::Header(w, h));
+        rows.extend(
+            entries
+                .iter()
+                .filter(|entry| resolution(entry) == (w, h))
+                .map(|&entry| ModeRow::Mode(entry)),
+        );
+    }
+    rows
+}
+
+/// Lowest and highest refresh rate available at the monitor's current
+/// resolution.
+pub fn refresh_range(monitor: &WlMonitor) -> Option<(i32, i32)> {
+    let (w, h) = monitor_resolution(monitor);
+    let rates = monitor
+        .modes
+        .iter()
+        .filter(|m| m.resolution.width == w && m.resolution.height == h)
+        .map(|m| m.refresh_rate);
+    let min = rates.clone().min()?;
+    let max = rates.max()?;
+    Some((min, max))
+}
+
+/// Size in logical pixels of a `w`×`h` output at `scale`.
+pub fn logical_size(w: i32, h: i32, scale: f64) -> (i32, i32) {
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+    (
+        (w as f64 / scale).round() as i32,
+        (h as f64 / scale).round() as i32,
+    )
+}
+
+/// Swaps width and height for transforms that rotate by 90 or 270 degrees.
+pub fn transformed_dimensions(
+    w: i32,
+    h: i32,
+    transform: WlTransform,
+) -> (i32, i32) {
+    match transform {
         WlTransform::Rotate90
         | WlTransform::Rotate270
         | WlTransform::Flipped90
@@ -73,3 +270,60 @@ pub fn transform_label(t: WlTransform) -> &'static str {
         WlTransform::Flipped270 => "Flipped 270",
     }
 }
+
+/// What a failed monitor action was trying to do, phrased to follow
+/// "Failed to".
+pub fn action_label(action: &ActionKind) -> &'static str {
+    match action {
+        ActionKind::Toggle => "toggle the monitor",
+        ActionKind::ConfigApply => "apply the output configuration",
+        ActionKind::SwitchMode => "switch the mode",
+        ActionKind::SetScale => "set the scale",
+        ActionKind::SetTransform => "set the transform",
+        ActionKind::SetPosition => "set the position",
+    }
+}
+
+/// The closest of the 16 basic terminal colors to an RGB color. Grays map
+/// to the gray shades so dim text stays visible on a dark background.
+pub fn basic_color(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    if max - min < 32 {
+        return match max {
+            0..=31 => Color::Black,
+            32..=159 => Color::DarkGray,
+            160..=223 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+    const PALETTE: [(Color, (u8, u8, u8)); 12] = [
+        (Color::Red, (205, 0, 0)),
+        (Color::Green, (0, 205, 0)),
+        (Color::Yellow, (205, 205, 0)),
+        (Color::Blue, (0, 0, 238)),
+        (Color::Magenta, (205, 0, 205)),
+        (Color::Cyan, (0, 205, 205)),
+        (Color::LightRed, (255, 85, 85)),
+        (Color::LightGreen, (85, 255, 85)),
+        (Color::LightYellow, (255, 255, 85)),
+        (Color::LightBlue, (92, 92, 255)),
+        (Color::LightMagenta, (255, 85, 255)),
+        (Color::LightCyan, (85, 255, 255)),
+    ];
+    let distance = |(pr, pg, pb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, pr) + d(g, pg) + d(b, pb)
+    };
+    PALETTE
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(c, _)| *c)
+        .unwrap_or(color)
+}
+
+pub fn monitor_color(idx: usize) -> Color {
+    MONITOR_PALETTE[idx % MONITOR_PALETTE.len()]
+}
@@ -1,9 +1,13 @@
-use serde::Deserialize;
 use serde::Serialize;
-use std::{fs, io, path::PathBuf};
+use serde::{Deserialize, Deserializer};
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
 use thiserror::Error;
 
-use crate::utils;
+use crate::{
+    compositor::Compositor,
+    constants::{MIN_COLOR_TEMP, NEUTRAL_COLOR_TEMP},
+    utils,
+};
 
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -27,18 +31,196 @@ pub enum ConfigError {
     #[error("invalid toml in config: {0}")]
     Parse(#[from] toml::de::Error),
 
+    #[error("invalid config at {path}: {source}")]
+    Invalid {
+        path: String,
+        #[source]
+        source: toml::de::Error,
+    },
+
     #[error("io error: {0}")]
     Io(#[from] io::Error),
 
     #[error("failed to serialize config: {0}")]
     Serialize(#[from] toml::ser::Error),
+
+    #[error("{key} must be greater than zero, got {value}")]
+    NotPositive { key: &'static str, value: String },
+
+    #[error("min_scale ({min}) must not be greater than max_scale ({max})")]
+    ScaleRange { min: f64, max: f64 },
+
+    #[error(
+        "color_temperature for {monitor} must be between {}K and {}K, got {kelvin}K",
+        MIN_COLOR_TEMP,
+        NEUTRAL_COLOR_TEMP
+    )]
+    ColorTemp { monitor: String, kelvin: u32 },
+}
+
+impl ConfigError {
+    /// Whether the config file doesn't exist yet, as opposed to existing
+    /// but failing to load.
+    pub fn is_missing(&self) -> bool {
+        matches!(self, ConfigError::Read { source, .. } if source.kind() == io::ErrorKind::NotFound)
+    }
 }
 
+/// Schema version written to new configs. Bump it and add a step to
+/// `migrate` whenever a key is renamed or its meaning changes.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Comment written at the top of the files xwlm writes, unless the config
+/// sets its own.
+pub const DEFAULT_HEADER: &str =
+    "This file is managed by xwlm. Do not edit manually.";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    #[serde(default)]
+    pub version: u32,
     pub monitor_config_path: PathBuf,
-    #[serde(default = "default_workspace_count")]
-    pub workspace_count: usize,
+    /// Overrides the compositor's default number of workspaces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_count: Option<usize>,
+    /// Overrides the compositor's lower scale limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_scale: Option<f64>,
+    /// Overrides the compositor's upper scale limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_scale: Option<f64>,
+    /// Pixels a monitor moves per key press before key-repeat speeds it up.
+    #[serde(default = "default_position_step")]
+    pub position_step: i32,
+    /// Amount the Scale panel changes the scale per key press.
+    #[serde(default = "default_scale_step")]
+    pub scale_step: f64,
+    /// Write Hyprland monitors as `desc:Make Model Serial` instead of by
+    /// connector, so identical or reshuffled monitors keep their layout.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub match_by_description: bool,
+    /// Undo mode, scale and transform changes unless they are kept within
+    /// a few seconds, in case the screen went dark.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub safe_apply: bool,
+    /// Write the monitor config whenever a change is applied. When off,
+    /// changes only go to the live compositor until saved explicitly.
+    #[serde(default = "default_autosave", skip_serializing_if = "is_true")]
+    pub autosave: bool,
+    /// Moving past the end of the Mode, Transform or Workspace list jumps
+    /// to the other end. When off, the selection stops at the boundary.
+    #[serde(default = "default_wrap_lists", skip_serializing_if = "is_true")]
+    pub wrap_lists: bool,
+    /// Draw with the basic 16 terminal colors instead of RGB, for terminals
+    /// that claim truecolor support without honoring it.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub basic_colors: bool,
+    /// Show positions as offsets from this monitor instead of absolute
+    /// coordinates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_to: Option<String>,
+    /// Color temperature in Kelvin per monitor, restored on startup.
+    /// Monitors that aren't listed stay neutral.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub color_temperature: BTreeMap<String, u32>,
+    /// Run after each save for every enabled monitor with a wallpaper,
+    /// e.g. `swaybg -o {name} -i {image}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallpaper_command: Option<String>,
+    /// Wallpaper image per monitor, set from the map with `I`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub wallpapers: BTreeMap<String, String>,
+    /// Comment written at the top of the monitor config and this file.
+    /// An empty string leaves it out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    /// Maps action names (e.g. `move_up`, `apply`) to key specs. Actions
+    /// that are not listed keep their default keys. Each action takes one
+    /// key or a list of them.
+    #[serde(
+        default,
+        deserialize_with = "one_or_more_keys",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub keybinds: BTreeMap<String, Vec<String>>,
+}
+
+impl Config {
+    pub fn new(monitor_config_path: PathBuf) -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            monitor_config_path,
+            workspace_count: None,
+            min_scale: None,
+            max_scale: None,
+            position_step: default_position_step(),
+            scale_step: default_scale_step(),
+            match_by_description: false,
+            safe_apply: false,
+            autosave: default_autosave(),
+            wrap_lists: default_wrap_lists(),
+            basic_colors: false,
+            relative_to: None,
+            color_temperature: BTreeMap::new(),
+            wallpaper_command: None,
+            wallpapers: BTreeMap::new(),
+            header: None,
+            keybinds: BTreeMap::new(),
+        }
+    }
+
+    pub fn header(&self) -> &str {
+        self.header.as_deref().unwrap_or(DEFAULT_HEADER)
+    }
+
+    pub fn workspace_count(&self, compositor: Compositor) -> usize {
+        self.workspace_count
+            .unwrap_or_else(|| compositor.default_workspace_count())
+    }
+
+    /// Rejects values that would leave the TUI unable to move or scale.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.position_step <= 0 {
+            return Err(ConfigError::NotPositive {
+                key: "position_step",
+                value: self.position_step.to_string(),
+            });
+        }
+        if !(self.scale_step > 0.0 && self.scale_step.is_finite()) {
+            return Err(ConfigError::NotPositive {
+                key: "scale_step",
+                value: self.scale_step.to_string(),
+            });
+        }
+        for (key, bound) in
+            [("min_scale", self.min_scale), ("max_scale", self.max_scale)]
+        {
+            if let Some(bound) = bound
+                && !(bound > 0.0 && bound.is_finite())
+            {
+                return Err(ConfigError::NotPositive {
+                    key,
+                    value: bound.to_string(),
+                });
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_scale, self.max_scale)
+            && min > max
+        {
+            return Err(ConfigError::ScaleRange { min, max });
+        }
+        if let Some((monitor, &kelvin)) =
+            self.color_temperature.iter().find(|&(_, &k)| {
+                !(MIN_COLOR_TEMP..=NEUTRAL_COLOR_TEMP).contains(&k)
+            })
+        {
+            return Err(ConfigError::ColorTemp {
+                monitor: monitor.clone(),
+                kelvin,
+            });
+        }
+        Ok(())
+    }
 }
 
 pub fn load_config() -> Result<Config, ConfigError> {
@@ -49,6 +231,114 @@ pub fn save_config(config: &Config) -> Result<(), ConfigError> {
     save_to_path("~/.config/xwlm/config.toml", config)
 }
 
+/// Sets one key in config.toml and leaves every other line, comment and
+/// the order alone. `table` is the `[table]` holding the key, `None` for a
+/// top-level one. A `None` value removes the key.
+pub fn patch_config(
+    table: Option<&str>,
+    key: &str,
+    value: Option<toml::Value>,
+) -> Result<(), ConfigError> {
+    patch_path("~/.config/xwlm/config.toml", table, key, value)
+}
+
+fn patch_path(
+    path: &str,
+    table: Option<&str>,
+    key: &str,
+    value: Option<toml::Value>,
+) -> Result<(), ConfigError> {
+    let expanded_path = utils::expand_tilde(path)?;
+    let content =
+        fs::read_to_string(&expanded_path).map_err(|e| ConfigError::Read {
+            path: path.to_string(),
+            source: e,
+        })?;
+    let patched = patch_toml(&content, table, key, value.as_ref());
+    // Refuse to write a file that no longer parses, e.g. when the table
+    // was written inline
+    toml::from_str::<toml::Table>(&patched)?;
+    fs::write(&expanded_path, patched).map_err(|e| ConfigError::Write {
+        path: expanded_path.to_string_lossy().into(),
+        source: e,
+    })
+}
+
+/// `content` with `key` in `table` set to `value`, or removed when it's
+/// `None`. Only the key's own line changes; a new key goes at the end of
+/// its table, and a missing table is added at the end of the file.
+fn patch_toml(
+    content: &str,
+    table: Option<&str>,
+    key: &str,
+    value: Option<&toml::Value>,
+) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    let quoted = toml::Value::String(key.to_string()).to_string();
+    let written_key = if bare { key } else { &quoted };
+    let entry = value.map(|value| format!("{written_key} = {value}"));
+
+    let header = |line: &str| {
+        let line = line.trim();
+        (line.starts_with('[') && !line.starts_with("[["))
+            .then(|| line.trim_matches(['[', ']']).trim().to_string())
+    };
+    let is_key = |line: &str| {
+        let line = line.trim_start();
+        [key, quoted.as_str()].iter().any(|k| {
+            line.strip_prefix(k)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+    };
+
+    // The lines of the wanted table, header excluded
+    let mut start = table.is_none().then_some(0);
+    let mut end = lines.len();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(name) = header(line) {
+            if start.is_some() {
+                end = i;
+                break;
+            }
+            if table == Some(name.as_str()) {
+                start = Some(i + 1);
+            }
+        }
+    }
+
+    let Some(start) = start else {
+        if let Some(entry) = entry {
+            while lines.last().is_some_and(|l| l.trim().is_empty()) {
+                lines.pop();
+            }
+            lines.push(String::new());
+            lines.push(format!("[{}]", table.unwrap_or_default()));
+            lines.push(entry);
+        }
+        return lines.join("\n") + "\n";
+    };
+
+    match (lines[start..end].iter().position(|l| is_key(l)), entry) {
+        (Some(at), Some(entry)) => lines[start + at] = entry,
+        (Some(at), None) => {
+            lines.remove(start + at);
+        }
+        (None, Some(entry)) => {
+            let at = lines[start..end]
+                .iter()
+                .rposition(|l| !l.trim().is_empty())
+                .map_or(start, |last| start + last + 1);
+            lines.insert(at, entry);
+        }
+        (None, None) => {}
+    }
+    lines.join("\n") + "\n"
+}
+
 fn load_from_path(path: &str) -> Result<Config, ConfigError> {
     let expanded_path = utils::expand_tilde(path)?;
     let file_content =
@@ -57,11 +347,85 @@ fn load_from_path(path: &str) -> Result<Config, ConfigError> {
             source: e,
         })?;
 
-    let config = toml::from_str(&file_content)?;
+    let invalid = |source| ConfigError::Invalid {
+        path: path.to_string(),
+        source,
+    };
+    let migrated = migrate(&file_content).map_err(invalid)?;
+    let config: Config =
+        toml::from_str(migrated.as_deref().unwrap_or(&file_content))
+            .map_err(invalid)?;
+
+    if let Some(migrated) = migrated {
+        let expanded_path = utils::expand_tilde(path)?;
+        fs::write(&expanded_path, migrated).map_err(|e| {
+            ConfigError::Write {
+                path: expanded_path.to_string_lossy().into(),
+                source: e,
+            }
+        })?;
+    }
 
     Ok(config)
 }
 
+/// Upgrades config.toml written by an older release to the current schema
+/// with line edits, so the user's comments and key order survive. `None`
+/// when it is already current.
+fn migrate(content: &str) -> Result<Option<String>, toml::de::Error> {
+    let table: toml::Table = toml::from_str(content)?;
+    let version = table
+        .get("version")
+        .and_then(toml::Value::as_integer)
+        .unwrap_or(0);
+    if version >= CONFIG_VERSION as i64 {
+        return Ok(None);
+    }
+
+    let mut content = content.to_string();
+    // v0 (before 0.2.0) used the `comp_` prefixed key names.
+    if version < 1 {
+        for (old, new) in [
+            ("comp_monitors_config_path", "monitor_config_path"),
+            ("comp_workspace_count", "workspace_count"),
+        ] {
+            if !table.contains_key(old) {
+                continue;
+            }
+            content = if table.contains_key(new) {
+                patch_toml(&content, None, old, None)
+            } else {
+                rename_toml_key(&content, old, new)
+            };
+        }
+    }
+
+    let version = toml::Value::Integer(CONFIG_VERSION as i64);
+    Ok(Some(patch_toml(&content, None, "version", Some(&version))))
+}
+
+/// `content` with the top-level key `old` renamed to `new` where it
+/// stands, keeping its value and any comment on the line.
+fn rename_toml_key(content: &str, old: &str, new: &str) -> String {
+    let mut top_level = true;
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        top_level &= !trimmed.starts_with('[');
+        let rest = trimmed
+            .strip_prefix(old)
+            .filter(|rest| rest.trim_start().starts_with('='));
+        match rest {
+            Some(rest) if top_level => {
+                let indent = &line[..line.len() - trimmed.len()];
+                lines.push(format!("{indent}{new}{rest}"));
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
 fn save_to_path(path: &str, config: &Config) -> Result<(), ConfigError> {
     let expanded_path = utils::expand_tilde(path)?;
 
@@ -72,9 +436,10 @@ fn save_to_path(path: &str, config: &Config) -> Result<(), ConfigError> {
         })?;
     }
 
-    let comment = "# This file is managed by xwlm. Do not edit manually.\n# The monitor_config_path should always point to a file that ONLY contains\n# monitor and workspace configurations. Any other settings in that file will be\n# overwritten when xwlm saves changes.\n\n";
+    let comment = "# The monitor_config_path should always point to a file that ONLY contains\n# monitor and workspace configurations. Any other settings in that file will be\n# overwritten when xwlm saves changes.\n\n";
     let toml_string = toml::to_string_pretty(config)?;
-    let final_content = format!("{}{}", comment, toml_string);
+    let final_content =
+        with_header(config.header(), &format!("{}{}", comment, toml_string));
 
     fs::write(&expanded_path, final_content).map_err(|e| {
         ConfigError::Write {
@@ -86,8 +451,80 @@ fn save_to_path(path: &str, config: &Config) -> Result<(), ConfigError> {
     Ok(())
 }
 
-fn default_workspace_count() -> usize {
-    10
+/// Puts `header` above `content` as comment lines, after a shebang if
+/// `content` starts with one. An empty header leaves `content` unchanged.
+pub fn with_header(header: &str, content: &str) -> String {
+    if header.trim().is_empty() {
+        return content.to_string();
+    }
+    let comment: String = header
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with('#') {
+                format!("{}\n", line)
+            } else if line.trim().is_empty() {
+                "#\n".to_string()
+            } else {
+                format!("# {}\n", line)
+            }
+        })
+        .collect();
+
+    match content.strip_prefix("#!") {
+        Some(_) => {
+            let (shebang, rest) =
+                content.split_once('\n').unwrap_or((content, ""));
+            format!("{}\n{}\n{}", shebang, comment, rest)
+        }
+        None => format!("{}\n{}", comment, content),
+    }
+}
+
+fn one_or_more_keys<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Keys {
+        One(String),
+        More(Vec<String>),
+    }
+
+    let keybinds = BTreeMap::<String, Keys>::deserialize(deserializer)?;
+    Ok(keybinds
+        .into_iter()
+        .map(|(action, keys)| match keys {
+            Keys::One(key) => (action, vec![key]),
+            Keys::More(keys) => (action, keys),
+        })
+        .collect())
+}
+
+fn default_position_step() -> i32 {
+    1
+}
+
+fn default_scale_step() -> f64 {
+    0.01
+}
+
+fn default_autosave() -> bool {
+    true
+}
+
+fn default_wrap_lists() -> bool {
+    true
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
 }
 
 #[cfg(test)]
@@ -100,8 +537,12 @@ mod tests {
     #[test]
     fn save_then_load_config_works() {
         let config = Config {
-            monitor_config_path: PathBuf::from("/tmp/test.conf"),
-            workspace_count: 5,
+            workspace_count: Some(5),
+            keybinds: BTreeMap::from([(
+                "quit".to_string(),
+                vec!["x".to_string()],
+            )]),
+            ..Config::new(PathBuf::from("/tmp/test.conf"))
         };
 
         save_to_path(TEST_PATH, &config).unwrap();
@@ -111,6 +552,161 @@ mod tests {
         assert_eq!(loaded.workspace_count, config.workspace_count);
 
         assert_eq!(loaded.monitor_config_path, config.monitor_config_path);
+
+        assert_eq!(loaded.keybinds, config.keybinds);
+    }
+
+    #[test]
+    fn load_migrates_v0_config() {
+        let path = "~/.config/test-xwlm/v0.toml";
+        let expanded = utils::expand_tilde(path).unwrap();
+        if let Some(parent) = expanded.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(
+            &expanded,
+            "\
+# where my monitors live
+comp_monitors_config_path = \"/tmp/old.conf\"
+comp_workspace_count = 4 # one per desk
+
+[wallpapers]
+eDP-1 = \"~/a.png\"
+",
+        )
+        .unwrap();
+
+        let loaded = load_from_path(path).unwrap();
+
+        assert_eq!(loaded.version, CONFIG_VERSION);
+        assert_eq!(loaded.monitor_config_path, PathBuf::from("/tmp/old.conf"));
+        assert_eq!(loaded.workspace_count, Some(4));
+
+        let rewritten = std::fs::read_to_string(&expanded).unwrap();
+        assert_eq!(
+            rewritten,
+            "\
+# where my monitors live
+monitor_config_path = \"/tmp/old.conf\"
+workspace_count = 4 # one per desk
+version = 1
+
+[wallpapers]
+eDP-1 = \"~/a.png\"
+"
+        );
+    }
+
+    #[test]
+    fn migrate_leaves_current_config_alone() {
+        let content = "version = 1\nmonitor_config_path = \"/tmp/a\"\n";
+        assert_eq!(migrate(content).unwrap(), None);
+    }
+
+    #[test]
+    fn workspace_count_defaults_per_compositor() {
+        let config: Config =
+            toml::from_str("monitor_config_path = \"/tmp/a\"\n").unwrap();
+        assert_eq!(config.workspace_count, None);
+        assert_eq!(config.workspace_count(Compositor::Hyprland), 10);
+        assert_eq!(config.workspace_count(Compositor::River), 0);
+
+        let config = Config {
+            workspace_count: Some(4),
+            ..Config::new(PathBuf::from("/tmp/test.conf"))
+        };
+        assert_eq!(config.workspace_count(Compositor::River), 4);
+    }
+
+    #[test]
+    fn validate_rejects_non_positive_steps() {
+        let config = Config::new(PathBuf::from("/tmp/test.conf"));
+        assert!(config.validate().is_ok());
+
+        let config = Config {
+            scale_step: 0.0,
+            ..Config::new(PathBuf::from("/tmp/test.conf"))
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::NotPositive {
+                key: "scale_step",
+                ..
+            })
+        ));
+
+        let config = Config {
+            position_step: -5,
+            ..Config::new(PathBuf::from("/tmp/test.conf"))
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::NotPositive {
+                key: "position_step",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_bad_scale_bounds() {
+        let config = Config {
+            min_scale: Some(2.0),
+            max_scale: Some(1.0),
+            ..Config::new(PathBuf::from("/tmp/test.conf"))
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ScaleRange { .. })
+        ));
+
+        let config = Config {
+            max_scale: Some(f64::NAN),
+            ..Config::new(PathBuf::from("/tmp/test.conf"))
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::NotPositive {
+                key: "max_scale",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_color_temps() {
+        let mut config = Config::new(PathBuf::from("/tmp/test.conf"));
+        config.color_temperature.insert("DP-1".to_string(), 4500);
+        assert!(config.validate().is_ok());
+
+        config.color_temperature.insert("eDP-1".to_string(), 100);
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ColorTemp { kelvin: 100, .. })
+        ));
+    }
+
+    #[test]
+    fn with_header_comments_each_line() {
+        assert_eq!(
+            with_header(DEFAULT_HEADER, "monitor = DP-1\n"),
+            format!("# {}\n\nmonitor = DP-1\n", DEFAULT_HEADER)
+        );
+        assert_eq!(
+            with_header(
+                "Managed by xwlm\n# see ~/dotfiles",
+                "output DP-1 on\n"
+            ),
+            "# Managed by xwlm\n# see ~/dotfiles\n\noutput DP-1 on\n"
+        );
+        assert_eq!(with_header("", "monitor = DP-1\n"), "monitor = DP-1\n");
+        assert_eq!(
+            with_header(
+                "Managed",
+                "#!/bin/sh\nwlr-randr --output DP-1 --off\n"
+            ),
+            "#!/bin/sh\n# Managed\n\nwlr-randr --output DP-1 --off\n"
+        );
     }
 
     #[test]
@@ -119,7 +715,7 @@ mod tests {
 
         let result = load_from_path(path);
 
-        assert!(result.is_err());
+        assert!(result.is_err_and(|e| e.is_missing()));
     }
 
     #[test]
@@ -136,6 +732,76 @@ mod tests {
 
         let result = load_from_path(path);
 
-        assert!(matches!(result, Err(ConfigError::Parse(_))));
+        assert!(matches!(result, Err(ConfigError::Invalid { .. })));
+        assert!(!result.unwrap_err().is_missing());
+    }
+
+    #[test]
+    fn keybind_takes_one_key_or_a_list() {
+        let config: Config = toml::from_str(
+            "monitor_config_path = \"/tmp/a\"\n\n[keybinds]\nquit = \"x\"\napply = [\"Enter\", \"y\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.keybinds["quit"], ["x"]);
+        assert_eq!(config.keybinds["apply"], ["Enter", "y"]);
+    }
+
+    #[test]
+    fn patch_toml_changes_only_the_key() {
+        let content = "\
+# my settings
+autosave = true # keep
+basic_colors = false
+
+[wallpapers]
+# laptop
+eDP-1 = \"~/a.png\"
+";
+        let patched = patch_toml(
+            content,
+            None,
+            "basic_colors",
+            Some(&toml::Value::Boolean(true)),
+        );
+        assert_eq!(patched, content.replace("false", "true"));
+
+        let patched = patch_toml(
+            content,
+            Some("wallpapers"),
+            "DP 2",
+            Some(&toml::Value::String("~/b.png".into())),
+        );
+        assert!(
+            patched.ends_with("eDP-1 = \"~/a.png\"\n\"DP 2\" = \"~/b.png\"\n"),
+            "{patched}"
+        );
+
+        let patched = patch_toml(content, Some("wallpapers"), "eDP-1", None);
+        assert_eq!(patched, content.replace("eDP-1 = \"~/a.png\"\n", ""));
+
+        let patched = patch_toml(
+            content,
+            Some("color_temperature"),
+            "eDP-1",
+            Some(&toml::Value::Integer(5000)),
+        );
+        assert!(patched.starts_with(content), "{patched}");
+        assert!(
+            patched.ends_with("\n[color_temperature]\neDP-1 = 5000\n"),
+            "{patched}"
+        );
+
+        let patched = patch_toml(
+            content,
+            None,
+            "relative_to",
+            Some(&toml::Value::String("DP-1".into())),
+        );
+        assert!(
+            patched.contains(
+                "basic_colors = false\nrelative_to = \"DP-1\"\n\n[wallpapers]"
+            ),
+            "{patched}"
+        );
     }
 }